@@ -0,0 +1,52 @@
+//! The "Keyboard shortcuts" window and the menu bar accelerators.
+
+use crate::keybindings::{Action, Keybindings};
+use eframe::egui::{Context, Grid, Key, Modifiers, Ui};
+
+/// Alt plus the key focuses the menu, Enter or Space then opens it.
+pub const MENU_ACCELERATORS: [(Key, &str); 2] = [(Key::F, "File"), (Key::H, "Help")];
+
+/// Whether the accelerator of `menu` was pressed this frame, consuming the press.
+pub fn accelerator_pressed(ctx: &Context, menu: &str) -> bool {
+    MENU_ACCELERATORS
+        .iter()
+        .find(|(_, name)| *name == menu)
+        .is_some_and(|(key, _)| ctx.input_mut(|i| i.consume_key(Modifiers::ALT, *key)))
+}
+
+/// Lists every keyboard shortcut, including the ones egui provides for focused widgets.
+/// Rebindable actions show their current binding.
+pub fn shortcuts_ui(ui: &mut Ui, bindings: &Keybindings) {
+    let actions = Action::ALL.map(|a| {
+        (
+            ui.ctx().format_shortcut(&bindings.get(a)),
+            a.title().to_string(),
+        )
+    });
+    let accelerators = MENU_ACCELERATORS.iter().map(|(key, menu)| {
+        (
+            format!("Alt+{}", key.name()),
+            format!("Focus the {} menu", menu),
+        )
+    });
+    let focus = [
+        (
+            "Tab / Shift+Tab",
+            "Move the focus to the next / previous control",
+        ),
+        ("Enter / Space", "Activate the focused control"),
+        (
+            "Up / Down",
+            "Select the previous / next Pokémon in a focused list",
+        ),
+    ]
+    .map(|(keys, action)| (keys.to_string(), action.to_string()));
+
+    Grid::new("shortcuts").striped(true).show(ui, |ui| {
+        for (keys, action) in actions.into_iter().chain(accelerators).chain(focus) {
+            ui.strong(keys);
+            ui.label(action);
+            ui.end_row();
+        }
+    });
+}
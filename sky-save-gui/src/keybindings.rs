@@ -0,0 +1,276 @@
+//! Rebindable keyboard shortcuts, stored in the platform data directory.
+//!
+//! The file has one `action = chord` line per action, e.g. `save_as = Command+Shift+S`.
+//! `Command` is Ctrl on Windows and Linux and ⌘ on macOS, so the defaults fit every platform.
+
+use crate::recovery::data_dir;
+use eframe::egui::{Context, Event, Key, KeyboardShortcut, Modifiers, RichText, Window};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Action {
+    Open,
+    SaveAs,
+    Quit,
+}
+
+impl Action {
+    pub const ALL: [Action; 3] = [Action::Open, Action::SaveAs, Action::Quit];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Action::Open => "Open",
+            Action::SaveAs => "Save As",
+            Action::Quit => "Quit",
+        }
+    }
+
+    /// The name of the action in the keybindings file.
+    fn id(&self) -> &'static str {
+        match self {
+            Action::Open => "open",
+            Action::SaveAs => "save_as",
+            Action::Quit => "quit",
+        }
+    }
+
+    fn default_shortcut(&self) -> KeyboardShortcut {
+        match self {
+            Action::Open => KeyboardShortcut::new(Modifiers::COMMAND, Key::O),
+            Action::SaveAs => KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::S),
+            Action::Quit => KeyboardShortcut::new(Modifiers::NONE, Key::Escape),
+        }
+    }
+}
+
+/// The shortcut of every action, indexed by `Action`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keybindings {
+    shortcuts: [KeyboardShortcut; Action::ALL.len()],
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self {
+            shortcuts: Action::ALL.map(|a| a.default_shortcut()),
+        }
+    }
+}
+
+impl Keybindings {
+    fn path() -> Option<PathBuf> {
+        data_dir().map(|d| d.join("keybindings.txt"))
+    }
+
+    /// Loads the saved keybindings. Missing or unreadable lines keep their defaults.
+    pub fn load() -> Self {
+        let mut bindings = Self::default();
+        let Some(text) = Self::path().and_then(|p| fs::read_to_string(p).ok()) else {
+            return bindings;
+        };
+
+        for line in text.lines() {
+            let Some((id, chord)) = line.split_once('=') else {
+                continue;
+            };
+            let action = Action::ALL.into_iter().find(|a| a.id() == id.trim());
+            if let (Some(action), Some(shortcut)) = (action, parse_chord(chord.trim())) {
+                bindings.set(action, shortcut);
+            }
+        }
+
+        bindings
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = Self::path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let text: String = Action::ALL
+            .iter()
+            .map(|a| format!("{} = {}\n", a.id(), format_chord(&self.get(*a))))
+            .collect();
+        fs::write(path, text)
+    }
+
+    pub fn get(&self, action: Action) -> KeyboardShortcut {
+        self.shortcuts[action as usize]
+    }
+
+    pub fn set(&mut self, action: Action, shortcut: KeyboardShortcut) {
+        self.shortcuts[action as usize] = shortcut;
+    }
+
+    /// The action other than `except` bound to `shortcut`, if any.
+    pub fn conflict(&self, shortcut: KeyboardShortcut, except: Action) -> Option<Action> {
+        Action::ALL
+            .into_iter()
+            .find(|&a| a != except && self.get(a) == shortcut)
+    }
+
+    /// Consumes the key presses of every bound action and returns the triggered actions.
+    pub fn triggered(&self, ctx: &Context) -> Vec<Action> {
+        ctx.input_mut(|i| {
+            Action::ALL
+                .into_iter()
+                .filter(|&a| i.consume_shortcut(&self.get(a)))
+                .collect()
+        })
+    }
+}
+
+const MODIFIER_NAMES: [(&str, Modifiers); 4] = [
+    ("Command", Modifiers::COMMAND),
+    ("Ctrl", Modifiers::CTRL),
+    ("Alt", Modifiers::ALT),
+    ("Shift", Modifiers::SHIFT),
+];
+
+/// Formats a chord for the keybindings file, unlike `Context::format_shortcut`
+/// the result is the same on every platform.
+fn format_chord(shortcut: &KeyboardShortcut) -> String {
+    MODIFIER_NAMES
+        .iter()
+        .filter(|(_, m)| shortcut.modifiers.contains(*m))
+        .map(|(name, _)| *name)
+        .chain([shortcut.logical_key.name()])
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+fn parse_chord(chord: &str) -> Option<KeyboardShortcut> {
+    let (modifiers, key) = chord.rsplit_once('+').unwrap_or(("", chord));
+    let modifiers =
+        modifiers
+            .split('+')
+            .filter(|m| !m.is_empty())
+            .try_fold(Modifiers::NONE, |acc, m| {
+                MODIFIER_NAMES
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(m.trim()))
+                    .map(|(_, modifier)| acc | *modifier)
+            })?;
+
+    Some(KeyboardShortcut::new(
+        modifiers,
+        Key::from_name(key.trim())?,
+    ))
+}
+
+/// The keybindings window, rebinding an action with the next chord pressed.
+#[derive(Debug, Default)]
+pub struct KeybindingsEditor {
+    pub open: bool,
+    rebinding: Option<Action>,
+    notice: Option<String>,
+}
+
+impl KeybindingsEditor {
+    /// Whether the next chord is being captured, shortcuts shouldn't trigger meanwhile.
+    pub fn capturing(&self) -> bool {
+        self.open && self.rebinding.is_some()
+    }
+
+    /// Shows the window and saves `bindings` whenever one changes.
+    pub fn show(&mut self, ctx: &Context, bindings: &mut Keybindings) {
+        if !self.open {
+            self.rebinding = None;
+            return;
+        }
+
+        if let Some(action) = self.rebinding {
+            if let Some(shortcut) = captured_chord(ctx) {
+                self.rebinding = None;
+                self.notice = match bindings.conflict(shortcut, action) {
+                    Some(other) => Some(format!(
+                        "{} is already bound to {}.",
+                        ctx.format_shortcut(&shortcut),
+                        other.title()
+                    )),
+                    None => {
+                        bindings.set(action, shortcut);
+                        bindings
+                            .save()
+                            .err()
+                            .map(|e| format!("Couldn't save: {}", e))
+                    }
+                };
+            }
+        }
+
+        let mut open = self.open;
+        Window::new("Keybindings")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                eframe::egui::Grid::new("keybindings")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for action in Action::ALL {
+                            ui.label(action.title());
+                            if self.rebinding == Some(action) {
+                                ui.label(RichText::new("Press a key…").italics());
+                                if ui.button("Cancel").clicked() {
+                                    self.rebinding = None;
+                                }
+                            } else {
+                                ui.label(ctx.format_shortcut(&bindings.get(action)));
+                                if ui.button("Rebind").clicked() {
+                                    self.rebinding = Some(action);
+                                    self.notice = None;
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+
+                if let Some(notice) = &self.notice {
+                    ui.label(RichText::new(notice).color(ui.style().visuals.warn_fg_color));
+                }
+                if ui.button("Reset to defaults").clicked() {
+                    *bindings = Keybindings::default();
+                    self.rebinding = None;
+                    self.notice = bindings
+                        .save()
+                        .err()
+                        .map(|e| format!("Couldn't save: {}", e));
+                }
+            });
+        self.open = open;
+    }
+}
+
+/// The first key pressed this frame with its modifiers, consuming the press.
+/// Ctrl is reported as Command on Windows and Linux, so it's stored as Command there.
+fn captured_chord(ctx: &Context) -> Option<KeyboardShortcut> {
+    ctx.input_mut(|i| {
+        let index = i.events.iter().position(|e| {
+            matches!(
+                e,
+                Event::Key {
+                    pressed: true,
+                    repeat: false,
+                    ..
+                }
+            )
+        })?;
+        let Event::Key { key, modifiers, .. } = i.events.remove(index) else {
+            return None;
+        };
+
+        let modifiers = Modifiers {
+            alt: modifiers.alt,
+            shift: modifiers.shift,
+            ctrl: modifiers.ctrl && !modifiers.command,
+            command: modifiers.command,
+            mac_cmd: false,
+        };
+        Some(KeyboardShortcut::new(modifiers, key))
+    })
+}
@@ -1,39 +1,80 @@
+mod help;
+mod keybindings;
+mod new_save;
+mod recovery;
 mod tabs;
+mod team_code;
+mod toasts;
+mod verify;
 
+use crate::keybindings::{Action, Keybindings, KeybindingsEditor};
+use crate::new_save::NewSaveDialog;
 use crate::tabs::{
-    ActivePokemonTab, GeneralTab, GuiTabState, StoredPokemonTab, TabPane, TabsBehavior,
+    ActivePokemonTab, CompareTab, GeneralTab, GuiTabState, OverviewTab, StatisticsTab,
+    StoredPokemonTab, TabPane, TabsBehavior,
 };
+use crate::team_code::{ImportAction, ImportTeamDialog};
+use crate::toasts::Toasts;
+use crate::verify::{VerifyAction, VerifyDialog};
 use eframe::egui::widget_text::RichText;
 use eframe::egui::{
-    containers, Button, CentralPanel, Context, FontFamily, FontId, Key, Margin, TopBottomPanel,
-    ViewportCommand, Visuals,
+    containers, Button, CentralPanel, ComboBox, Context, FontFamily, FontId, Margin,
+    TopBottomPanel, ViewportCommand, Visuals,
 };
 use eframe::{egui, App, CreationContext, Frame};
 use egui::IconData;
-use egui_tiles::{Tiles, Tree};
-use sky_save::SkySave;
+use egui_tiles::{Tile, Tiles, Tree};
+use sky_save::{
+    ActiveSaveBlock, NormalizePolicy, PokemonKind, ReportFormat, SaveBlock, SaveContainer, SkySave,
+    TeamPlacement,
+};
 use std::fmt::Debug;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::{Receiver, Sender};
 use std::sync::{mpsc, Arc};
 use std::thread;
+use std::time::Instant;
 
 pub mod built_info {
     include!(concat!(env!("OUT_DIR"), "/built.rs"));
 }
 
+/// The size of a bare save image, files longer than this are dumps or carry a footer.
+const SAVE_IMAGE_LEN: usize = 0x20000;
+
 pub const ICON_BYTES: &[u8] = include_bytes!("../res/icon.rgba").as_slice();
 
 #[derive(Debug)]
 enum Message {
     SaveFileOpened { filepath: PathBuf },
     SaveFileSavedAs { filepath: PathBuf },
+    ReportExported { filepath: PathBuf },
+    CompareOpened { filepath: PathBuf },
 }
 
 #[derive(Debug, Default)]
 struct State {
     pub filepath: Option<PathBuf>,
     pub save: Option<SkySave>,
+    /// Describes checksum problems found when opening, until dismissed.
+    pub checksum_banner: Option<String>,
+    pub status: Option<String>,
+    pub toasts: Toasts,
+    /// A recovery file newer than the opened file, until restored or discarded.
+    pub recovery_offer: Option<PathBuf>,
+    pub last_autosave: Option<Instant>,
+    /// Set by significant actions, so the next frame writes the recovery file without waiting for the interval.
+    pub autosave_due: bool,
+    pub show_file_info: bool,
+    pub show_shortcuts: bool,
+    pub import_team: Option<ImportTeamDialog>,
+    pub keybindings: Keybindings,
+    pub keybindings_editor: KeybindingsEditor,
+    pub verify: Option<VerifyDialog>,
+    pub new_save: Option<NewSaveDialog>,
+    /// The block to switch to once the user confirms discarding unsaved changes.
+    pub switch_block: Option<ActiveSaveBlock>,
 }
 
 #[derive(Debug)]
@@ -51,7 +92,10 @@ impl SkySaveGui {
         ctx.set_visuals(Visuals::dark());
 
         SkySaveGui {
-            state: State::default(),
+            state: State {
+                keybindings: Keybindings::load(),
+                ..Default::default()
+            },
             message_ch: mpsc::channel(),
             tabs: None,
         }
@@ -75,7 +119,8 @@ impl SkySaveGui {
     pub fn save_dialog(&mut self, callback_tx: Sender<Message>) {
         thread::spawn(move || {
             let path = rfd::FileDialog::new()
-                .add_filter("PMD EoS Saves", &["sav", "dsv"])
+                .add_filter("Raw save image", &["sav"])
+                .add_filter("DeSmuME save", &["dsv"])
                 .set_title("Save save file as")
                 .save_file();
 
@@ -87,28 +132,399 @@ impl SkySaveGui {
         });
     }
 
+    pub fn report_dialog(&mut self, callback_tx: Sender<Message>) {
+        thread::spawn(move || {
+            let path = rfd::FileDialog::new()
+                .add_filter("Markdown", &["md"])
+                .add_filter("Plain text", &["txt"])
+                .set_title("Export report")
+                .save_file();
+
+            if let Some(filepath) = path {
+                callback_tx
+                    .send(Message::ReportExported { filepath })
+                    .unwrap();
+            }
+        });
+    }
+
+    pub fn compare_dialog(&mut self, callback_tx: Sender<Message>) {
+        thread::spawn(move || {
+            let path = rfd::FileDialog::new()
+                .add_filter("PMD EoS Saves", &["sav", "dsv"])
+                .set_title("Compare with")
+                .pick_file();
+
+            if let Some(filepath) = path {
+                callback_tx
+                    .send(Message::CompareOpened { filepath })
+                    .unwrap();
+            }
+        });
+    }
+
     pub fn do_open(&mut self, path: PathBuf) {
         match SkySave::open(&path) {
             Ok(mut s) => {
+                let mut problems = vec![];
+                if !s.block_checksum_ok(SaveBlock::Primary) {
+                    problems.push("the primary block's checksum is invalid");
+                }
+                if !s.quicksave_valid {
+                    problems.push("the quicksave's checksum is invalid");
+                }
+                self.state.checksum_banner = (!problems.is_empty()).then(|| {
+                    format!(
+                        "Loaded the {:?} block, {}.",
+                        s.active_save_block,
+                        problems.join(" and ")
+                    )
+                });
+                self.state.status = None;
+
                 self.tabs = Some(self.build_tabs(&mut s));
+                self.state.recovery_offer = recovery::find(&path);
+                self.state.last_autosave = Some(Instant::now());
                 self.state.filepath = Some(path);
                 self.state.save = Some(s);
             }
             Err(e) => {
-                eprintln!("{:?}", e);
+                error_dialog(format!("Couldn't open {}: {}", path.display(), e));
             }
         }
     }
 
+    /// Saves to `path`, in the layout its extension asks for.
+    /// Saving over the opened file keeps its layout, `.sav` writes the bare image,
+    /// and `.dsv` keeps the trailing bytes of the opened file, which hold its DeSmuME footer.
     pub fn do_save(&mut self, path: PathBuf) {
+        let over_original = self.state.filepath.as_deref() == Some(path.as_path());
         if let Some(ref mut save) = self.state.save {
-            match save.save(&path) {
-                Ok(_) => {}
+            let extension = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(str::to_ascii_lowercase);
+            let raw = !over_original && extension.as_deref() == Some("sav");
+            if !over_original && extension.as_deref() == Some("dsv") && !has_trailing_bytes(save) {
+                error_dialog(format!(
+                    "Couldn't save to {}: the opened file has no DeSmuME footer to keep, \
+                    and this editor can't create one. Save as .sav instead.",
+                    path.display()
+                ));
+                return;
+            }
+
+            let result = if raw {
+                save.save_canonical(&path)
+            } else {
+                save.save(&path)
+            };
+            match result {
+                Ok(_) => {
+                    let layout = if raw {
+                        "raw save image".to_string()
+                    } else {
+                        save.container.layout_name(save.data.len())
+                    };
+                    self.state
+                        .toasts
+                        .info(format!("Saved to {}", file_name(&path)));
+                    self.state.status = Some(format!("{}: {}", file_name(&path), layout));
+                    // The edits a recovery file would restore are on disk now.
+                    if let Some(original) = &self.state.filepath {
+                        recovery::remove(original);
+                    }
+                    self.state.recovery_offer = None;
+                    if self.state.filepath.is_none() {
+                        self.state.last_autosave = Some(Instant::now());
+                        self.state.filepath = Some(path);
+                    }
+                }
                 Err(e) => {
-                    eprintln!("{:?}", e);
+                    error_dialog(format!("Couldn't save to {}: {}", path.display(), e));
+                }
+            }
+        }
+    }
+
+    pub fn do_export_report(&mut self, path: PathBuf) {
+        if let Some(ref save) = self.state.save {
+            let format = match path.extension().and_then(|e| e.to_str()) {
+                Some("txt") => ReportFormat::PlainText,
+                _ => ReportFormat::Markdown,
+            };
+
+            match fs::write(&path, save.report(format)) {
+                Ok(_) => self
+                    .state
+                    .toasts
+                    .info(format!("Report exported to {}", file_name(&path))),
+                Err(e) => {
+                    error_dialog(format!("Couldn't export to {}: {}", path.display(), e));
+                }
+            }
+        }
+    }
+
+    /// Restores the offered recovery file into the editor.
+    /// The recovery file keeps the layout of the opened file, so saving over it keeps its padding, mirror or footer.
+    /// The opened file is left untouched until the user saves.
+    pub fn do_restore_recovery(&mut self) {
+        let Some(path) = self.state.recovery_offer.take() else {
+            return;
+        };
+
+        match fs::read(&path)
+            .map_err(sky_save::SaveError::Io)
+            .and_then(SkySave::from_slice)
+        {
+            Ok(mut s) => {
+                self.tabs = Some(self.build_tabs(&mut s));
+                self.state.save = Some(s);
+                self.state
+                    .toasts
+                    .info("Recovered unsaved changes, save to keep them");
+            }
+            Err(e) => {
+                error_dialog(format!("Couldn't restore the recovery file: {}", e));
+            }
+        }
+    }
+
+    /// Writes the open save to its recovery file if it has unsaved changes,
+    /// once `AUTOSAVE_INTERVAL` passed since the last write or after a significant action.
+    /// The recovery file holds the whole file in the opened layout, not only the 128KiB image.
+    pub fn autosave(&mut self) {
+        let (Some(save), Some(path), Some(last)) = (
+            &self.state.save,
+            &self.state.filepath,
+            self.state.last_autosave,
+        ) else {
+            return;
+        };
+        let due = self.state.autosave_due || last.elapsed() >= recovery::AUTOSAVE_INTERVAL;
+        if !due || self.state.recovery_offer.is_some() {
+            return;
+        }
+
+        self.state.last_autosave = Some(Instant::now());
+        self.state.autosave_due = false;
+        if save.pending_changes().is_ok_and(|c| c.is_empty()) {
+            return;
+        }
+        if let Err(e) = save.export_canonical().and_then(|image| {
+            let mut data = save.data.clone();
+            data[..image.len()].copy_from_slice(&image);
+            recovery::write(path, &save.container.wrap(&data)).map_err(sky_save::SaveError::Io)
+        }) {
+            self.state
+                .toasts
+                .warning(format!("Couldn't write the recovery file: {}", e));
+        }
+    }
+
+    /// Applies a bulk edit from `sky_save::bulk` and reloads the tabs to show it.
+    pub fn do_bulk(
+        &mut self,
+        verb: &str,
+        edit: impl FnOnce(&mut SkySave) -> Result<usize, sky_save::SaveError>,
+    ) {
+        let Some(mut save) = self.state.save.take() else {
+            return;
+        };
+
+        let result = edit(&mut save);
+        self.tabs = Some(self.build_tabs(&mut save));
+        self.state.save = Some(save);
+        self.state.autosave_due = true;
+        match result {
+            Ok(count) => self
+                .state
+                .toasts
+                .info(format!("{} {} Pokémon", verb, count)),
+            Err(e) => {
+                error_dialog(format!("Couldn't apply the change: {}", e));
+            }
+        }
+    }
+
+    /// Replaces the open save with one made in the New Save dialog.
+    /// It has no file until saved with Save As.
+    pub fn do_new(&mut self, mut save: SkySave) {
+        self.tabs = Some(self.build_tabs(&mut save));
+        self.state.save = Some(save);
+        self.state.filepath = None;
+        self.state.checksum_banner = None;
+        self.state.recovery_offer = None;
+        self.state.last_autosave = None;
+        self.state.status = Some("New save, use Save As to write it".into());
+    }
+
+    /// Reloads the open save from `block`, discarding unsaved changes.
+    /// Saving afterward mirrors `block` over the other one.
+    pub fn do_switch_block(&mut self, block: ActiveSaveBlock) {
+        let Some(save) = &self.state.save else {
+            return;
+        };
+
+        match SkySave::from_slice_block(save.container.wrap(&save.data), block) {
+            Ok(mut s) => {
+                self.tabs = Some(self.build_tabs(&mut s));
+                self.state.save = Some(s);
+                // The discarded edits must not come back from the recovery file.
+                if let Some(original) = &self.state.filepath {
+                    recovery::remove(original);
+                }
+                self.state
+                    .toasts
+                    .info(format!("Editing the {:?} block", block));
+            }
+            Err(e) => {
+                error_dialog(format!("Couldn't load the {:?} block: {}", block, e));
+            }
+        }
+    }
+
+    /// Opens `path` read-only in a Compare tab, replacing any previous one.
+    pub fn do_compare(&mut self, path: PathBuf) {
+        let (Some(save), Some(tree)) = (&self.state.save, &mut self.tabs) else {
+            return;
+        };
+
+        let other = match SkySave::open(&path) {
+            Ok(o) => o,
+            Err(e) => {
+                error_dialog(format!("Couldn't open {}: {}", path.display(), e));
+                return;
+            }
+        };
+
+        let find = |tree: &Tree<TabPane>, name: &str| {
+            tree.tiles.iter().find_map(|(id, tile)| match tile {
+                Tile::Pane(p) if p.name == name => Some(*id),
+                _ => None,
+            })
+        };
+        if let Some(old) = find(tree, "Compare") {
+            tree.remove_recursively(old);
+        }
+        let Some(parent) = find(tree, "Overview").and_then(|id| tree.tiles.parent_of(id)) else {
+            return;
+        };
+
+        let pane = tree.tiles.insert_pane(TabPane {
+            name: "Compare",
+            tab_state: GuiTabState::Compare(CompareTab::new(path, other, save)),
+        });
+        if let Some(Tile::Container(c)) = tree.tiles.get_mut(parent) {
+            c.add_child(pane);
+        }
+        tree.make_active(|id, _| id == pane);
+    }
+
+    /// Puts the active team on the clipboard as a team code.
+    pub fn do_copy_team_code(&mut self, ctx: &Context) {
+        let Some(save) = &self.state.save else {
+            return;
+        };
+
+        match save.team_file(&[]) {
+            Ok(team) => {
+                ctx.copy_text(team.to_code());
+                self.state.toasts.info(format!(
+                    "Copied a team code with {} Pokémon",
+                    team.members.len()
+                ));
+            }
+            Err(e) => {
+                error_dialog(format!("Couldn't export the team: {}", e));
+            }
+        }
+    }
+
+    /// Imports the team previewed in the import window into the free active slots.
+    pub fn do_import_team_code(&mut self) {
+        let (
+            Some(save),
+            Some(ImportTeamDialog {
+                parsed: Some(Ok(team)),
+                ..
+            }),
+        ) = (&mut self.state.save, &self.state.import_team)
+        else {
+            return;
+        };
+
+        match save.import_team_file(team, TeamPlacement::Active) {
+            Ok(n) => {
+                self.state.import_team = None;
+                self.state
+                    .toasts
+                    .info(format!("Imported {} Pokémon from the team code", n));
+
+                let mut save = self.state.save.take().unwrap(); // Safe, checked above.
+                self.tabs = Some(self.build_tabs(&mut save));
+                self.state.save = Some(save);
+                self.state.autosave_due = true;
+            }
+            Err(e) => {
+                error_dialog(format!("Couldn't import the team: {}", e));
+            }
+        }
+    }
+
+    /// Selects the Pokémon at `slot` in its tab and switches to that tab.
+    pub fn goto_slot(&mut self, kind: PokemonKind, slot: usize) {
+        let (Some(save), Some(tree)) = (&self.state.save, &mut self.tabs) else {
+            return;
+        };
+
+        let mut found = None;
+        for (id, tile) in tree.tiles.iter_mut() {
+            let Tile::Pane(pane) = tile else {
+                continue;
+            };
+            match (&mut pane.tab_state, kind) {
+                (GuiTabState::StoredPokemon(tab), PokemonKind::Stored) => {
+                    tab.select(save, slot);
+                    found = Some(*id);
                 }
+                (GuiTabState::ActivePokemon(tab), PokemonKind::Active) => {
+                    tab.select(save, slot);
+                    found = Some(*id);
+                }
+                _ => {}
             }
         }
+        if let Some(found) = found {
+            tree.make_active(|id, _| id == found);
+        }
+    }
+
+    /// Applies `policy` and rebuilds the tabs, so they show the fixed values.
+    pub fn do_normalize(&mut self, policy: NormalizePolicy) {
+        let Some(mut save) = self.state.save.take() else {
+            return;
+        };
+
+        let changes = save.normalize(policy);
+        self.tabs = Some(self.build_tabs(&mut save));
+        self.state.verify = Some(VerifyDialog::new(&save));
+        self.state.save = Some(save);
+        self.state.autosave_due = true;
+        self.state
+            .toasts
+            .info(format!("Applied {} fixes", changes.len()));
+    }
+
+    pub fn do_action(&mut self, ctx: &Context, action: Action) {
+        let tx = self.message_ch.0.clone();
+        match action {
+            Action::Open => self.open_dialog(tx),
+            Action::SaveAs if self.state.save.is_some() => self.save_dialog(tx),
+            Action::SaveAs => {}
+            Action::Quit => ctx.send_viewport_cmd(ViewportCommand::Close),
+        }
     }
 
     pub fn build_tabs(&mut self, save: &mut SkySave) -> Tree<TabPane> {
@@ -116,6 +532,10 @@ impl SkySaveGui {
         let mut ui_tabs = vec![];
 
         let tabs = vec![
+            TabPane {
+                name: "Overview",
+                tab_state: GuiTabState::Overview(OverviewTab),
+            },
             TabPane {
                 name: "General",
                 tab_state: GuiTabState::General(GeneralTab::new(save)),
@@ -128,6 +548,10 @@ impl SkySaveGui {
                 name: "Active Pokemon",
                 tab_state: GuiTabState::ActivePokemon(ActivePokemonTab::new(save)),
             },
+            TabPane {
+                name: "Statistics",
+                tab_state: GuiTabState::Statistics(StatisticsTab),
+            },
         ];
 
         ui_tabs.push({
@@ -147,44 +571,242 @@ impl SkySaveGui {
 impl App for SkySaveGui {
     fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
         TopBottomPanel::top("top").show(ctx, |ui| {
-            ui.menu_button("File", |ui| {
-                if ui.button("Open").clicked() {
+            let bindings = self.state.keybindings.clone();
+            let shortcut = |action| ctx.format_shortcut(&bindings.get(action));
+            let file = ui.menu_button("File", |ui| {
+                if ui.button("New Save…").clicked() {
+                    self.state.new_save = Some(NewSaveDialog::default());
+                    ui.close_menu();
+                }
+
+                if ui
+                    .add(Button::new("Open").shortcut_text(shortcut(Action::Open)))
+                    .clicked()
+                {
                     let tx = self.message_ch.0.clone();
                     self.open_dialog(tx);
                     ui.close_menu();
                 }
 
-                ui.add_enabled_ui(self.state.filepath.is_some(), |ui| {
-                    if ui.button("Save As").clicked() {
+                ui.add_enabled_ui(self.state.save.is_some(), |ui| {
+                    if ui
+                        .add(Button::new("Save As").shortcut_text(shortcut(Action::SaveAs)))
+                        .clicked()
+                    {
                         let tx = self.message_ch.0.clone();
                         self.save_dialog(tx);
                         ui.close_menu();
                     }
+
+                    if ui.button("Export report…").clicked() {
+                        let tx = self.message_ch.0.clone();
+                        self.report_dialog(tx);
+                        ui.close_menu();
+                    }
+
+                    if ui.button("Compare with…").clicked() {
+                        let tx = self.message_ch.0.clone();
+                        self.compare_dialog(tx);
+                        ui.close_menu();
+                    }
+
+                    ui.separator();
+                    if ui.button("Copy team code").clicked() {
+                        self.do_copy_team_code(ctx);
+                        ui.close_menu();
+                    }
+
+                    if ui.button("Import team code…").clicked() {
+                        self.state.import_team = Some(ImportTeamDialog::default());
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                });
+
+                ui.add_enabled_ui(self.state.filepath.is_some(), |ui| {
+                    if ui.button("File info").clicked() {
+                        self.state.show_file_info = true;
+                        ui.close_menu();
+                    }
                 });
 
-                if ui.button("Quit").clicked() {
+                if ui
+                    .add(Button::new("Quit").shortcut_text(shortcut(Action::Quit)))
+                    .clicked()
+                {
                     ctx.send_viewport_cmd(ViewportCommand::Close);
                 }
             });
+            ui.menu_button("Tools", |ui| {
+                ui.add_enabled_ui(self.state.save.is_some(), |ui| {
+                    if ui.button("Verify Save").clicked() {
+                        self.state.verify = self.state.save.as_ref().map(VerifyDialog::new);
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button("Max all IQ").clicked() {
+                        self.do_bulk("Maxed the IQ of", SkySave::max_all_iq);
+                        ui.close_menu();
+                    }
+                    // The editor has no move table, so PP is left as is.
+                    if ui
+                        .button("Heal team")
+                        .on_hover_text(
+                            "Restores HP and clears sealed moves.\n\
+                            PP is left as is, the editor doesn't know the max PP of moves.",
+                        )
+                        .clicked()
+                    {
+                        self.do_bulk("Healed", |s| s.heal_team(|_| None));
+                        ui.close_menu();
+                    }
+                });
+            });
+            ui.menu_button("Settings", |ui| {
+                if ui.button("Keybindings…").clicked() {
+                    self.state.keybindings_editor.open = true;
+                    ui.close_menu();
+                }
+            });
+            let help = ui.menu_button("Help", |ui| {
+                if ui.button("Keyboard shortcuts").clicked() {
+                    self.state.show_shortcuts = true;
+                    ui.close_menu();
+                }
+            });
+
+            for (menu, response) in [("File", file.response), ("Help", help.response)] {
+                if help::accelerator_pressed(ctx, menu) {
+                    response.request_focus();
+                }
+            }
         });
 
+        egui::Window::new("Keyboard shortcuts")
+            .open(&mut self.state.show_shortcuts)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| help::shortcuts_ui(ui, &self.state.keybindings));
+
+        if let Some(dialog) = &mut self.state.import_team {
+            let mut open = true;
+            let action = egui::Window::new("Import team code")
+                .open(&mut open)
+                .collapsible(false)
+                .show(ctx, |ui| team_code::import_team_ui(dialog, ui))
+                .and_then(|r| r.inner)
+                .unwrap_or(ImportAction::None);
+
+            match action {
+                ImportAction::Import => self.do_import_team_code(),
+                ImportAction::Cancel => self.state.import_team = None,
+                ImportAction::None if !open => self.state.import_team = None,
+                ImportAction::None => {}
+            }
+        }
+
+        if let (Some(banner), Some(save)) =
+            (self.state.checksum_banner.clone(), self.state.save.as_mut())
+        {
+            TopBottomPanel::top("checksum_banner").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(banner).color(ui.style().visuals.warn_fg_color));
+
+                    let can_repair = save.active_save_block == ActiveSaveBlock::Backup
+                        && !save.block_checksum_ok(SaveBlock::Primary);
+                    if can_repair && ui.button("Repair primary from backup").clicked() {
+                        save.repair_from_active_block();
+                        self.state.checksum_banner = None;
+                        self.state.status = Some("Primary block repaired from the backup".into());
+                    }
+                    if ui.button("Ignore").clicked() {
+                        self.state.checksum_banner = None;
+                    }
+                });
+            });
+        }
+
+        if self.state.recovery_offer.is_some() {
+            TopBottomPanel::top("recovery_banner").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        RichText::new("Unsaved changes from a previous session were found.")
+                            .color(ui.style().visuals.warn_fg_color),
+                    );
+                    if ui.button("Restore").clicked() {
+                        self.do_restore_recovery();
+                    }
+                    if ui.button("Discard").clicked() {
+                        if let Some(original) = &self.state.filepath {
+                            recovery::remove(original);
+                        }
+                        self.state.recovery_offer = None;
+                    }
+                });
+            });
+        }
+
+        if let (Some(save), Some(path)) = (&self.state.save, &self.state.filepath) {
+            egui::Window::new("File info")
+                .open(&mut self.state.show_file_info)
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| file_info_ui(ui, save, path));
+        }
+
+        if let Some(dialog) = &mut self.state.new_save {
+            if let Some(save) = dialog.show(ctx) {
+                self.do_new(save);
+            }
+            if !self.state.new_save.as_ref().is_some_and(|d| d.open) {
+                self.state.new_save = None;
+            }
+        }
+
+        if let Some(dialog) = &mut self.state.verify {
+            match dialog.show(ctx) {
+                Some(VerifyAction::Goto { kind, slot }) => self.goto_slot(kind, slot),
+                Some(VerifyAction::Fix(policy)) => self.do_normalize(policy),
+                None => {}
+            }
+            if !self.state.verify.as_ref().is_some_and(|d| d.open) {
+                self.state.verify = None;
+            }
+        }
+
         CentralPanel::default().show(ctx, |ui| {
             if let Some(sv) = ctx.input(|st| st.raw.dropped_files.clone()).first() {
-                let path = sv.path.clone().unwrap();
-                self.do_open(path);
+                if let Some(path) = sv.path.clone() {
+                    if sky_save::quick_probe(&path) {
+                        self.do_open(path);
+                    } else {
+                        self.state
+                            .toasts
+                            .warning(format!("{} is not a valid save file", file_name(&path)));
+                    }
+                }
             }
 
             if let Ok(msg) = self.message_ch.1.try_recv() {
                 match msg {
                     Message::SaveFileOpened { filepath } => self.do_open(filepath),
                     Message::SaveFileSavedAs { filepath } => self.do_save(filepath),
+                    Message::ReportExported { filepath } => self.do_export_report(filepath),
+                    Message::CompareOpened { filepath } => self.do_compare(filepath),
                 }
             }
 
             if let Some(s) = &mut self.state.save {
-                let mut be = TabsBehavior { save: s };
+                let mut be = TabsBehavior {
+                    save: s,
+                    goto: None,
+                };
                 if let Some(t) = &mut self.tabs {
                     t.ui(&mut be, ui);
+
+                    if let Some(name) = be.goto {
+                        t.make_active(|_, tile| matches!(tile, Tile::Pane(p) if p.name == name));
+                    }
                 }
             } else {
                 CentralPanel::default()
@@ -209,20 +831,149 @@ impl App for SkySaveGui {
         });
 
         TopBottomPanel::bottom("pnl_version").show(ctx, |ui| {
-            ui.label(format!(
-                "Version: {} (git commit: {})",
-                built_info::PKG_VERSION,
-                built_info::GIT_COMMIT_HASH_SHORT.unwrap_or("Unknown")
-            ));
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "Version: {} (git commit: {})",
+                    built_info::PKG_VERSION,
+                    built_info::GIT_COMMIT_HASH_SHORT.unwrap_or("Unknown")
+                ));
+                if let Some(status) = &self.state.status {
+                    ui.separator();
+                    ui.label(status);
+                }
+                if let Some(save) = &self.state.save {
+                    ui.separator();
+                    let current = save.active_save_block;
+                    let mut selected = current;
+                    ComboBox::from_id_source("editing_block")
+                        .selected_text(format!("Editing: {:?}", current))
+                        .show_ui(ui, |ui| {
+                            for block in [ActiveSaveBlock::Primary, ActiveSaveBlock::Backup] {
+                                ui.selectable_value(&mut selected, block, format!("{:?}", block));
+                            }
+                        })
+                        .response
+                        .on_hover_text(
+                            "Once the edited block changed, saving mirrors it over the other one.",
+                        );
+                    if current == ActiveSaveBlock::Backup {
+                        ui.label(
+                            RichText::new(
+                                "Saving edits copies the backup block over the primary block",
+                            )
+                            .color(ui.style().visuals.warn_fg_color),
+                        );
+                    }
+
+                    if selected != current {
+                        if save.pending_changes().is_ok_and(|c| c.is_empty()) {
+                            self.do_switch_block(selected);
+                        } else {
+                            self.state.switch_block = Some(selected);
+                        }
+                    }
+                }
+            });
         });
 
-        if ctx.input(|st| st.key_pressed(Key::Escape)) {
-            ctx.send_viewport_cmd(ViewportCommand::Close);
+        if let Some(block) = self.state.switch_block {
+            let mut open = true;
+            let mut confirmed = false;
+            egui::Window::new("Switch block")
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(format!(
+                        "Switching to the {:?} block discards your unsaved changes.",
+                        block
+                    ));
+                    ui.horizontal(|ui| {
+                        confirmed = ui.button("Switch").clicked();
+                        if ui.button("Cancel").clicked() {
+                            self.state.switch_block = None;
+                        }
+                    });
+                });
+            if confirmed {
+                self.do_switch_block(block);
+            }
+            if confirmed || !open {
+                self.state.switch_block = None;
+            }
+        }
+
+        self.state.toasts.show(ctx);
+        self.autosave();
+        ctx.request_repaint_after(recovery::AUTOSAVE_INTERVAL);
+
+        self.state
+            .keybindings_editor
+            .show(ctx, &mut self.state.keybindings);
+        if !self.state.keybindings_editor.capturing() {
+            for action in self.state.keybindings.triggered(ctx) {
+                self.do_action(ctx, action);
+            }
         }
     }
 }
 
+/// Whether the file was a raw image followed by more bytes, like a DeSmuME footer.
+fn has_trailing_bytes(save: &SkySave) -> bool {
+    save.container == SaveContainer::Raw && save.data.len() > SAVE_IMAGE_LEN
+}
+
+/// Shows file-level facts about the open save, for bug reports.
+fn file_info_ui(ui: &mut egui::Ui, save: &SkySave, path: &Path) {
+    let ok = |valid: bool| if valid { "valid" } else { "invalid" };
+
+    egui::Grid::new("file_info").striped(true).show(ui, |ui| {
+        let mut row = |label: &str, value: String| {
+            ui.label(label);
+            ui.label(value);
+            ui.end_row();
+        };
+
+        row("Path", path.display().to_string());
+        let size = save.container.file_len(save.data.len());
+        row("Size", format!("{} bytes ({:#X})", size, size));
+        row("Container", save.container.layout_name(save.data.len()));
+        row("Loaded block", format!("{:?}", save.active_save_block));
+        row(
+            "Primary checksum",
+            ok(save.block_checksum_ok(SaveBlock::Primary)).into(),
+        );
+        row(
+            "Backup checksum",
+            ok(save.block_checksum_ok(SaveBlock::Backup)).into(),
+        );
+        row("Quicksave checksum", ok(save.quicksave_valid).into());
+    });
+}
+
+/// Shows an error in a modal dialog, without blocking the UI thread.
+fn error_dialog(description: String) {
+    thread::spawn(move || {
+        rfd::MessageDialog::new()
+            .set_level(rfd::MessageLevel::Error)
+            .set_title("Error")
+            .set_description(description)
+            .set_buttons(rfd::MessageButtons::Ok)
+            .show();
+    });
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .into_owned()
+}
+
 fn main() -> eframe::Result {
+    #[cfg(debug_assertions)]
+    env_logger::init();
+
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_min_inner_size([640.0, 480.0])
@@ -0,0 +1,121 @@
+//! The "New Save" dialog, creating a save from `SkySave::new_blank` and an optional starter template.
+
+use crate::tabs::{truncation_hint, CREATE_STATS};
+use eframe::egui::{Context, DragValue, RichText, TextEdit, Window};
+use sky_save::{PmdString, SkySave, StoredPokemon};
+
+/// The level of the template's starter team.
+const TEMPLATE_LEVEL: u8 = 5;
+/// The held money the template starts with, editable in the dialog.
+const TEMPLATE_MONEY: u32 = 1000;
+
+#[derive(Debug)]
+pub struct NewSaveDialog {
+    pub open: bool,
+    use_template: bool,
+    team_name: String,
+    money: u32,
+    /// The species ID and nickname of the hero and the partner.
+    starters: [(u16, String); 2],
+}
+
+impl Default for NewSaveDialog {
+    fn default() -> Self {
+        Self {
+            open: true,
+            use_template: true,
+            team_name: "Team".into(),
+            money: TEMPLATE_MONEY,
+            starters: [(1, "Hero".into()), (4, "Partner".into())],
+        }
+    }
+}
+
+impl NewSaveDialog {
+    /// Builds the save, with the hero and the partner in the first two stored slots and in the team.
+    /// Their stats are the `CREATE_STATS` placeholders, their EXP and moves start at zero.
+    fn create(&self) -> SkySave {
+        let mut save = SkySave::new_blank();
+        if !self.use_template {
+            return save;
+        }
+
+        save.general.team_name = PmdString::truncate_to_fit(&self.team_name).0;
+        save.general.held_money = self.money;
+        for (slot, (id, name)) in self.starters.iter().enumerate() {
+            save.stored_pokemon[slot] = StoredPokemon {
+                valid: true,
+                id: *id,
+                level: TEMPLATE_LEVEL,
+                name: PmdString::truncate_to_fit(name).0,
+                hp: CREATE_STATS.0,
+                attack: CREATE_STATS.1[0],
+                sp_attack: CREATE_STATS.1[1],
+                defense: CREATE_STATS.1[2],
+                sp_defense: CREATE_STATS.1[3],
+                ..Default::default()
+            };
+            save.add_to_team(slot)
+                .expect("a blank save has room for the starters");
+        }
+
+        save
+    }
+
+    /// Shows the dialog, returning the new save once the user creates it.
+    pub fn show(&mut self, ctx: &Context) -> Option<SkySave> {
+        let mut save = None;
+        let mut open = self.open;
+
+        Window::new("New Save")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.checkbox(&mut self.use_template, "Start from the starter template");
+                ui.add_enabled_ui(self.use_template, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Team name: ");
+                        ui.add(TextEdit::singleline(&mut self.team_name));
+                    });
+                    truncation_hint(ui, &self.team_name);
+                    ui.horizontal(|ui| {
+                        ui.label("Held money: ");
+                        ui.add(DragValue::new(&mut self.money).speed(50.0));
+                    });
+                    for (title, (id, name)) in ["Hero", "Partner"].iter().zip(&mut self.starters) {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("{} species ID: ", title));
+                            ui.add(DragValue::new(id).speed(1.0));
+                            ui.label("Nickname: ");
+                            ui.add(TextEdit::singleline(name));
+                        });
+                        truncation_hint(ui, name);
+                    }
+                    ui.label(
+                        RichText::new(format!(
+                            "The starters are level {} with {} HP and {} in every other stat.\n\
+                            Their EXP and moves start at zero.",
+                            TEMPLATE_LEVEL, CREATE_STATS.0, CREATE_STATS.1[0]
+                        ))
+                        .color(ui.style().visuals.weak_text_color()),
+                    );
+                });
+
+                ui.separator();
+                ui.label(
+                    RichText::new(
+                        "Regions of the save this editor doesn't know are left zeroed.\n\
+                        Load the file in-game and save once before relying on it.",
+                    )
+                    .color(ui.style().visuals.warn_fg_color),
+                );
+                if ui.button("Create").clicked() {
+                    save = Some(self.create());
+                }
+            });
+
+        self.open = open && save.is_none();
+        save
+    }
+}
@@ -0,0 +1,74 @@
+//! Recovery files for unsaved edits, written periodically while a save is open.
+//!
+//! Each opened file gets its own recovery file in the platform data directory,
+//! named after a hash of its path. Recovery files are never written over the user's files.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How often the open save is written to its recovery file, when it has unsaved changes.
+pub const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(120);
+
+/// The platform data directory of the editor, if it can be determined.
+pub fn data_dir() -> Option<PathBuf> {
+    let base = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|h| PathBuf::from(h).join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .filter(|d| !d.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/share")))
+    };
+
+    base.map(|b| b.join("sky-save-gui"))
+}
+
+/// FNV-1a, stable across Rust versions unlike `DefaultHasher`.
+fn path_hash(path: &Path) -> u64 {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    path.as_os_str()
+        .as_encoded_bytes()
+        .iter()
+        .fold(0xcbf29ce484222325, |h, &b| {
+            (h ^ b as u64).wrapping_mul(0x100000001b3)
+        })
+}
+
+/// The recovery file of the save at `original`. It holds the whole file, in the layout of `original`.
+pub fn recovery_path(original: &Path) -> Option<PathBuf> {
+    data_dir().map(|d| {
+        d.join("recovery")
+            .join(format!("{:016x}.sav", path_hash(original)))
+    })
+}
+
+pub fn write(original: &Path, data: &[u8]) -> io::Result<()> {
+    let path = recovery_path(original)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    fs::write(path, data)
+}
+
+/// Returns the recovery file of `original` if it exists and is newer than `original`.
+pub fn find(original: &Path) -> Option<PathBuf> {
+    let path = recovery_path(original)?;
+    let recovered = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+    let saved = fs::metadata(original).and_then(|m| m.modified()).ok();
+
+    saved.is_none_or(|s| recovered > s).then_some(path)
+}
+
+/// Deletes the recovery file of `original`, if any.
+pub fn remove(original: &Path) {
+    if let Some(path) = recovery_path(original) {
+        let _ = fs::remove_file(path);
+    }
+}
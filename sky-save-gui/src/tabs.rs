@@ -1,18 +1,195 @@
+use crate::error_dialog;
 use eframe::egui;
 use eframe::egui::{
-    containers, vec2, Align, CentralPanel, CollapsingHeader, Color32, DragValue, Id, Layout,
-    Margin, Response, RichText, ScrollArea, Sense, Stroke, TextEdit, TextStyle, TopBottomPanel, Ui,
-    Vec2, WidgetText,
+    containers, vec2, Align, CentralPanel, CollapsingHeader, Color32, DragValue, Id, Key, Layout,
+    Margin, Modifiers, Response, RichText, ScrollArea, Sense, Stroke, TextEdit, TextStyle,
+    TopBottomPanel, Ui, Vec2, WidgetText,
 };
-use egui_tiles::{Behavior, TabState, TileId, Tiles, UiResponse};
+use egui_extras::{Column, TableBuilder};
+use egui_tiles::{Behavior, TabState, Tile, TileId, Tiles, UiResponse};
 use egui_virtual_list::VirtualList;
-use sky_save::{ActivePokemon, PmdString, SkySave, StoredPokemon};
+use sky_save::{
+    field_descriptor, field_descriptors, ActivePokemon, FieldDescriptor, FieldOwner, PmdString,
+    RosterProblem, SaveDiff, SkySave, Slot, SlotDiff, StoredPokemon, Value, FIRST_RECRUIT_SLOT,
+    MAX_IQ, MAX_LEVEL,
+};
+use std::path::PathBuf;
 
 #[derive(Debug)]
 pub enum GuiTabState {
+    Overview(OverviewTab),
     General(GeneralTab),
     StoredPokemon(StoredPokemonTab),
     ActivePokemon(ActivePokemonTab),
+    Statistics(StatisticsTab),
+    Compare(CompareTab),
+}
+
+/// A read-only summary of the save, shown first after opening a file.
+#[derive(Debug, Default)]
+pub struct OverviewTab;
+
+/// Returns the name of the tab to switch to, if a link was clicked.
+pub fn overview_ui(ui: &mut Ui, save: &SkySave) -> Option<&'static str> {
+    let mut goto = None;
+    let mut link = |ui: &mut Ui, text: String, tab: &'static str| {
+        if ui
+            .link(text)
+            .on_hover_text(format!("Edit in {}", tab))
+            .clicked()
+        {
+            goto = Some(tab);
+        }
+    };
+
+    ui.heading("Overview");
+    ui.add_space(16.0);
+
+    let general = &save.general;
+    ui.horizontal(|ui| {
+        ui.label("Team: ");
+        link(ui, general.team_name.to_string_until_nul(), "General");
+    });
+    ui.horizontal(|ui| {
+        ui.label("Explorer rank: ");
+        link(
+            ui,
+            format!("{} ({} points)", general.rank_tier(), general.explorer_rank),
+            "General",
+        );
+    });
+    ui.horizontal(|ui| {
+        ui.label("Money: ");
+        link(
+            ui,
+            format!(
+                "{} total: {} held, {} stored, {} held in special episodes",
+                general.held_money as u64
+                    + general.stored_money as u64
+                    + general.sp_episode_held_money as u64,
+                general.held_money,
+                general.stored_money,
+                general.sp_episode_held_money
+            ),
+            "General",
+        );
+    });
+    ui.horizontal(|ui| {
+        ui.label("Adventures: ");
+        link(ui, general.number_of_adventures.to_string(), "General");
+    });
+
+    let capacity = save.stored_pokemon.len() - FIRST_RECRUIT_SLOT;
+    let recruited = save.stored_pokemon[FIRST_RECRUIT_SLOT..]
+        .iter()
+        .filter(|p| p.valid)
+        .count();
+    ui.horizontal(|ui| {
+        ui.label("Recruited: ");
+        link(
+            ui,
+            format!("{} / {}", recruited, capacity),
+            "Stored Pokemon",
+        );
+    });
+
+    ui.add_space(8.0);
+    ui.strong("Team");
+    for p in save.active_pokemon.iter().filter(|p| p.valid) {
+        ui.horizontal(|ui| {
+            link(
+                ui,
+                format!("{} (Lv. {})", p.name.to_string_until_nul(), p.level),
+                "Active Pokemon",
+            );
+        });
+    }
+
+    ui.add_space(8.0);
+    ui.strong("Save status");
+    ui.label(format!(
+        "Loaded from the {:?} block",
+        save.active_save_block
+    ));
+    let status = |ok: bool| if ok { "valid" } else { "invalid" };
+    ui.label(format!("Checksum: {}", status(save.checksums_ok)));
+    ui.label(format!("Quicksave: {}", status(save.quicksave_valid)));
+
+    goto
+}
+
+/// Charts of `SkySave::box_stats`, recomputed every frame so they follow edits.
+#[derive(Debug, Default)]
+pub struct StatisticsTab;
+
+/// The number of species shown in the species chart, the rest are summed up as "Other".
+const TOP_SPECIES: usize = 10;
+
+pub fn statistics_ui(ui: &mut Ui, save: &SkySave) {
+    ui.heading("Statistics");
+    ui.add_space(16.0);
+
+    let stats = save.box_stats();
+    if stats.recruited == 0 {
+        ui.label(
+            RichText::new("No recruited Pokémon yet, the charts fill in as the box does.")
+                .color(ui.style().visuals.weak_text_color()),
+        );
+        return;
+    }
+
+    ui.label(format!(
+        "{} recruited, {} species",
+        stats.recruited,
+        stats.species.len()
+    ));
+    if let Some(iq) = stats.average_iq {
+        ui.label(format!("Average IQ: {:.1}", iq));
+    }
+
+    ui.add_space(8.0);
+    ui.strong("Recruits per level");
+    let levels: Vec<(String, usize)> = stats
+        .levels
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (format!("{}-{}", i * 10 + 1, i * 10 + 10), *n))
+        .collect();
+    bar_chart(ui, "level_chart", &levels);
+
+    // Species are shown by ID, the crate has no name tables.
+    ui.add_space(8.0);
+    ui.strong("Recruits per species");
+    let mut species: Vec<(u16, usize)> = stats.species.into_iter().collect();
+    species.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    let other: usize = species.iter().skip(TOP_SPECIES).map(|(_, n)| n).sum();
+    let mut bars: Vec<(String, usize)> = species
+        .iter()
+        .take(TOP_SPECIES)
+        .map(|(id, n)| (format!("ID {}", id), *n))
+        .collect();
+    if other > 0 {
+        bars.push(("Other".into(), other));
+    }
+    bar_chart(ui, "species_chart", &bars);
+}
+
+/// Draws a horizontal bar per `(label, count)`, scaled to the largest count.
+fn bar_chart(ui: &mut Ui, id: &str, bars: &[(String, usize)]) {
+    let max = bars.iter().map(|(_, n)| *n).max().unwrap_or(0).max(1);
+    let color = ui.style().visuals.selection.bg_fill;
+
+    egui::Grid::new(id).show(ui, |ui| {
+        for (label, n) in bars {
+            ui.label(label);
+            let (rect, _) = ui.allocate_exact_size(vec2(240.0, 14.0), Sense::hover());
+            let mut bar = rect;
+            bar.set_width(rect.width() * *n as f32 / max as f32);
+            ui.painter().rect_filled(bar, 2.0, color);
+            ui.label(n.to_string());
+            ui.end_row();
+        }
+    });
 }
 
 #[derive(Debug, Default)]
@@ -29,18 +206,19 @@ impl GeneralTab {
 }
 
 pub fn general_ui(state: &mut GeneralTab, ui: &mut Ui, save: &mut SkySave) {
-    save.general.team_name = PmdString::from(state.name_buffer.as_bytes());
-
     ui.heading("General Save Data");
     ui.add_space(16.0);
     ui.horizontal(|ui| {
         ui.label("Team name: ");
-        ui.add(
-            TextEdit::singleline(&mut state.name_buffer)
-                .char_limit(10)
-                .hint_text("Team name"),
-        );
+        // Only written when edited, so the bytes after the terminator are kept otherwise.
+        if ui
+            .add(TextEdit::singleline(&mut state.name_buffer).hint_text("Team name"))
+            .changed()
+        {
+            save.general.team_name = PmdString::truncate_to_fit(&state.name_buffer).0;
+        }
     });
+    truncation_hint(ui, &state.name_buffer);
 
     ui.horizontal(|ui| {
         ui.label("Held money: ");
@@ -64,12 +242,140 @@ pub fn general_ui(state: &mut GeneralTab, ui: &mut Ui, save: &mut SkySave) {
     });
 }
 
+/// Criteria for the stored list, combined with AND. Empty criteria match every slot.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StoredFilter {
+    species: Vec<u16>,
+    level: Option<(u8, u8)>,
+    move_id: Option<u16>,
+    iq_skill: Option<usize>,
+}
+
+impl StoredFilter {
+    fn is_empty(&self) -> bool {
+        *self == StoredFilter::default()
+    }
+
+    fn matches(&self, pkm: &StoredPokemon) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+
+        pkm.valid
+            && (self.species.is_empty() || self.species.contains(&pkm.id))
+            && self
+                .level
+                .is_none_or(|(min, max)| (min..=max).contains(&pkm.level))
+            && self
+                .move_id
+                .is_none_or(|id| pkm.moves().iter().any(|m| m.valid && m.id == id))
+            && self
+                .iq_skill
+                .is_none_or(|skill| pkm.iq_map.enabled().any(|s| s == skill))
+    }
+}
+
 #[derive(Debug)]
 pub struct StoredPokemonTab {
     list: VirtualList,
     current: usize,
     item_state: StoredPokemon,
     name_buffer: String,
+    filter: StoredFilter,
+    species_input: u16,
+    /// The slots matching `filter`, recomputed only when the criteria or the stored Pokémon change.
+    visible: Vec<usize>,
+    /// The criteria `visible` was computed for, `None` once the stored Pokémon change,
+    /// see `StoredPokemonTab::stored_changed`.
+    visible_filter: Option<StoredFilter>,
+    table_view: bool,
+    sort: (StoredColumn, bool),
+    /// `visible` ordered by `sort`, re-sorted only when either changes.
+    sorted: Vec<usize>,
+    sorted_by: Option<(StoredColumn, bool)>,
+    goto_input: usize,
+    /// The list row to scroll to on the next frame.
+    scroll_to: Option<usize>,
+    row_height: f32,
+    /// Whether the selected row takes keyboard focus once it's shown, after moving with the arrow keys.
+    focus_selected: bool,
+    notice: Option<String>,
+    /// The open "Find duplicates" dialog, each slot with whether it's selected for clearing.
+    duplicates: Option<Vec<Vec<(usize, bool)>>>,
+    /// The slot awaiting confirmation of "Max this Pokémon".
+    maximize: Option<usize>,
+    /// The open "Create Pokémon here" dialog.
+    create: Option<Box<CreateForm>>,
+}
+
+/// The input of the "Create Pokémon here" dialog, for the empty slot `slot`.
+#[derive(Debug)]
+struct CreateForm {
+    slot: usize,
+    id: u16,
+    level: u8,
+    name: String,
+    /// HP, Attack, Sp. Attack, Defense and Sp. Defense.
+    stats: (u16, [u8; 4]),
+}
+
+/// The starting stats of the "Create Pokémon here" dialog and the new save template. These are placeholders
+/// in the range of a low level Pokémon, not the species' base stats, which the crate has no tables for.
+pub const CREATE_STATS: (u16, [u8; 4]) = (20, [10; 4]);
+
+/// A sortable column of the stored table view.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum StoredColumn {
+    Slot,
+    Name,
+    Species,
+    Level,
+    Iq,
+    Hp,
+}
+
+impl StoredColumn {
+    const ALL: [StoredColumn; 6] = [
+        StoredColumn::Slot,
+        StoredColumn::Name,
+        StoredColumn::Species,
+        StoredColumn::Level,
+        StoredColumn::Iq,
+        StoredColumn::Hp,
+    ];
+
+    fn title(&self) -> &'static str {
+        match self {
+            StoredColumn::Slot => "Slot",
+            StoredColumn::Name => "Name",
+            StoredColumn::Species => "Species",
+            StoredColumn::Level => "Level",
+            StoredColumn::Iq => "IQ",
+            StoredColumn::Hp => "HP",
+        }
+    }
+
+    /// Sorts `slots` by this column, empty slots last.
+    fn sort(&self, slots: &mut [usize], save: &SkySave, ascending: bool) {
+        slots.sort_by(|&a, &b| {
+            let (pa, pb) = (&save.stored_pokemon[a], &save.stored_pokemon[b]);
+            let order = match self {
+                StoredColumn::Slot => a.cmp(&b),
+                StoredColumn::Name => pa
+                    .name
+                    .to_string_until_nul()
+                    .cmp(&pb.name.to_string_until_nul()),
+                StoredColumn::Species => pa.id.cmp(&pb.id),
+                StoredColumn::Level => pa.level.cmp(&pb.level),
+                StoredColumn::Iq => pa.iq.cmp(&pb.iq),
+                StoredColumn::Hp => pa.hp.cmp(&pb.hp),
+            };
+
+            pb.valid
+                .cmp(&pa.valid)
+                .then(if ascending { order } else { order.reverse() })
+        });
+    }
 }
 
 impl StoredPokemonTab {
@@ -83,21 +389,173 @@ impl StoredPokemonTab {
             current,
             item_state: stored,
             name_buffer,
+            filter: StoredFilter::default(),
+            species_input: 0,
+            visible: Vec::new(),
+            visible_filter: None,
+            table_view: false,
+            sort: (StoredColumn::Slot, true),
+            sorted: Vec::new(),
+            sorted_by: None,
+            goto_input: 0,
+            scroll_to: None,
+            row_height: 0.0,
+            focus_selected: false,
+            notice: None,
+            duplicates: None,
+            maximize: None,
+            create: None,
         }
     }
+
+    /// Selects the stored slot `index`, see `goto_stored_slot`.
+    pub fn select(&mut self, save: &SkySave, index: usize) {
+        goto_stored_slot(self, save, index);
+    }
+
+    /// Marks the stored Pokémon as edited, so the filter is applied again on the next frame.
+    /// Every edit from this tab must call it, edits made elsewhere rebuild the tabs instead.
+    fn stored_changed(&mut self) {
+        self.visible_filter = None;
+    }
+}
+
+fn stored_filter_ui(state: &mut StoredPokemonTab, ui: &mut Ui) {
+    CollapsingHeader::new("Filters")
+        .id_source("filters")
+        .show_unindented(ui, |ui| {
+            let filter = &mut state.filter;
+
+            ui.horizontal(|ui| {
+                ui.label("Species ID: ");
+                ui.add(DragValue::new(&mut state.species_input).speed(1.0));
+                if ui.button("Add").clicked() && !filter.species.contains(&state.species_input) {
+                    filter.species.push(state.species_input);
+                }
+            });
+            ui.horizontal(|ui| {
+                let mut enabled = filter.level.is_some();
+                let (mut min, mut max) = filter.level.unwrap_or((1, 100));
+                ui.checkbox(&mut enabled, "Level: ");
+                ui.add_enabled(enabled, DragValue::new(&mut min).range(0..=max));
+                ui.add_enabled(enabled, DragValue::new(&mut max).range(min..=127));
+                filter.level = enabled.then_some((min, max));
+            });
+            ui.horizontal(|ui| {
+                let mut enabled = filter.move_id.is_some();
+                let mut id = filter.move_id.unwrap_or(1);
+                ui.checkbox(&mut enabled, "Knows move ID: ");
+                ui.add_enabled(enabled, DragValue::new(&mut id).speed(1.0));
+                filter.move_id = enabled.then_some(id);
+            });
+            ui.horizontal(|ui| {
+                let mut enabled = filter.iq_skill.is_some();
+                let mut skill = filter.iq_skill.unwrap_or(1);
+                ui.checkbox(&mut enabled, "Has IQ skill: ");
+                ui.add_enabled(
+                    enabled,
+                    DragValue::new(&mut skill).range(0..=sky_save::IQ_MAP_BIT_LEN - 1),
+                );
+                filter.iq_skill = enabled.then_some(skill);
+            });
+        });
+
+    let filter = &mut state.filter;
+    ui.horizontal_wrapped(|ui| {
+        filter
+            .species
+            .retain(|id| !ui.small_button(format!("Species {} ✖", id)).clicked());
+        if let Some((min, max)) = filter.level {
+            if ui
+                .small_button(format!("Level {}-{} ✖", min, max))
+                .clicked()
+            {
+                filter.level = None;
+            }
+        }
+        if let Some(id) = filter.move_id {
+            if ui.small_button(format!("Move {} ✖", id)).clicked() {
+                filter.move_id = None;
+            }
+        }
+        if let Some(skill) = filter.iq_skill {
+            if ui.small_button(format!("IQ skill {} ✖", skill)).clicked() {
+                filter.iq_skill = None;
+            }
+        }
+    });
 }
 
 pub fn stored_ui(state: &mut StoredPokemonTab, ui: &mut Ui, save: &mut SkySave) {
     ui.heading("Stored Pokemon");
     ui.add_space(16.0);
+    stored_filter_ui(state, ui);
+    if state.visible_filter.as_ref() != Some(&state.filter) {
+        let visible: Vec<usize> = (0..save.stored_pokemon.len())
+            .filter(|&i| state.filter.matches(&save.stored_pokemon[i]))
+            .collect();
+        // Edits that keep the same rows don't re-sort the table under the cursor.
+        if visible != state.visible {
+            state.visible = visible;
+            state.sorted_by = None;
+        }
+        state.visible_filter = Some(state.filter.clone());
+    }
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut state.table_view, "Table view");
+        ui.separator();
+        ui.label("Go to slot: ");
+        ui.add(DragValue::new(&mut state.goto_input).range(0..=save.stored_pokemon.len() - 1));
+        if ui.button("Go").clicked() {
+            goto_stored_slot(state, save, state.goto_input);
+        }
+        ui.separator();
+        if ui.button("Find duplicates").clicked() {
+            let groups = save.find_duplicate_stored();
+            if groups.is_empty() {
+                state.notice = Some("No duplicates found.".into());
+            } else {
+                state.duplicates = Some(
+                    groups
+                        .into_iter()
+                        .map(|g| g.into_iter().enumerate().map(|(i, s)| (s, i > 0)).collect())
+                        .collect(),
+                );
+            }
+        }
+    });
+    duplicates_ui(state, ui, save);
+    maximize_ui(state, ui, save);
+    create_ui(state, ui, save);
+    if let Some(notice) = state.notice.clone() {
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(notice).color(ui.style().visuals.warn_fg_color));
+            if ui.small_button("✖").clicked() {
+                state.notice = None;
+            }
+        });
+    }
+    ui.add_space(8.0);
+    if state.table_view {
+        stored_table_ui(state, ui, save);
+        return;
+    }
+
     ui.horizontal_top(|ui| {
         ui.vertical(|ui| {
-            ScrollArea::vertical().id_source("scroll1").show(ui, |ui| {
+            let mut area = ScrollArea::vertical().id_source("scroll1");
+            if let Some(row) = state.scroll_to.take() {
+                area = area.vertical_scroll_offset(row as f32 * state.row_height);
+            }
+            area.show(ui, |ui| {
                 ui.set_width(128.0);
+                let visible = &state.visible;
+                let spacing = ui.spacing().item_spacing.y;
                 state
                     .list
-                    .ui_custom_layout(ui, save.stored_pokemon.len(), |ui, index| {
-                        egui::Frame::canvas(ui.style())
+                    .ui_custom_layout(ui, visible.len(), |ui, row| {
+                        let index = visible[row];
+                        let frame = egui::Frame::canvas(ui.style())
                             .outer_margin(Margin {
                                 right: 16.0,
                                 ..Default::default()
@@ -108,25 +566,50 @@ pub fn stored_ui(state: &mut StoredPokemonTab, ui: &mut Ui, save: &mut SkySave)
                                     |ui| {
                                         let selected = index == state.current;
                                         let text = if save.stored_pokemon[index].valid {
-                                            RichText::new(
+                                            RichText::new(format!(
+                                                "{:03} {}",
+                                                index,
                                                 save.stored_pokemon[index]
                                                     .name
-                                                    .to_string_until_nul(),
-                                            )
+                                                    .to_string_until_nul()
+                                            ))
                                         } else {
-                                            RichText::new("[Empty]")
+                                            RichText::new(format!("{:03} [Empty]", index))
                                                 .color(Color32::from_hex("#666666").unwrap())
                                         };
 
-                                        if ui.selectable_label(selected, text).clicked() {
+                                        let response = ui.selectable_label(selected, text);
+                                        if selected && state.focus_selected {
+                                            response.request_focus();
+                                            state.focus_selected = false;
+                                        }
+                                        let step = arrow_key_step(ui, &response, row, visible.len());
+                                        if let Some(next) = step {
+                                            state.scroll_to = Some(next);
+                                            state.focus_selected = true;
+                                        }
+                                        if let Some(index) =
+                                            step.map(|r| visible[r]).or(response
+                                                .clicked()
+                                                .then_some(index))
+                                        {
                                             state.current = index;
                                             state.item_state = save.stored_pokemon[index].clone();
                                             state.name_buffer =
                                                 state.item_state.name.to_string_until_nul()
                                         }
+                                        if save.stored_pokemon[index].valid {
+                                            response.context_menu(|ui| {
+                                                if ui.button("Max this Pokémon").clicked() {
+                                                    state.maximize = Some(index);
+                                                    ui.close_menu();
+                                                }
+                                            });
+                                        }
                                     },
                                 );
                             });
+                        state.row_height = frame.response.rect.height() + spacing;
                         1
                     });
             });
@@ -134,13 +617,21 @@ pub fn stored_ui(state: &mut StoredPokemonTab, ui: &mut Ui, save: &mut SkySave)
         ui.separator();
         ui.vertical(|ui| {
             ScrollArea::vertical().id_source("scroll2").show(ui, |ui| {
-                save.stored_pokemon[state.current].name =
-                    PmdString::from(state.name_buffer.as_bytes());
-
                 ui.horizontal(|ui| {
                     ui.label("Valid: ");
                     ui.checkbox(&mut state.item_state.valid, "");
                 });
+                if !save.stored_pokemon[state.current].valid
+                    && ui.button("Create Pokémon here").clicked()
+                {
+                    state.create = Some(Box::new(CreateForm {
+                        slot: state.current,
+                        id: 1,
+                        level: 5,
+                        name: String::new(),
+                        stats: CREATE_STATS,
+                    }));
+                }
                 ui.add_enabled_ui(state.item_state.valid, |ui| {
                     ui.horizontal(|ui| {
                         ui.label("ID: ");
@@ -148,8 +639,17 @@ pub fn stored_ui(state: &mut StoredPokemonTab, ui: &mut Ui, save: &mut SkySave)
                     });
                     ui.horizontal(|ui| {
                         ui.label("Name: ");
-                        ui.add(TextEdit::singleline(&mut state.name_buffer).char_limit(10));
+                        // Only written when edited, so the bytes after the terminator are kept otherwise.
+                        if ui
+                            .add(TextEdit::singleline(&mut state.name_buffer))
+                            .changed()
+                        {
+                            save.stored_pokemon[state.current].name =
+                                PmdString::truncate_to_fit(&state.name_buffer).0;
+                            state.stored_changed();
+                        }
                     });
+                    truncation_hint(ui, &state.name_buffer);
                     CollapsingHeader::new("Details")
                         .id_source("details")
                         .show_unindented(ui, |ui| {
@@ -212,15 +712,15 @@ pub fn stored_ui(state: &mut StoredPokemonTab, ui: &mut Ui, save: &mut SkySave)
                     CollapsingHeader::new("Moves")
                         .id_source("moves")
                         .show_unindented(ui, |ui| {
-                            let moves = [
-                                &mut state.item_state.move_1,
-                                &mut state.item_state.move_2,
-                                &mut state.item_state.move_3,
-                                &mut state.item_state.move_4,
-                            ];
-                            for m in moves {
+                            for m in state.item_state.moves_mut() {
                                 ui.horizontal(|ui| {
-                                    ui.label("ID: ");
+                                    // Highlights the slots matching the move filter.
+                                    let label = RichText::new("ID: ");
+                                    ui.label(if m.valid && state.filter.move_id == Some(m.id) {
+                                        label.strong().color(ui.style().visuals.selection.stroke.color)
+                                    } else {
+                                        label
+                                    });
                                     ui.add(DragValue::new(&mut m.id).speed(1.0));
                                     ui.horizontal(|ui| {
                                         ui.spacing_mut().item_spacing = vec2(0.25, 0.0);
@@ -256,12 +756,382 @@ pub fn stored_ui(state: &mut StoredPokemonTab, ui: &mut Ui, save: &mut SkySave)
     });
 }
 
+/// Selects the stored slot `index` in the detail view and scrolls the list to it.
+/// Filters hiding the slot are cleared, and the table view is left, with a notice.
+fn goto_stored_slot(state: &mut StoredPokemonTab, save: &SkySave, index: usize) {
+    let mut changes = Vec::new();
+    if !state.visible.contains(&index) {
+        state.filter = StoredFilter::default();
+        state.visible = (0..save.stored_pokemon.len()).collect();
+        state.visible_filter = Some(state.filter.clone());
+        state.sorted_by = None;
+        changes.push("the filters were cleared");
+    }
+    if state.table_view {
+        state.table_view = false;
+        changes.push("the table view was closed");
+    }
+    state.notice = (!changes.is_empty())
+        .then(|| format!("To show slot {}, {}.", index, changes.join(" and ")));
+
+    state.current = index;
+    state.item_state = save.stored_pokemon[index].clone();
+    state.name_buffer = state.item_state.name.to_string_until_nul();
+    state.scroll_to = state.visible.iter().position(|&i| i == index);
+}
+
+/// Shows the "Find duplicates" dialog, if open.
+/// All copies but the first of each group start selected, clearing empties the selected slots.
+fn duplicates_ui(state: &mut StoredPokemonTab, ui: &mut Ui, save: &mut SkySave) {
+    let Some(groups) = &mut state.duplicates else {
+        return;
+    };
+
+    let mut open = true;
+    let mut clear = false;
+    egui::Window::new("Duplicates")
+        .open(&mut open)
+        .collapsible(false)
+        .show(ui.ctx(), |ui| {
+            ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                for group in groups.iter_mut() {
+                    let p = &save.stored_pokemon[group[0].0];
+                    ui.label(
+                        RichText::new(format!(
+                            "{} (species {}, Lv. {})",
+                            p.name.to_string_until_nul(),
+                            p.id,
+                            p.level
+                        ))
+                        .strong(),
+                    );
+                    ui.horizontal_wrapped(|ui| {
+                        for (slot, selected) in group.iter_mut() {
+                            ui.checkbox(selected, format!("{:03}", slot));
+                        }
+                    });
+                    ui.separator();
+                }
+            });
+
+            let count = groups.iter().flatten().filter(|(_, s)| *s).count();
+            ui.horizontal(|ui| {
+                ui.add_enabled_ui(count > 0, |ui| {
+                    clear = ui.button(format!("Clear {} selected", count)).clicked();
+                });
+            });
+        });
+
+    if clear {
+        let slots: Vec<usize> = groups
+            .iter()
+            .flatten()
+            .filter(|(_, s)| *s)
+            .map(|(slot, _)| *slot)
+            .collect();
+        for &slot in &slots {
+            save.stored_pokemon[slot] = StoredPokemon::default();
+        }
+        if slots.contains(&state.current) {
+            state.item_state = save.stored_pokemon[state.current].clone();
+            state.name_buffer = String::new();
+        }
+
+        state.stored_changed();
+        state.notice = Some(format!("Cleared {} duplicate slots.", slots.len()));
+        open = false;
+    }
+    if !open {
+        state.duplicates = None;
+    }
+}
+
+/// The changes made by "Max this Pokémon", as (field, target value) pairs.
+/// Stats and EXP are left alone, the crate has no base stat or growth tables to recalculate them from.
+const MAXIMIZE_FIELDS: [(&str, u64); 2] = [("level", MAX_LEVEL as u64), ("iq", MAX_IQ as u64)];
+
+/// Shows the confirmation for "Max this Pokémon", listing the fields that will change.
+/// The changes are applied through the field descriptors, only once all of them fit their bit widths.
+fn maximize_ui(state: &mut StoredPokemonTab, ui: &mut Ui, save: &mut SkySave) {
+    let Some(index) = state.maximize else {
+        return;
+    };
+
+    let changes: Vec<(FieldDescriptor, u64, u64)> = MAXIMIZE_FIELDS
+        .iter()
+        .filter_map(|&(name, to)| {
+            let field = field_descriptor(FieldOwner::StoredPokemon, name)?;
+            match field.get(save, Slot::Pokemon(index)).ok()? {
+                Value::Uint(old) if old != to => Some((field, old, to)),
+                _ => None,
+            }
+        })
+        .collect();
+
+    let mut open = true;
+    let mut apply = false;
+    egui::Window::new("Max this Pokémon")
+        .open(&mut open)
+        .collapsible(false)
+        .show(ui.ctx(), |ui| {
+            ui.label(format!(
+                "{:03} {}",
+                index,
+                save.stored_pokemon[index].name.to_string_until_nul()
+            ));
+            if changes.is_empty() {
+                ui.label("Already maxed, nothing to change.");
+            }
+            for (field, old, to) in &changes {
+                ui.label(format!("{}: {} → {}", field.name, old, to));
+            }
+            ui.label(
+                RichText::new("Stats and EXP are not changed.")
+                    .color(ui.style().visuals.weak_text_color()),
+            );
+
+            ui.add_enabled_ui(!changes.is_empty(), |ui| {
+                apply = ui.button("Apply").clicked();
+            });
+        });
+
+    if apply {
+        let overflow = changes.iter().find(|(field, _, to)| {
+            field
+                .range()
+                .is_some_and(|r| i64::try_from(*to).map_or(true, |to| !r.contains(&to)))
+        });
+        let result = match overflow {
+            Some((field, _, _)) => Err(sky_save::SaveError::FieldOverflow {
+                field: field.name,
+                bits: field.bits,
+            }),
+            None => changes.iter().try_for_each(|(field, _, to)| {
+                field.set(save, Slot::Pokemon(index), Value::Uint(*to))
+            }),
+        };
+        if let Err(e) = result {
+            state.notice = Some(format!("Couldn't max slot {}: {}", index, e));
+        }
+        if index == state.current {
+            state.item_state = save.stored_pokemon[index].clone();
+        }
+        state.stored_changed();
+        open = false;
+    }
+    if !open {
+        state.maximize = None;
+    }
+}
+
+/// Shows the "Create Pokémon here" dialog, filling the empty slot with a valid Pokémon
+/// of the chosen species, level, nickname and stats. EXP and moves are left zeroed,
+/// the crate has no species names or base stat tables to pick defaults from.
+fn create_ui(state: &mut StoredPokemonTab, ui: &mut Ui, save: &mut SkySave) {
+    let Some(form) = &mut state.create else {
+        return;
+    };
+
+    let mut open = true;
+    let mut create = false;
+    egui::Window::new("Create Pokémon")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ui.ctx(), |ui| {
+            ui.label(format!("Slot {:03}", form.slot));
+            ui.horizontal(|ui| {
+                ui.label("Species ID: ");
+                ui.add(
+                    DragValue::new(&mut form.id)
+                        .range(1..=stored_max("id"))
+                        .speed(1.0),
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.label("Level: ");
+                ui.add(DragValue::new(&mut form.level).range(1..=100).speed(1.0));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Nickname: ");
+                ui.add(TextEdit::singleline(&mut form.name));
+            });
+            truncation_hint(ui, &form.name);
+            ui.horizontal(|ui| {
+                ui.label("HP: ");
+                ui.add(
+                    DragValue::new(&mut form.stats.0)
+                        .range(1..=stored_max("hp"))
+                        .speed(1.0),
+                );
+            });
+            ui.horizontal(|ui| {
+                for (title, stat) in ["Atk", "Sp. Atk", "Def", "Sp. Def"]
+                    .iter()
+                    .zip(&mut form.stats.1)
+                {
+                    ui.label(format!("{}: ", title));
+                    ui.add(DragValue::new(stat).speed(1.0));
+                }
+            });
+            ui.label(
+                RichText::new("EXP and moves start at zero.")
+                    .color(ui.style().visuals.weak_text_color()),
+            );
+
+            ui.add_enabled_ui(!form.name.is_empty(), |ui| {
+                create = ui.button("Create").clicked();
+            });
+        });
+
+    if create {
+        save.stored_pokemon[form.slot] = StoredPokemon {
+            valid: true,
+            id: form.id,
+            level: form.level,
+            name: PmdString::truncate_to_fit(&form.name).0,
+            hp: form.stats.0,
+            attack: form.stats.1[0],
+            sp_attack: form.stats.1[1],
+            defense: form.stats.1[2],
+            sp_defense: form.stats.1[3],
+            ..Default::default()
+        };
+
+        state.current = form.slot;
+        state.item_state = save.stored_pokemon[form.slot].clone();
+        state.name_buffer = state.item_state.name.to_string_until_nul();
+        state.stored_changed();
+        open = false;
+    }
+    if !open {
+        state.create = None;
+    }
+}
+
+/// Shows the filtered stored Pokémon as a sortable table.
+/// Double-clicking a row opens it in the detail view.
+fn stored_table_ui(state: &mut StoredPokemonTab, ui: &mut Ui, save: &mut SkySave) {
+    if state.sorted_by != Some(state.sort) {
+        state.sorted.clone_from(&state.visible);
+        state.sort.0.sort(&mut state.sorted, save, state.sort.1);
+        state.sorted_by = Some(state.sort);
+    }
+    let rows = &state.sorted;
+    let (id_max, iq_max, hp_max) = (stored_max("id"), stored_max("iq"), stored_max("hp"));
+
+    let mut open = None;
+    let mut edited = false;
+    TableBuilder::new(ui)
+        .striped(true)
+        .sense(Sense::click())
+        .columns(Column::auto().at_least(48.0), StoredColumn::ALL.len())
+        .column(Column::remainder())
+        .header(20.0, |mut header| {
+            for c in StoredColumn::ALL {
+                header.col(|ui| {
+                    let arrow = match state.sort {
+                        (s, true) if s == c => " ⏶",
+                        (s, false) if s == c => " ⏷",
+                        _ => "",
+                    };
+                    if ui.button(format!("{}{}", c.title(), arrow)).clicked() {
+                        state.sort = (c, state.sort.0 != c || !state.sort.1);
+                    }
+                });
+            }
+            header.col(|ui| {
+                ui.strong("Moves");
+            });
+        })
+        .body(|body| {
+            body.rows(20.0, rows.len(), |mut row| {
+                let index = rows[row.index()];
+                let pkm = &mut save.stored_pokemon[index];
+
+                row.col(|ui| {
+                    ui.label(format!("{:03}", index));
+                });
+                row.col(|ui| {
+                    ui.label(if pkm.valid {
+                        RichText::new(pkm.name.to_string_until_nul())
+                    } else {
+                        RichText::new("[Empty]").color(Color32::from_hex("#666666").unwrap())
+                    });
+                });
+                row.col(|ui| {
+                    edited |= ui
+                        .add(DragValue::new(&mut pkm.id).range(0..=id_max).speed(1.0))
+                        .changed();
+                });
+                row.col(|ui| {
+                    edited |= ui
+                        .add(DragValue::new(&mut pkm.level).range(0..=100).speed(1.0))
+                        .changed();
+                });
+                row.col(|ui| {
+                    edited |= ui
+                        .add(DragValue::new(&mut pkm.iq).range(0..=iq_max).speed(1.0))
+                        .changed();
+                });
+                row.col(|ui| {
+                    edited |= ui
+                        .add(DragValue::new(&mut pkm.hp).range(0..=hp_max).speed(1.0))
+                        .changed();
+                });
+                row.col(|ui| {
+                    let moves: Vec<String> = pkm
+                        .moves()
+                        .iter()
+                        .filter(|m| m.valid)
+                        .map(|m| m.id.to_string())
+                        .collect();
+                    ui.label(moves.join(", "));
+                });
+
+                if row.response().double_clicked() {
+                    open = Some(index);
+                }
+            });
+        });
+
+    if edited {
+        state.stored_changed();
+    }
+    if let Some(index) = open {
+        state.current = index;
+        state.item_state = save.stored_pokemon[index].clone();
+        state.name_buffer = state.item_state.name.to_string_until_nul();
+        state.table_view = false;
+    }
+}
+
+/// Warns under a name input when `name` doesn't fit in the 10 bytes of a PMD name, showing what would be saved.
+pub fn truncation_hint(ui: &mut Ui, name: &str) {
+    let (fitted, truncated) = PmdString::truncate_to_fit(name);
+    if truncated {
+        ui.label(
+            RichText::new(format!("Too long for a PMD name, saved as \"{}\".", fitted))
+                .color(ui.style().visuals.warn_fg_color),
+        );
+    }
+}
+
+/// The largest value the stored Pokémon field `name` fits in the save.
+fn stored_max(name: &str) -> u64 {
+    field_descriptor(FieldOwner::StoredPokemon, name)
+        .and_then(|f| f.range())
+        .map_or(u64::MAX, |r| *r.end() as u64)
+}
+
 #[derive(Debug)]
 pub struct ActivePokemonTab {
     list: VirtualList,
     current: usize,
     item_state: ActivePokemon,
     name_buffer: String,
+    /// See `StoredPokemonTab::focus_selected`.
+    focus_selected: bool,
 }
 
 impl ActivePokemonTab {
@@ -275,12 +1145,65 @@ impl ActivePokemonTab {
             current,
             item_state,
             name_buffer,
+            focus_selected: false,
         }
     }
+
+    /// Selects the team member `index` in the detail view.
+    pub fn select(&mut self, save: &SkySave, index: usize) {
+        self.current = index;
+        self.item_state = save.active_pokemon[index].clone();
+        self.name_buffer = self.item_state.name.to_string_until_nul();
+    }
+}
+
+/// The row to select when the focused list row gets an arrow key press, see `focus_selected`.
+/// Tab leaves the list, so the arrow keys are the only way to move within it from the keyboard.
+fn arrow_key_step(ui: &Ui, response: &Response, row: usize, len: usize) -> Option<usize> {
+    if !response.has_focus() {
+        return None;
+    }
+
+    ui.input_mut(|i| {
+        if i.consume_key(Modifiers::NONE, Key::ArrowDown) {
+            Some((row + 1).min(len - 1))
+        } else if i.consume_key(Modifiers::NONE, Key::ArrowUp) {
+            Some(row.saturating_sub(1))
+        } else {
+            None
+        }
+    })
 }
 
 pub fn active_ui(state: &mut ActivePokemonTab, ui: &mut Ui, save: &mut SkySave) {
     ui.heading("Active Pokemon");
+    let roster_problems = save.validate_roster();
+    if !roster_problems.is_empty() {
+        egui::Frame::group(ui.style())
+            .fill(ui.style().visuals.warn_fg_color.gamma_multiply(0.15))
+            .stroke(Stroke::new(1.0, ui.style().visuals.warn_fg_color))
+            .show(ui, |ui| {
+                for problem in &roster_problems {
+                    ui.label(
+                        RichText::new(problem.to_string()).color(ui.style().visuals.warn_fg_color),
+                    );
+                }
+                // Renumbering can't fix unlinked members, there's nothing to link them with.
+                let renumberable = roster_problems
+                    .iter()
+                    .any(|p| !matches!(p, RosterProblem::Unlinked { .. }));
+                if renumberable
+                    && ui
+                        .button("Renumber roster")
+                        .on_hover_text("Renumbers the team 1 to N in slot order, unlinked members stay unlinked")
+                        .clicked()
+                {
+                    save.normalize_roster();
+                    state.item_state.roaster_number =
+                        save.active_pokemon[state.current].roaster_number;
+                }
+            });
+    }
     ui.add_space(16.0);
     ui.horizontal_top(|ui| {
         ui.vertical(|ui| {
@@ -310,7 +1233,21 @@ pub fn active_ui(state: &mut ActivePokemonTab, ui: &mut Ui, save: &mut SkySave)
                                                 .color(Color32::from_hex("#666666").unwrap())
                                         };
 
-                                        if ui.selectable_label(selected, text).clicked() {
+                                        let response = ui.selectable_label(selected, text);
+                                        if selected && state.focus_selected {
+                                            response.request_focus();
+                                            state.focus_selected = false;
+                                        }
+                                        let step = arrow_key_step(
+                                            ui,
+                                            &response,
+                                            index,
+                                            save.active_pokemon.len(),
+                                        );
+                                        state.focus_selected |= step.is_some();
+                                        if let Some(index) =
+                                            step.or(response.clicked().then_some(index))
+                                        {
                                             state.current = index;
                                             state.item_state = save.active_pokemon[index].clone();
                                             state.name_buffer =
@@ -325,8 +1262,6 @@ pub fn active_ui(state: &mut ActivePokemonTab, ui: &mut Ui, save: &mut SkySave)
         });
         ui.separator();
         ui.vertical(|ui| {
-            save.active_pokemon[state.current].name = PmdString::from(state.name_buffer.as_bytes());
-
             ScrollArea::vertical().id_source("scroll2").show(ui, |ui| {
                 ui.horizontal(|ui| {
                     ui.label("Valid: ");
@@ -339,8 +1274,15 @@ pub fn active_ui(state: &mut ActivePokemonTab, ui: &mut Ui, save: &mut SkySave)
                     });
                     ui.horizontal(|ui| {
                         ui.label("Name: ");
-                        ui.add(TextEdit::singleline(&mut state.name_buffer).char_limit(10));
+                        if ui
+                            .add(TextEdit::singleline(&mut state.name_buffer))
+                            .changed()
+                        {
+                            save.active_pokemon[state.current].name =
+                                PmdString::truncate_to_fit(&state.name_buffer).0;
+                        }
                     });
+                    truncation_hint(ui, &state.name_buffer);
                     CollapsingHeader::new("Details")
                         .id_source("details")
                         .show_unindented(ui, |ui| {
@@ -367,6 +1309,18 @@ pub fn active_ui(state: &mut ActivePokemonTab, ui: &mut Ui, save: &mut SkySave)
                                         .speed(1.0)
                                         .range(1..=4),
                                 );
+
+                                let problems: Vec<String> = roster_problems
+                                    .iter()
+                                    .filter(|p| p.slots().contains(&state.current))
+                                    .map(|p| p.to_string())
+                                    .collect();
+                                if !problems.is_empty() {
+                                    ui.label(
+                                        RichText::new("⚠").color(ui.style().visuals.warn_fg_color),
+                                    )
+                                    .on_hover_text(problems.join("\n"));
+                                }
                             });
                         });
                     CollapsingHeader::new("Stats")
@@ -404,14 +1358,7 @@ pub fn active_ui(state: &mut ActivePokemonTab, ui: &mut Ui, save: &mut SkySave)
                     CollapsingHeader::new("Moves")
                         .id_source("moves")
                         .show_unindented(ui, |ui| {
-                            let moves = [
-                                &mut state.item_state.move_1,
-                                &mut state.item_state.move_2,
-                                &mut state.item_state.move_3,
-                                &mut state.item_state.move_4,
-                            ];
-
-                            for m in moves {
+                            for m in state.item_state.moves_mut() {
                                 ui.horizontal(|ui| {
                                     ui.label("ID: ");
                                     ui.add(DragValue::new(&mut m.id).speed(1.0));
@@ -441,6 +1388,107 @@ pub fn active_ui(state: &mut ActivePokemonTab, ui: &mut Ui, save: &mut SkySave)
     });
 }
 
+/// Compares the open save with another file, which is opened read-only.
+#[derive(Debug)]
+pub struct CompareTab {
+    path: PathBuf,
+    other: Box<SkySave>,
+    /// Recomputed on refresh, or after copying a value.
+    diff: SaveDiff,
+}
+
+impl CompareTab {
+    pub fn new(path: PathBuf, other: SkySave, save: &SkySave) -> Self {
+        let diff = save.diff(&other);
+        Self {
+            path,
+            other: Box::new(other),
+            diff,
+        }
+    }
+}
+
+pub fn compare_ui(state: &mut CompareTab, ui: &mut Ui, save: &mut SkySave) {
+    ui.heading(format!("Compared with {}", state.path.display()));
+    ui.add_space(16.0);
+    if ui.button("Refresh").clicked() {
+        state.diff = save.diff(&state.other);
+    }
+    if state.diff.is_empty() {
+        ui.label("No differences.");
+    }
+    ui.add_space(8.0);
+
+    let mut copy = None;
+    ScrollArea::vertical().show(ui, |ui| {
+        CollapsingHeader::new("General")
+            .default_open(true)
+            .show(ui, |ui| {
+                egui::Grid::new("compare_general")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Field");
+                        ui.strong("This file");
+                        ui.strong("Other file");
+                        ui.end_row();
+
+                        for d in field_descriptors()
+                            .into_iter()
+                            .filter(|d| d.owner == FieldOwner::General)
+                        {
+                            let (Ok(this), Ok(other)) =
+                                (d.get(save, Slot::None), d.get(&state.other, Slot::None))
+                            else {
+                                continue;
+                            };
+
+                            let color = if this == other {
+                                ui.style().visuals.text_color()
+                            } else {
+                                ui.style().visuals.warn_fg_color
+                            };
+                            ui.label(d.name);
+                            ui.label(RichText::new(this.to_string()).color(color));
+                            ui.label(RichText::new(other.to_string()).color(color));
+                            if this != other
+                                && ui
+                                    .small_button("⏴ Use")
+                                    .on_hover_text("Copy the value from the other file")
+                                    .clicked()
+                            {
+                                copy = Some((d, other));
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        let slots = |ui: &mut Ui, title: &str, diffs: &[(usize, SlotDiff)]| {
+            CollapsingHeader::new(format!("{} ({})", title, diffs.len()))
+                .default_open(true)
+                .show(ui, |ui| {
+                    for (slot, d) in diffs {
+                        let text = match d {
+                            SlotDiff::Added => "added".to_string(),
+                            SlotDiff::Removed => "removed".to_string(),
+                            SlotDiff::Changed(fields) => format!("changed: {}", fields.join(", ")),
+                        };
+                        ui.label(format!("{:03} {}", slot, text));
+                    }
+                });
+        };
+        slots(ui, "Stored Pokemon", &state.diff.stored);
+        slots(ui, "Active Pokemon", &state.diff.active);
+    });
+
+    if let Some((d, value)) = copy {
+        if let Err(e) = d.set(save, Slot::None, value) {
+            error_dialog(format!("Couldn't copy {}: {}", d.name, e));
+        }
+        state.diff = save.diff(&state.other);
+    }
+}
+
 #[derive(Debug)]
 pub struct TabPane {
     pub name: &'static str,
@@ -450,16 +1498,35 @@ pub struct TabPane {
 #[derive(Debug)]
 pub struct TabsBehavior<'a> {
     pub save: &'a mut SkySave,
+    /// The name of a tab to switch to after this frame.
+    pub goto: Option<&'static str>,
 }
 
 impl<'a> Behavior<TabPane> for TabsBehavior<'a> {
+    fn is_tab_closable(&self, tiles: &Tiles<TabPane>, tile_id: TileId) -> bool {
+        matches!(
+            tiles.get(tile_id),
+            Some(Tile::Pane(TabPane {
+                tab_state: GuiTabState::Compare(_),
+                ..
+            }))
+        )
+    }
+
     fn pane_ui(&mut self, ui: &mut Ui, _tile_id: TileId, pane: &mut TabPane) -> UiResponse {
         CentralPanel::default()
             .frame(containers::Frame::default().outer_margin(Margin::symmetric(16.0, 16.0)))
             .show_inside(ui, |ui| match &mut pane.tab_state {
+                GuiTabState::Overview(_) => {
+                    if let Some(tab) = overview_ui(ui, self.save) {
+                        self.goto = Some(tab);
+                    }
+                }
                 GuiTabState::General(s) => general_ui(s, ui, self.save),
                 GuiTabState::StoredPokemon(s) => stored_ui(s, ui, self.save),
                 GuiTabState::ActivePokemon(s) => active_ui(s, ui, self.save),
+                GuiTabState::Statistics(_) => statistics_ui(ui, self.save),
+                GuiTabState::Compare(s) => compare_ui(s, ui, self.save),
             });
 
         UiResponse::None
@@ -0,0 +1,81 @@
+//! Copying the active team as a team code, and importing one with a preview.
+
+use eframe::egui::{Button, Grid, RichText, TextEdit, Ui};
+use sky_save::{SaveError, TeamFile, TeamMember};
+
+/// The state of the "Import team code" window.
+#[derive(Debug, Default)]
+pub struct ImportTeamDialog {
+    pub code: String,
+    /// The parsed code, `None` while the text field is empty.
+    pub parsed: Option<Result<TeamFile, SaveError>>,
+}
+
+/// What the user chose in the import window this frame.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ImportAction {
+    None,
+    Import,
+    Cancel,
+}
+
+/// Shows the code field and a preview of the Pokémon it holds.
+/// Nothing is imported until the user confirms.
+pub fn import_team_ui(state: &mut ImportTeamDialog, ui: &mut Ui) -> ImportAction {
+    ui.label("Paste a team code:");
+    if ui
+        .add(
+            TextEdit::multiline(&mut state.code)
+                .desired_rows(3)
+                .desired_width(f32::INFINITY),
+        )
+        .changed()
+    {
+        state.parsed = (!state.code.trim().is_empty()).then(|| TeamFile::from_code(&state.code));
+    }
+
+    match &state.parsed {
+        Some(Ok(team)) => {
+            ui.label(format!(
+                "{} Pokémon, exported by version {}:",
+                team.members.len(),
+                team.crate_version
+            ));
+            Grid::new("import_team_preview")
+                .striped(true)
+                .show(ui, |ui| {
+                    for m in &team.members {
+                        let (kind, name, id, level) = match m {
+                            TeamMember::Active(p) => ("Active", &p.name, p.id, p.level),
+                            TeamMember::Stored(p) => ("Stored", &p.name, p.id, p.level),
+                        };
+                        ui.label(name.to_string_until_nul());
+                        ui.label(format!("Species {}", id));
+                        ui.label(format!("Lv. {}", level));
+                        ui.label(kind);
+                        ui.end_row();
+                    }
+                });
+        }
+        Some(Err(e)) => {
+            ui.label(
+                RichText::new(format!("Invalid team code: {}", e))
+                    .color(ui.style().visuals.error_fg_color),
+            );
+        }
+        None => {}
+    }
+
+    let mut action = ImportAction::None;
+    ui.horizontal(|ui| {
+        let valid = matches!(state.parsed, Some(Ok(_)));
+        if ui.add_enabled(valid, Button::new("Import")).clicked() {
+            action = ImportAction::Import;
+        }
+        if ui.button("Cancel").clicked() {
+            action = ImportAction::Cancel;
+        }
+    });
+
+    action
+}
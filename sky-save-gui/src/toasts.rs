@@ -0,0 +1,72 @@
+use eframe::egui::{Align2, Area, Context, Frame, Id, RichText};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How long a toast stays on screen.
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ToastKind {
+    Info,
+    Warning,
+}
+
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub kind: ToastKind,
+    pub text: String,
+}
+
+/// Short, auto-dismissing notifications shown in the bottom-right corner.
+/// Toasts are shown one at a time, in the order they were pushed.
+#[derive(Debug, Default)]
+pub struct Toasts {
+    queue: VecDeque<Toast>,
+    current: Option<(Toast, Instant)>,
+}
+
+impl Toasts {
+    pub fn info(&mut self, text: impl Into<String>) {
+        self.push(ToastKind::Info, text.into());
+    }
+
+    pub fn warning(&mut self, text: impl Into<String>) {
+        self.push(ToastKind::Warning, text.into());
+    }
+
+    fn push(&mut self, kind: ToastKind, text: String) {
+        self.queue.push_back(Toast { kind, text });
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        if self
+            .current
+            .as_ref()
+            .is_some_and(|(_, shown)| shown.elapsed() >= TOAST_DURATION)
+        {
+            self.current = None;
+        }
+        if self.current.is_none() {
+            self.current = self.queue.pop_front().map(|t| (t, Instant::now()));
+        }
+
+        let Some((toast, shown)) = &self.current else {
+            return;
+        };
+
+        Area::new(Id::new("toast"))
+            .anchor(Align2::RIGHT_BOTTOM, [-16.0, -40.0])
+            .interactable(false)
+            .show(ctx, |ui| {
+                Frame::popup(ui.style()).show(ui, |ui| {
+                    let text = RichText::new(&toast.text);
+                    ui.label(match toast.kind {
+                        ToastKind::Info => text,
+                        ToastKind::Warning => text.color(ui.style().visuals.warn_fg_color),
+                    });
+                });
+            });
+
+        ctx.request_repaint_after(TOAST_DURATION.saturating_sub(shown.elapsed()));
+    }
+}
@@ -0,0 +1,199 @@
+//! The "Verify Save" dialog, showing the checksums and `SkySave::verify_integrity`.
+
+use eframe::egui::{Color32, Context, RichText, ScrollArea, Ui, Window};
+use sky_save::{
+    Anomaly, IntegrityReport, NormalizePolicy, PokemonKind, SaveBlock, Severity, SkySave,
+};
+use std::fmt::Write;
+
+/// What the user asked the dialog for this frame.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum VerifyAction {
+    /// Select a Pokémon in its tab.
+    Goto { kind: PokemonKind, slot: usize },
+    /// Apply the fixes, after confirmation.
+    Fix(NormalizePolicy),
+}
+
+#[derive(Debug)]
+pub struct VerifyDialog {
+    pub open: bool,
+    report: IntegrityReport,
+    checksums: [(&'static str, bool); 3],
+    confirm_fix: bool,
+}
+
+impl VerifyDialog {
+    pub fn new(save: &SkySave) -> Self {
+        Self {
+            open: true,
+            report: save.verify_integrity(),
+            checksums: [
+                ("Primary", save.block_checksum_ok(SaveBlock::Primary)),
+                ("Backup", save.block_checksum_ok(SaveBlock::Backup)),
+                ("Quicksave", save.quicksave_valid),
+            ],
+            confirm_fix: false,
+        }
+    }
+
+    /// The fixes `SkySave::normalize` can make for the current findings.
+    /// Valid moves with ID 0 have no fix, there's no way to tell which move was meant.
+    fn fix_policy(&self) -> NormalizePolicy {
+        let any = |f: fn(&Anomaly) -> bool| self.report.anomalies.iter().any(f);
+
+        NormalizePolicy {
+            clamp_levels: any(|a| {
+                matches!(a, Anomaly::LevelZero { .. } | Anomaly::LevelTooHigh { .. })
+            }),
+            clear_empty_slots: false,
+            renumber_roster: any(|a| matches!(a, Anomaly::Roster(_))),
+        }
+    }
+
+    /// The report as plain text, for pasting into bug reports.
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        for (block, ok) in self.checksums {
+            let _ = writeln!(
+                out,
+                "{} checksum: {}",
+                block,
+                if ok { "valid" } else { "invalid" }
+            );
+        }
+        if self.report.is_clean() {
+            let _ = writeln!(out, "No problems found.");
+        }
+        let _ = write!(out, "{}", self.report);
+
+        out
+    }
+
+    pub fn show(&mut self, ctx: &Context) -> Option<VerifyAction> {
+        let mut action = None;
+        let mut open = self.open;
+
+        Window::new("Verify Save")
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new("Checksums are of the file as loaded.")
+                        .color(ui.style().visuals.weak_text_color()),
+                );
+                for (block, ok) in self.checksums {
+                    let (text, color) = if ok {
+                        ("valid", Color32::GREEN)
+                    } else {
+                        ("invalid", ui.style().visuals.error_fg_color)
+                    };
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{} checksum:", block));
+                        ui.label(RichText::new(text).color(color));
+                    });
+                }
+                ui.separator();
+
+                if self.report.is_clean() {
+                    ui.label("No problems found.");
+                }
+                ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    for severity in [Severity::Error, Severity::Warning, Severity::Info] {
+                        if let Some(a) = findings_ui(ui, &self.report, severity) {
+                            action = Some(a);
+                        }
+                    }
+                });
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button("Copy report to clipboard").clicked() {
+                        ctx.copy_text(self.to_text());
+                    }
+
+                    let policy = self.fix_policy();
+                    let fixable = policy.clamp_levels || policy.renumber_roster;
+                    if fixable && !self.confirm_fix && ui.button("Fix automatically…").clicked() {
+                        self.confirm_fix = true;
+                    }
+                });
+
+                if self.confirm_fix {
+                    let policy = self.fix_policy();
+                    ui.group(|ui| {
+                        ui.label("This will:");
+                        if policy.clamp_levels {
+                            ui.label("• Clamp levels to 1-100");
+                        }
+                        if policy.renumber_roster {
+                            ui.label("• Renumber the roster");
+                        }
+                        ui.horizontal(|ui| {
+                            if ui.button("Apply").clicked() {
+                                action = Some(VerifyAction::Fix(policy));
+                                self.confirm_fix = false;
+                            }
+                            if ui.button("Cancel").clicked() {
+                                self.confirm_fix = false;
+                            }
+                        });
+                    });
+                }
+            });
+
+        self.open = open;
+        action
+    }
+}
+
+/// Lists the findings of `severity` under a heading, each with a "Go to" button.
+fn findings_ui(ui: &mut Ui, report: &IntegrityReport, severity: Severity) -> Option<VerifyAction> {
+    let findings: Vec<&Anomaly> = report
+        .anomalies
+        .iter()
+        .filter(|a| a.severity() == severity)
+        .collect();
+    if findings.is_empty() {
+        return None;
+    }
+
+    let mut action = None;
+    let color = match severity {
+        Severity::Error => ui.style().visuals.error_fg_color,
+        Severity::Warning => ui.style().visuals.warn_fg_color,
+        Severity::Info => ui.style().visuals.text_color(),
+    };
+    ui.label(
+        RichText::new(format!("{:?} ({})", severity, findings.len()))
+            .strong()
+            .color(color),
+    );
+
+    for a in findings {
+        ui.horizontal(|ui| {
+            if ui.small_button("Go to").clicked() {
+                action = Some(goto(a));
+            }
+            ui.label(a.to_string());
+        });
+    }
+
+    action
+}
+
+/// Roster problems go to the first team member involved.
+fn goto(anomaly: &Anomaly) -> VerifyAction {
+    match anomaly {
+        Anomaly::LevelZero { kind, slot }
+        | Anomaly::LevelTooHigh { kind, slot, .. }
+        | Anomaly::EmptyMove { kind, slot, .. } => VerifyAction::Goto {
+            kind: *kind,
+            slot: *slot,
+        },
+        Anomaly::Roster(problem) => VerifyAction::Goto {
+            kind: PokemonKind::Active,
+            slot: problem.slots()[0],
+        },
+    }
+}
@@ -1,7 +1,12 @@
 //! Handles loading and storing the active Pokémon in the party.
 
-use crate::offsets::active::{moves, pokemon, ACTIVE_MOVE_BIT_LEN, ACTIVE_PKM_BIT_LEN};
-use crate::{IqMapBits, PmdString};
+use crate::layout::bit_layout;
+use crate::offsets::active::{
+    moves, pokemon, ACTIVE_MOVE_BIT_LEN, ACTIVE_PKM_BITS, ACTIVE_PKM_BIT_LEN, ACTIVE_PKM_BYTE_LEN,
+    ACTIVE_PKM_COUNT,
+};
+use crate::save::{load_save_bits, slot_bits, store_save_bits};
+use crate::{ActiveSaveBlock, IqMap, PmdString, SaveError, StoredMove, StoredPokemon};
 use bitvec::prelude::*;
 
 /// A static `BitArray` representing the bits of an `ActivePokemon`.
@@ -9,144 +14,269 @@ pub type ActivePokemonBits = BitArr!(for ACTIVE_PKM_BIT_LEN, in u8, Lsb0);
 /// A static `BitArray` representing the bits of a `StoredMove`.
 pub type ActiveMoveBits = BitArr!(for ACTIVE_MOVE_BIT_LEN, in u8, Lsb0);
 
-/// Represents each of the four moves in an `ActivePokemon`.
-#[derive(Debug, Default, Clone)]
-pub struct ActiveMove {
-    pub valid: bool,
-    pub linked: bool,
-    pub switched: bool,
-    pub set: bool,
-    pub sealed: bool,
-    pub id: u16,
-    pub pp: u8,
-    pub power_boost: u8,
+bit_layout! {
+    /// Represents each of the four moves in an `ActivePokemon`.
+    #[derive(Debug, Default, Clone)]
+    pub struct ActiveMove(ActiveMoveBits) {
+        pub valid: bool = moves::VALID,
+        pub linked: bool = moves::LINKED,
+        pub switched: bool = moves::SWITCHED,
+        pub set: bool = moves::SET,
+        pub sealed: bool = moves::SEALED,
+        pub id: u16 = moves::ID,
+        pub pp: u8 = moves::PP,
+        pub power_boost: u8 = moves::POWER_BOOST,
+    }
 }
 
-impl ActiveMove {
-    pub fn from_bitslice(bits: &BitSlice<u8, Lsb0>) -> Self {
+/// Converts a `StoredMove` to an `ActiveMove`.
+/// The crate has no base PP data, so the PP starts at zero.
+impl From<&StoredMove> for ActiveMove {
+    fn from(value: &StoredMove) -> Self {
         Self {
-            valid: bits[moves::VALID],
-            linked: bits[moves::LINKED],
-            switched: bits[moves::SWITCHED],
-            set: bits[moves::SET],
-            sealed: bits[moves::SEALED],
-            id: bits[moves::ID].load_le(),
-            pp: bits[moves::PP].load_le(),
-            power_boost: bits[moves::POWER_BOOST].load_le(),
+            valid: value.valid,
+            linked: value.linked,
+            switched: value.switched,
+            set: value.set,
+            sealed: false,
+            id: value.id,
+            pp: 0,
+            power_boost: value.power_boost,
         }
     }
-
-    pub fn to_bits(&self) -> ActiveMoveBits {
-        let mut bits = bitarr![u8, Lsb0; 0; ACTIVE_MOVE_BIT_LEN];
-        bits.set(moves::VALID, self.valid);
-        bits.set(moves::LINKED, self.linked);
-        bits.set(moves::SWITCHED, self.switched);
-        bits.set(moves::SET, self.set);
-        bits[moves::ID].store_le(self.id);
-        bits[moves::PP].store_le(self.pp);
-        bits[moves::POWER_BOOST].store_le(self.power_boost);
-        bits
-    }
 }
 
-/// Represents one of the four active Pokémon in the party.
-#[derive(Debug, Default, Clone)]
-pub struct ActivePokemon {
-    pub valid: bool,
-    pub unknown_1: u8,
-    pub level: u8,
-    pub met_at: u8,
-    pub met_floor: u8,
-    pub unknown_2: bool,
-    pub iq: u16,
-    pub roaster_number: u16,
-    pub unknown_3: u32,
-    pub id: u16,
-    pub current_hp: u16,
-    pub max_hp: u16,
-    pub attack: u8,
-    pub sp_attack: u8,
-    pub defense: u8,
-    pub sp_defense: u8,
-    pub exp: u32,
-    pub move_1: ActiveMove,
-    pub move_2: ActiveMove,
-    pub move_3: ActiveMove,
-    pub move_4: ActiveMove,
-    pub unknown_4: u128,
-    pub iq_map: IqMapBits,
-    pub tactic: u8,
-    pub unknown_5: u16,
-    pub name: PmdString,
+bit_layout! {
+    /// Represents one of the four active Pokémon in the party.
+    #[derive(Debug, Default, Clone)]
+    pub struct ActivePokemon(ActivePokemonBits) {
+        pub valid: bool = pokemon::VALID,
+        pub unknown_1: u8 = pokemon::UNKNOWN_1,
+        pub level: u8 = pokemon::LEVEL,
+        pub met_at: u8 = pokemon::MET_AT,
+        pub met_floor: u8 = pokemon::MET_FLOOR,
+        pub unknown_2: bool = pokemon::UNKNOWN_2,
+        pub iq: u16 = pokemon::IQ,
+        pub roaster_number: u16 = pokemon::ROASTER_NUMBER,
+        pub unknown_3: u32 = pokemon::UNKNOWN_3,
+        pub id: u16 = pokemon::ID,
+        pub current_hp: u16 = pokemon::CURRENT_HP,
+        pub max_hp: u16 = pokemon::MAX_HP,
+        pub attack: u8 = pokemon::ATTACK,
+        pub sp_attack: u8 = pokemon::SP_ATTACK,
+        pub defense: u8 = pokemon::DEFENSE,
+        pub sp_defense: u8 = pokemon::SP_DEFENSE,
+        pub exp: u32 = pokemon::EXP,
+        pub move_1: ActiveMove = pokemon::MOVE_1,
+        pub move_2: ActiveMove = pokemon::MOVE_2,
+        pub move_3: ActiveMove = pokemon::MOVE_3,
+        pub move_4: ActiveMove = pokemon::MOVE_4,
+        pub unknown_4: u128 = pokemon::UNKNOWN_4,
+        pub iq_map: IqMap = pokemon::IQ_MAP,
+        pub tactic: u8 = pokemon::TACTIC,
+        pub unknown_5: u16 = pokemon::UNKNOWN_5,
+        pub name: PmdString = pokemon::NAME,
+    }
 }
 
 impl ActivePokemon {
-    pub fn from_bitslice(value: &BitSlice<u8, Lsb0>) -> Self {
-        let mut iq: IqMapBits = bitarr!(u8, Lsb0; 0; 69);
-        iq[0..69].copy_from_bitslice(&value[pokemon::IQ_MAP]);
+    /// Decodes team slot `index` from `block` of the raw save image `data`, without parsing anything else.
+    /// The checksums aren't checked, see `SkySaveMut` for validated access.
+    pub fn read_from_slot(
+        data: &[u8],
+        block: ActiveSaveBlock,
+        index: usize,
+    ) -> Result<Self, SaveError> {
+        let range = slot_bits(
+            data.len(),
+            ACTIVE_PKM_BITS,
+            ACTIVE_PKM_BIT_LEN,
+            ACTIVE_PKM_COUNT,
+            index,
+        )?;
+        Ok(Self::from_bitslice(load_save_bits(
+            data.view_bits(),
+            block,
+            range,
+        )))
+    }
+
+    /// Encodes into team slot `index` of `block` in the raw save image `data`.
+    /// Fails without writing anything if a value doesn't fit in its field.
+    /// The block's checksum is left stale, see `SaveBlock::fix_checksum`.
+    pub fn write_to_slot(
+        &self,
+        data: &mut [u8],
+        block: ActiveSaveBlock,
+        index: usize,
+    ) -> Result<(), SaveError> {
+        let range = slot_bits(
+            data.len(),
+            ACTIVE_PKM_BITS,
+            ACTIVE_PKM_BIT_LEN,
+            ACTIVE_PKM_COUNT,
+            index,
+        )?;
+        self.check_widths()?;
+        store_save_bits(
+            data.view_bits_mut(),
+            block,
+            range,
+            &self.to_bits()[..ACTIVE_PKM_BIT_LEN],
+        );
+
+        Ok(())
+    }
+
+    /// Packs the record as other editors exchange it, the active slot bits as they are in the save.
+    /// Bit `n` of the record is bit `n % 8` of byte `n / 8`, least significant first,
+    /// and the bits past `ACTIVE_PKM_BIT_LEN` in the last byte are zero.
+    /// Values too large for their field are truncated like `to_bits`.
+    pub fn to_packed_bytes(&self) -> [u8; ACTIVE_PKM_BYTE_LEN] {
+        self.to_bits().into_inner()
+    }
+
+    /// Unpacks a record in the layout of `to_packed_bytes`.
+    /// The padding bits in the last byte are ignored, not every tool clears them.
+    pub fn from_packed_bytes(bytes: &[u8]) -> Result<Self, SaveError> {
+        if bytes.len() != ACTIVE_PKM_BYTE_LEN {
+            return Err(SaveError::InvalidPackedLength {
+                expected: ACTIVE_PKM_BYTE_LEN,
+                found: bytes.len(),
+            });
+        }
+
+        Ok(Self::from_bitslice(
+            &bytes.view_bits()[..ACTIVE_PKM_BIT_LEN],
+        ))
+    }
+
+    /// Returns references to the four moves, in slot order.
+    pub fn moves(&self) -> [&ActiveMove; 4] {
+        [&self.move_1, &self.move_2, &self.move_3, &self.move_4]
+    }
+
+    /// Returns mutable references to the four moves, in slot order.
+    pub fn moves_mut(&mut self) -> [&mut ActiveMove; 4] {
+        [
+            &mut self.move_1,
+            &mut self.move_2,
+            &mut self.move_3,
+            &mut self.move_4,
+        ]
+    }
 
-        let name_bytes = &value[pokemon::NAME];
+    /// Replaces the move at `slot` (0 to 3).
+    pub fn set_move(&mut self, slot: usize, value: ActiveMove) -> Result<(), SaveError> {
+        let m = self
+            .moves_mut()
+            .into_iter()
+            .nth(slot)
+            .ok_or(SaveError::InvalidSlot(slot))?;
+        *m = value;
 
+        Ok(())
+    }
+}
+
+/// Converts a `StoredPokemon` to an `ActivePokemon`.
+/// The Pokémon starts with full HP, the roster number and unknown fields are zeroed.
+impl From<&StoredPokemon> for ActivePokemon {
+    fn from(value: &StoredPokemon) -> Self {
         Self {
-            valid: value[pokemon::VALID],
-            unknown_1: value[pokemon::UNKNOWN_1].load_le(),
-            level: value[pokemon::LEVEL].load_le(),
-            met_at: value[pokemon::MET_AT].load_le(),
-            met_floor: value[pokemon::MET_FLOOR].load_le(),
-            unknown_2: value[pokemon::UNKNOWN_2],
-            iq: value[pokemon::IQ].load_le(),
-            roaster_number: value[pokemon::ROASTER_NUMBER].load_le(),
-            unknown_3: value[pokemon::UNKNOWN_3].load_le(),
-            id: value[pokemon::ID].load_le(),
-            current_hp: value[pokemon::CURRENT_HP].load_le(),
-            max_hp: value[pokemon::MAX_HP].load_le(),
-            attack: value[pokemon::ATTACK].load_le(),
-            sp_attack: value[pokemon::SP_ATTACK].load_le(),
-            defense: value[pokemon::DEFENSE].load_le(),
-            sp_defense: value[pokemon::SP_DEFENSE].load_le(),
-            exp: value[pokemon::EXP].load_le(),
-            move_1: ActiveMove::from_bitslice(&value[pokemon::MOVE_1]),
-            move_2: ActiveMove::from_bitslice(&value[pokemon::MOVE_2]),
-            move_3: ActiveMove::from_bitslice(&value[pokemon::MOVE_3]),
-            move_4: ActiveMove::from_bitslice(&value[pokemon::MOVE_4]),
-            unknown_4: value[pokemon::UNKNOWN_4].load_le(),
-            iq_map: iq,
-            tactic: value[pokemon::TACTIC].load_le(),
-            unknown_5: value[pokemon::UNKNOWN_5].load_le(),
-            name: PmdString::from(name_bytes),
+            valid: value.valid,
+            level: value.level,
+            met_at: value.met_at,
+            met_floor: value.met_floor,
+            iq: value.iq,
+            id: value.id,
+            current_hp: value.hp,
+            max_hp: value.hp,
+            attack: value.attack,
+            sp_attack: value.sp_attack,
+            defense: value.defense,
+            sp_defense: value.sp_defense,
+            exp: value.exp,
+            move_1: ActiveMove::from(&value.move_1),
+            move_2: ActiveMove::from(&value.move_2),
+            move_3: ActiveMove::from(&value.move_3),
+            move_4: ActiveMove::from(&value.move_4),
+            iq_map: value.iq_map,
+            tactic: value.tactic,
+            name: value.name.clone(),
+            ..Default::default()
         }
     }
+}
 
-    pub fn to_bits(&self) -> ActivePokemonBits {
-        let mut bits = bitarr![u8, Lsb0; 0; ACTIVE_PKM_BIT_LEN];
-
-        bits.set(pokemon::VALID, self.valid);
-        bits[pokemon::UNKNOWN_1].store_le(self.unknown_1);
-        bits[pokemon::LEVEL].store_le(self.level);
-        bits[pokemon::MET_AT].store_le(self.met_at);
-        bits[pokemon::MET_FLOOR].store_le(self.met_floor);
-        bits.set(pokemon::UNKNOWN_2, self.unknown_2);
-        bits[pokemon::IQ].store_le(self.iq);
-        bits[pokemon::ROASTER_NUMBER].store_le(self.roaster_number);
-        bits[pokemon::UNKNOWN_3].store_le(self.unknown_3);
-        bits[pokemon::ID].store_le(self.id);
-        bits[pokemon::CURRENT_HP].store_le(self.current_hp);
-        bits[pokemon::MAX_HP].store_le(self.max_hp);
-        bits[pokemon::ATTACK].store_le(self.attack);
-        bits[pokemon::SP_ATTACK].store_le(self.sp_attack);
-        bits[pokemon::DEFENSE].store_le(self.defense);
-        bits[pokemon::SP_DEFENSE].store_le(self.sp_defense);
-        bits[pokemon::EXP].store_le(self.exp);
-        bits[pokemon::MOVE_1].copy_from_bitslice(&self.move_1.to_bits()[0..ACTIVE_MOVE_BIT_LEN]);
-        bits[pokemon::MOVE_2].copy_from_bitslice(&self.move_2.to_bits()[0..ACTIVE_MOVE_BIT_LEN]);
-        bits[pokemon::MOVE_3].copy_from_bitslice(&self.move_3.to_bits()[0..ACTIVE_MOVE_BIT_LEN]);
-        bits[pokemon::MOVE_4].copy_from_bitslice(&self.move_4.to_bits()[0..ACTIVE_MOVE_BIT_LEN]);
-        bits[pokemon::UNKNOWN_4].store_le(self.unknown_4);
-        bits[pokemon::IQ_MAP].copy_from_bitslice(&self.iq_map[0..69]);
-        bits[pokemon::TACTIC].store_le(self.tactic);
-        bits[pokemon::UNKNOWN_5].store_le(self.unknown_5);
-        bits[pokemon::NAME].copy_from_bitslice(self.name.to_save_bytes().view_bits::<Lsb0>());
-
-        bits
+#[test]
+fn test_set_move() {
+    let mut pkm = ActivePokemon::default();
+    let mv = ActiveMove {
+        valid: true,
+        id: 12,
+        pp: 20,
+        ..Default::default()
+    };
+
+    pkm.set_move(2, mv).unwrap();
+    assert_eq!(pkm.move_3.id, 12);
+    assert_eq!(pkm.moves()[2].pp, 20);
+    assert!(matches!(
+        pkm.set_move(4, ActiveMove::default()),
+        Err(SaveError::InvalidSlot(4))
+    ));
+}
+
+#[test]
+fn test_active_pokemon_bits_round_trip() {
+    let mut bytes = [0u8; 69];
+    for (i, b) in bytes.iter_mut().enumerate() {
+        *b = (i as u8).wrapping_mul(151).wrapping_add(17);
     }
+    let bits = &bytes.view_bits::<Lsb0>()[0..ACTIVE_PKM_BIT_LEN];
+
+    let pkm = ActivePokemon::from_bitslice(bits);
+    assert_eq!(&pkm.to_bits()[0..ACTIVE_PKM_BIT_LEN], bits);
+    assert_eq!(
+        &pkm.move_1.to_bits()[0..ACTIVE_MOVE_BIT_LEN],
+        &bits[pokemon::MOVE_1.range()]
+    );
+}
+
+#[test]
+fn test_active_pokemon_packed_bytes() {
+    use crate::fixture::fixture_save;
+    use crate::SkySave;
+
+    let data = fixture_save();
+    let save = SkySave::from_slice(&data).unwrap();
+    let block = &data.view_bits::<Lsb0>()[save.active_save_block as usize * 8..];
+
+    for (i, p) in save.active_pokemon.iter().enumerate() {
+        let start = ACTIVE_PKM_BITS.start + i * ACTIVE_PKM_BIT_LEN;
+        let mut expected = [0u8; ACTIVE_PKM_BYTE_LEN];
+        expected.view_bits_mut::<Lsb0>()[..ACTIVE_PKM_BIT_LEN]
+            .copy_from_bitslice(&block[start..start + ACTIVE_PKM_BIT_LEN]);
+
+        let packed = p.to_packed_bytes();
+        assert_eq!(packed, expected, "slot {}", i);
+        let unpacked = ActivePokemon::from_packed_bytes(&packed).unwrap();
+        assert_eq!(unpacked.to_packed_bytes(), packed, "slot {}", i);
+    }
+
+    // The six padding bits are ignored when unpacking and cleared when packing.
+    let packed = save.active_pokemon[0].to_packed_bytes();
+    let mut padded = packed;
+    padded[ACTIVE_PKM_BYTE_LEN - 1] |= 0xFC;
+    let pkm = ActivePokemon::from_packed_bytes(&padded).unwrap();
+    assert_eq!(pkm.to_packed_bytes(), packed);
+
+    assert!(matches!(
+        ActivePokemon::from_packed_bytes(&[0; ACTIVE_PKM_BYTE_LEN + 1]),
+        Err(SaveError::InvalidPackedLength {
+            expected: ACTIVE_PKM_BYTE_LEN,
+            found: 70
+        })
+    ));
 }
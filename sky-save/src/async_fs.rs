@@ -0,0 +1,75 @@
+//! Non-blocking file I/O for async frontends, behind the `tokio` feature.
+//!
+//! Only the file I/O is asynchronous. Parsing and serializing a save takes well under a millisecond,
+//! so they run on the calling task. `SkySave` is `Send`, so a loaded save can be moved between tasks:
+//!
+//! ```no_run
+//! # async fn edit() -> Result<(), sky_save::SaveError> {
+//! let mut save = sky_save::SkySave::open_async("game.sav").await?;
+//! save.general.held_money = 9999;
+//! save.save_async("game.sav").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::save::write_atomic;
+use crate::{SaveError, SaveOptions, SkySave};
+use std::io;
+use std::path::Path;
+
+impl SkySave {
+    /// Reads a file without blocking and loads it like `open`.
+    pub async fn open_async<P: AsRef<Path>>(filename: P) -> Result<Self, SaveError> {
+        let data = tokio::fs::read(filename).await.map_err(SaveError::Io)?;
+        Self::from_slice(&data)
+    }
+
+    /// Saves like `save`, writing the file without blocking.
+    pub async fn save_async<P: AsRef<Path>>(&mut self, filename: P) -> Result<(), SaveError> {
+        self.save_async_with(filename, SaveOptions::default()).await
+    }
+
+    /// Saves like `save_with`, writing the file without blocking.
+    /// Atomic writes run on the blocking thread pool, since they sync and rename the file.
+    pub async fn save_async_with<P: AsRef<Path>>(
+        &mut self,
+        filename: P,
+        options: SaveOptions,
+    ) -> Result<(), SaveError> {
+        let data = self.prepare_data()?;
+        let file = self.container.wrap(&data);
+        if options.atomic {
+            let path = filename.as_ref().to_path_buf();
+            tokio::task::spawn_blocking(move || write_atomic(&path, &file))
+                .await
+                .map_err(|e| SaveError::Io(io::Error::other(e)))??;
+        } else {
+            tokio::fs::write(filename, file)
+                .await
+                .map_err(SaveError::Io)?;
+        }
+
+        self.commit_data(data);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_async_round_trip() {
+    use crate::fixture::{fixture_save, TempPath};
+
+    let path = TempPath::new("async.sav");
+    std::fs::write(&path, fixture_save()).unwrap();
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let mut save = SkySave::open_async(&*path).await.unwrap();
+        save.general.held_money = 4321;
+        save.save_async(&*path).await.unwrap();
+
+        let saved = SkySave::open_async(&*path).await.unwrap();
+        assert_eq!(saved.general.held_money, 4321);
+    });
+}
@@ -0,0 +1,153 @@
+//! Records the edits made through `FieldDescriptor::set`, see `SkySave::audit_log`.
+//!
+//! The log is opt-in and only covers the setter-based APIs,
+//! mutating the public fields of the structures directly bypasses it.
+
+use crate::{FieldOwner, SkySave, Slot, Value};
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A single recorded edit.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AuditEntry {
+    pub owner: FieldOwner,
+    pub field: &'static str,
+    pub slot: Slot,
+    pub old: Value,
+    pub new: Value,
+    pub timestamp: SystemTime,
+}
+
+/// A list of recorded edits, oldest first.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct AuditLog {
+    entries: Vec<AuditEntry>,
+}
+
+impl AuditLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn push(&mut self, entry: AuditEntry) {
+        self.entries.push(entry);
+    }
+
+    pub fn entries(&self) -> &[AuditEntry] {
+        &self.entries
+    }
+
+    pub fn to_vec(&self) -> Vec<AuditEntry> {
+        self.entries.clone()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Serializes the log to a JSON array.
+    /// Timestamps are in seconds since the Unix epoch.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("[");
+
+        for (i, e) in self.entries.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+
+            let timestamp = e
+                .timestamp
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs());
+            let _ = write!(
+                out,
+                "{{\"owner\":\"{:?}\",\"field\":\"{}\",\"slot\":{},\"old\":{},\"new\":{},\"timestamp\":{}}}",
+                e.owner,
+                e.field,
+                slot_json(e.slot),
+                value_json(&e.old),
+                value_json(&e.new),
+                timestamp
+            );
+        }
+
+        out.push(']');
+        out
+    }
+}
+
+fn slot_json(slot: Slot) -> String {
+    match slot {
+        Slot::None => "null".to_string(),
+        Slot::Pokemon(i) => format!("{{\"pokemon\":{}}}", i),
+        Slot::Move { pokemon, slot } => format!("{{\"pokemon\":{},\"move\":{}}}", pokemon, slot),
+    }
+}
+
+fn value_json(value: &Value) -> String {
+    match value {
+        Value::Bool(v) => v.to_string(),
+        Value::Uint(v) => v.to_string(),
+        Value::Int(v) => v.to_string(),
+        Value::String(s) => {
+            let mut out = String::from("\"");
+            for c in s.chars() {
+                match c {
+                    '"' => out.push_str("\\\""),
+                    '\\' => out.push_str("\\\\"),
+                    c if c.is_control() => {
+                        let _ = write!(out, "\\u{:04x}", c as u32);
+                    }
+                    c => out.push(c),
+                }
+            }
+            out.push('"');
+            out
+        }
+        Value::Bits(bits) => {
+            let bits: Vec<&str> = bits.iter().map(|&b| if b { "1" } else { "0" }).collect();
+            format!("[{}]", bits.join(","))
+        }
+    }
+}
+
+impl SkySave {
+    /// Starts recording edits made through `FieldDescriptor::set`, keeping any existing log.
+    pub fn enable_audit_log(&mut self) {
+        self.audit_log.get_or_insert_with(AuditLog::new);
+    }
+
+    /// Stops recording edits and returns the log.
+    pub fn take_audit_log(&mut self) -> Option<AuditLog> {
+        self.audit_log.take()
+    }
+}
+
+#[test]
+fn test_audit_log() {
+    use crate::field_descriptor;
+
+    let mut save = SkySave::from_slice(vec![0; 0x20000]).unwrap();
+    let level = field_descriptor(FieldOwner::StoredPokemon, "level").unwrap();
+
+    level
+        .set(&mut save, Slot::Pokemon(3), Value::Uint(10))
+        .unwrap();
+    assert!(save.audit_log.is_none());
+
+    save.enable_audit_log();
+    level
+        .set(&mut save, Slot::Pokemon(3), Value::Uint(20))
+        .unwrap();
+    assert!(level
+        .set(&mut save, Slot::Pokemon(3), Value::Uint(200))
+        .is_err());
+
+    let log = save.take_audit_log().unwrap();
+    assert_eq!(log.entries().len(), 1);
+    assert_eq!(log.entries()[0].old, Value::Uint(10));
+    assert_eq!(log.entries()[0].new, Value::Uint(20));
+    assert!(log.to_json().starts_with(
+        "[{\"owner\":\"StoredPokemon\",\"field\":\"level\",\"slot\":{\"pokemon\":3},\"old\":10,\"new\":20,"
+    ));
+}
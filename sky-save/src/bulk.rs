@@ -0,0 +1,276 @@
+//! Edits every valid Pokémon at once, for the bulk actions of editors.
+//! Each helper returns the number of Pokémon it changed, invalid slots are skipped.
+
+use crate::{
+    field_descriptor, FieldDescriptor, FieldOwner, PokemonKind, SaveError, SkySave, Slot, Value,
+};
+
+/// The highest IQ the game reaches, see `SkySave::max_all_iq`.
+pub const MAX_IQ: u16 = 999;
+/// The highest level the game reaches, see `SkySave::set_all_levels`.
+pub const MAX_LEVEL: u8 = 100;
+
+/// A change to one field of the Pokémon `pokemon`, made through its `FieldDescriptor`.
+struct Edit {
+    pokemon: (PokemonKind, usize),
+    field: FieldDescriptor,
+    slot: Slot,
+    value: Value,
+}
+
+impl Edit {
+    fn new(kind: PokemonKind, index: usize, owner: FieldOwner, name: &str, value: Value) -> Self {
+        Self {
+            pokemon: (kind, index),
+            field: field_descriptor(owner, name).unwrap(), // Safe, only called with fields of `owner`.
+            slot: Slot::Pokemon(index),
+            value,
+        }
+    }
+
+    fn for_move(index: usize, move_slot: usize, name: &str, value: Value) -> Self {
+        Self {
+            slot: Slot::Move {
+                pokemon: index,
+                slot: move_slot,
+            },
+            ..Self::new(
+                PokemonKind::Active,
+                index,
+                FieldOwner::ActiveMove,
+                name,
+                value,
+            )
+        }
+    }
+}
+
+/// Checks every edit against the width of its field, then applies them through `FieldDescriptor::set`.
+/// Nothing is changed if any edit doesn't fit. Returns the number of Pokémon changed.
+fn apply(save: &mut SkySave, edits: Vec<Edit>) -> Result<usize, SaveError> {
+    for e in &edits {
+        if let (Some(range), Value::Uint(v)) = (e.field.range(), &e.value) {
+            if i64::try_from(*v).map_or(true, |v| !range.contains(&v)) {
+                return Err(SaveError::FieldOverflow {
+                    field: e.field.name,
+                    bits: e.field.bits,
+                });
+            }
+        }
+    }
+
+    let mut changed = vec![];
+    for e in edits {
+        e.field.set(save, e.slot, e.value)?;
+        if !changed.contains(&e.pokemon) {
+            changed.push(e.pokemon);
+        }
+    }
+
+    Ok(changed.len())
+}
+
+impl SkySave {
+    /// The valid stored and active Pokémon, as `(kind, index)`.
+    fn valid_pokemon(&self) -> Vec<(PokemonKind, usize)> {
+        let stored = self
+            .stored_pokemon
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.valid);
+        let active = self
+            .active_pokemon
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.valid);
+
+        stored
+            .map(|(i, _)| (PokemonKind::Stored, i))
+            .chain(active.map(|(i, _)| (PokemonKind::Active, i)))
+            .collect()
+    }
+
+    /// Sets `name` of every valid stored and active Pokémon whose `get` differs from `value`.
+    fn set_all(
+        &mut self,
+        name: &str,
+        value: u64,
+        get: impl Fn(&SkySave, PokemonKind, usize) -> u64,
+    ) -> Result<usize, SaveError> {
+        let edits = self
+            .valid_pokemon()
+            .into_iter()
+            .filter(|&(kind, i)| get(self, kind, i) != value)
+            .map(|(kind, i)| {
+                let owner = match kind {
+                    PokemonKind::Stored => FieldOwner::StoredPokemon,
+                    PokemonKind::Active => FieldOwner::ActivePokemon,
+                };
+                Edit::new(kind, i, owner, name, Value::Uint(value))
+            })
+            .collect();
+
+        apply(self, edits)
+    }
+
+    /// Sets the IQ of every valid stored and active Pokémon to `MAX_IQ`.
+    pub fn max_all_iq(&mut self) -> Result<usize, SaveError> {
+        self.set_all("iq", MAX_IQ.into(), |save, kind, i| match kind {
+            PokemonKind::Stored => save.stored_pokemon[i].iq.into(),
+            PokemonKind::Active => save.active_pokemon[i].iq.into(),
+        })
+    }
+
+    /// Sets the level of every valid stored and active Pokémon.
+    /// Fails without changing anything unless `level` is within 1 to `MAX_LEVEL`.
+    /// Stats and EXP are left alone, the crate has no base stat or growth tables.
+    pub fn set_all_levels(&mut self, level: u8) -> Result<usize, SaveError> {
+        if !(1..=MAX_LEVEL).contains(&level) {
+            return Err(SaveError::InvalidFieldValue("level"));
+        }
+
+        self.set_all("level", level.into(), |save, kind, i| match kind {
+            PokemonKind::Stored => save.stored_pokemon[i].level.into(),
+            PokemonKind::Active => save.active_pokemon[i].level.into(),
+        })
+    }
+
+    /// The edits setting the PP of every valid move of the valid team members to `max_pp(move_id)`.
+    fn pp_edits(&self, max_pp: &impl Fn(u16) -> Option<u8>) -> Vec<Edit> {
+        let mut edits = vec![];
+        for (i, p) in self
+            .active_pokemon
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.valid)
+        {
+            for (slot, m) in p.moves().iter().enumerate().filter(|(_, m)| m.valid) {
+                match max_pp(m.id) {
+                    Some(pp) if pp != m.pp => {
+                        edits.push(Edit::for_move(i, slot, "pp", Value::Uint(pp.into())))
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        edits
+    }
+
+    /// Restores the HP of every valid team member to their max HP, restores the PP of their valid moves
+    /// and clears their sealed moves.
+    /// The crate has no move tables, so the caller provides the max PP, see `restore_all_pp`.
+    /// Moves `max_pp` returns `None` for keep their PP, pass `|_| None` to leave every PP as is.
+    /// Fails with `SaveError::FieldOverflow` without changing anything if a max PP doesn't fit in its field.
+    pub fn heal_team(&mut self, max_pp: impl Fn(u16) -> Option<u8>) -> Result<usize, SaveError> {
+        let mut edits = self.pp_edits(&max_pp);
+        for (i, p) in self
+            .active_pokemon
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.valid)
+        {
+            if p.current_hp != p.max_hp {
+                edits.push(Edit::new(
+                    PokemonKind::Active,
+                    i,
+                    FieldOwner::ActivePokemon,
+                    "current_hp",
+                    Value::Uint(p.max_hp.into()),
+                ));
+            }
+            for (slot, _) in p.moves().iter().enumerate().filter(|(_, m)| m.sealed) {
+                edits.push(Edit::for_move(i, slot, "sealed", Value::Bool(false)));
+            }
+        }
+
+        apply(self, edits)
+    }
+
+    /// Sets the PP of every valid move of the valid team members to `max_pp(move_id)`.
+    /// The crate has no move tables, so the caller provides the max PP; moves it returns `None` for are skipped.
+    /// Fails with `SaveError::FieldOverflow` without changing anything if a max PP doesn't fit in its field.
+    pub fn restore_all_pp(
+        &mut self,
+        max_pp: impl Fn(u16) -> Option<u8>,
+    ) -> Result<usize, SaveError> {
+        let edits = self.pp_edits(&max_pp);
+        apply(self, edits)
+    }
+}
+
+#[test]
+fn test_max_all_iq_and_levels() {
+    let mut save = SkySave::new_blank();
+    save.stored_pokemon[5].valid = true;
+    save.stored_pokemon[6].valid = true;
+    save.stored_pokemon[6].iq = MAX_IQ;
+    save.stored_pokemon[7].iq = 10;
+    save.active_pokemon[0].valid = true;
+
+    assert_eq!(save.max_all_iq().unwrap(), 2);
+    assert_eq!(save.stored_pokemon[5].iq, MAX_IQ);
+    assert_eq!(save.stored_pokemon[7].iq, 10);
+    assert_eq!(save.active_pokemon[0].iq, MAX_IQ);
+    assert_eq!(save.max_all_iq().unwrap(), 0);
+
+    assert!(matches!(
+        save.set_all_levels(MAX_LEVEL + 1),
+        Err(SaveError::InvalidFieldValue("level"))
+    ));
+    assert!(save.set_all_levels(0).is_err());
+    assert_eq!(save.set_all_levels(MAX_LEVEL).unwrap(), 3);
+    assert_eq!(save.stored_pokemon[8].level, 0);
+    assert_eq!(save.active_pokemon[1].level, 0);
+
+    assert!(save.stored_pokemon.iter().all(|p| p.check_widths().is_ok()));
+    assert!(save.active_pokemon.iter().all(|p| p.check_widths().is_ok()));
+}
+
+#[test]
+fn test_heal_team_and_restore_pp() {
+    let mut save = SkySave::new_blank();
+    for (slot, valid) in [(0, true), (1, true), (2, false)] {
+        let p = &mut save.active_pokemon[slot];
+        p.valid = valid;
+        p.max_hp = 40;
+        p.current_hp = 12;
+        p.move_1.valid = true;
+        p.move_1.id = 33;
+        p.move_1.sealed = true;
+    }
+    save.active_pokemon[1].current_hp = 40;
+    save.active_pokemon[1].move_1.sealed = false;
+
+    assert!(matches!(
+        save.heal_team(|_| Some(u8::MAX)),
+        Err(SaveError::FieldOverflow { field: "pp", .. })
+    ));
+    assert_eq!(save.active_pokemon[0].current_hp, 12);
+
+    assert_eq!(save.heal_team(|id| (id == 33).then_some(15)).unwrap(), 2);
+    assert_eq!(save.active_pokemon[0].current_hp, 40);
+    assert!(!save.active_pokemon[0].move_1.sealed);
+    assert_eq!(save.active_pokemon[0].move_1.pp, 15);
+    assert_eq!(save.active_pokemon[1].move_1.pp, 15);
+    assert_eq!(save.active_pokemon[2].current_hp, 12);
+    assert!(save.active_pokemon[2].move_1.sealed);
+    assert_eq!(save.active_pokemon[2].move_1.pp, 0);
+
+    save.active_pokemon[0].move_2.id = 33;
+    assert!(matches!(
+        save.restore_all_pp(|_| Some(u8::MAX)),
+        Err(SaveError::FieldOverflow { field: "pp", .. })
+    ));
+    assert_eq!(save.active_pokemon[0].move_1.pp, 15);
+
+    assert_eq!(
+        save.restore_all_pp(|id| (id == 33).then_some(20)).unwrap(),
+        2
+    );
+    assert_eq!(save.active_pokemon[0].move_1.pp, 20);
+    // Invalid moves and invalid team slots are skipped.
+    assert_eq!(save.active_pokemon[0].move_2.pp, 0);
+    assert_eq!(save.active_pokemon[2].move_1.pp, 0);
+    assert_eq!(save.restore_all_pp(|_| Some(20)).unwrap(), 0);
+}
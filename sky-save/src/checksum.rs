@@ -0,0 +1,204 @@
+//! Streams the checksum of a save block, for patching saves on disk without loading them.
+
+use crate::save::MIN_SAVE_LEN;
+use crate::{SaveBlock, SaveContainer, SaveError};
+use std::fs::OpenOptions;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Calculates the checksum of a save block incrementally.
+/// Feed it the bytes of `SaveBlock::data_range` in order, in chunks of any size.
+///
+/// Gives the same result as the checksum `SkySave` calculates when the whole range is fed.
+#[derive(Debug, Clone)]
+pub struct BlockChecksum {
+    block: SaveBlock,
+    sum: u32,
+    pending: [u8; 4],
+    pending_len: usize,
+}
+
+impl BlockChecksum {
+    pub fn new(block: SaveBlock) -> Self {
+        Self {
+            block,
+            sum: 0,
+            pending: [0; 4],
+            pending_len: 0,
+        }
+    }
+
+    /// The block this checksum is calculated for.
+    pub fn block(&self) -> SaveBlock {
+        self.block
+    }
+
+    /// Adds the next bytes of the block.
+    pub fn update(&mut self, mut data: &[u8]) {
+        if self.pending_len > 0 {
+            let take = (4 - self.pending_len).min(data.len());
+            self.pending[self.pending_len..self.pending_len + take].copy_from_slice(&data[..take]);
+            self.pending_len += take;
+            data = &data[take..];
+
+            if self.pending_len < 4 {
+                return;
+            }
+
+            self.sum = self.sum.wrapping_add(u32::from_le_bytes(self.pending));
+            self.pending_len = 0;
+        }
+
+        let mut chunks = data.chunks_exact(4);
+        for chunk in &mut chunks {
+            self.sum = self
+                .sum
+                .wrapping_add(u32::from_le_bytes(chunk.try_into().unwrap())); // Safe, four bytes.
+        }
+
+        let rest = chunks.remainder();
+        self.pending[..rest.len()].copy_from_slice(rest);
+        self.pending_len = rest.len();
+    }
+
+    /// Returns the checksum, as stored in the save.
+    /// A trailing partial word is zero-extended.
+    pub fn finalize(self) -> [u8; 4] {
+        let mut last = [0; 4];
+        last[..self.pending_len].copy_from_slice(&self.pending[..self.pending_len]);
+
+        self.sum
+            .wrapping_add(u32::from_le_bytes(last))
+            .to_le_bytes()
+    }
+}
+
+/// Recalculates the checksums of a save file on disk, rewriting only the checksums.
+/// The blocks are streamed in small chunks, the file is never fully loaded.
+/// Mirrored and padded dumps are detected like `SkySave::from_slice` does,
+/// and the checksums of every copy of the image are fixed, see `SaveContainer`.
+pub fn fix_checksums_in_file<P: AsRef<Path>>(filename: P) -> Result<(), SaveError> {
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(filename)
+        .map_err(SaveError::Io)?;
+
+    let len = file.metadata().map_err(SaveError::Io)?.len() as usize;
+    if len < MIN_SAVE_LEN {
+        return Err(SaveError::InvalidSize);
+    }
+    let container = SaveContainer::detect_stream(&mut file, len).map_err(SaveError::Io)?;
+
+    let mut buf = [0u8; 0x1000];
+    for image in container.image_offsets() {
+        for block in [SaveBlock::Primary, SaveBlock::Backup, SaveBlock::Quicksave] {
+            let range = block.data_range();
+            let mut sum = BlockChecksum::new(block);
+
+            file.seek(SeekFrom::Start((image + range.start) as u64))
+                .map_err(SaveError::Io)?;
+            let mut left = range.len();
+            while left > 0 {
+                let len = left.min(buf.len());
+                file.read_exact(&mut buf[..len]).map_err(SaveError::Io)?;
+                sum.update(&buf[..len]);
+                left -= len;
+            }
+
+            file.seek(SeekFrom::Start(
+                (image + block.checksum_range().start) as u64,
+            ))
+            .map_err(SaveError::Io)?;
+            file.write_all(&sum.finalize()).map_err(SaveError::Io)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_block_checksum_matches_one_shot() {
+    let data: Vec<u8> = (0..MIN_SAVE_LEN).map(|i| (i * 7 + 3) as u8).collect();
+
+    for block in [SaveBlock::Primary, SaveBlock::Backup, SaveBlock::Quicksave] {
+        let mut sum = BlockChecksum::new(block);
+        for chunk in data[block.data_range()].chunks(13) {
+            sum.update(chunk);
+        }
+
+        assert_eq!(
+            sum.finalize(),
+            crate::save::checksum(&data, block.data_range())
+        );
+    }
+}
+
+#[test]
+fn test_fix_checksums_in_file_containers() {
+    use crate::fixture::TempPath;
+    use crate::save::fix_data_checksums;
+    use std::fs;
+
+    let mut image: Vec<u8> = (0..MIN_SAVE_LEN).map(|i| (i * 7 + 3) as u8).collect();
+    fix_data_checksums(&mut image);
+    let mut stale = image.clone();
+    for block in [SaveBlock::Primary, SaveBlock::Backup, SaveBlock::Quicksave] {
+        stale[block.checksum_range()].fill(0);
+    }
+
+    let containers = [
+        SaveContainer::Raw,
+        SaveContainer::Mirrored { copies: 2 },
+        SaveContainer::Padded {
+            offset: MIN_SAVE_LEN,
+            len: 0x40000,
+        },
+    ];
+    for container in containers {
+        let path = TempPath::new("fix_checksums.sav");
+        fs::write(&path, container.wrap(&stale)).unwrap();
+
+        fix_checksums_in_file(&path).unwrap();
+        assert_eq!(
+            fs::read(&path).unwrap(),
+            container.wrap(&image),
+            "{:?}",
+            container
+        );
+    }
+}
+
+#[test]
+fn test_fix_checksums_in_file_writes_in_place() {
+    use crate::fixture::TempPath;
+    use std::fs;
+
+    // A raw image with a DeSmuME style footer after it.
+    let mut data: Vec<u8> = (0..MIN_SAVE_LEN).map(|i| (i * 7 + 3) as u8).collect();
+    data.extend_from_slice(b"|-DESMUME SAVE-|");
+    let path = TempPath::new("fix_checksums_in_place.dsv");
+    fs::write(&path, &data).unwrap();
+    #[cfg(unix)]
+    let inode = std::os::unix::fs::MetadataExt::ino(&fs::metadata(&path).unwrap());
+
+    fix_checksums_in_file(&path).unwrap();
+    let fixed = fs::read(&path).unwrap();
+    #[cfg(unix)]
+    assert_eq!(
+        std::os::unix::fs::MetadataExt::ino(&fs::metadata(&path).unwrap()),
+        inode
+    );
+
+    // Only the checksums differ, everything else, the footer included, is byte-identical.
+    let checksums =
+        [SaveBlock::Primary, SaveBlock::Backup, SaveBlock::Quicksave].map(|b| b.checksum_range());
+    assert_eq!(fixed.len(), data.len());
+    for (i, (a, b)) in data.iter().zip(&fixed).enumerate() {
+        if !checksums.iter().any(|r| r.contains(&i)) {
+            assert_eq!(a, b, "byte {:#X}", i);
+        }
+    }
+    assert!(fixed.ends_with(b"|-DESMUME SAVE-|"));
+    assert!(crate::SkySave::from_slice(&fixed).unwrap().checksums_ok);
+}
@@ -0,0 +1,182 @@
+//! Detects the shape of flashcart and cart reader dumps.
+//!
+//! Some dumps are 256KiB or 512KiB, where the 128KiB save image is either mirrored across the whole file
+//! or placed in one window with the rest padded with `0xFF`.
+
+use crate::save::MIN_SAVE_LEN;
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// The dump sizes that may hold a mirrored or padded image.
+const CONTAINER_LENS: [usize; 2] = [0x40000, 0x80000];
+
+/// How the save image is laid out in the file it was loaded from.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum SaveContainer {
+    /// The file is the save image itself.
+    #[default]
+    Raw,
+    /// The file holds `copies` identical copies of the image.
+    Mirrored { copies: usize },
+    /// The file is `len` bytes of `0xFF`, with the image at `offset`.
+    Padded { offset: usize, len: usize },
+}
+
+impl SaveContainer {
+    /// Detects the layout of `data`.
+    /// Anything that isn't a recognized mirrored or padded dump is `Raw`.
+    pub fn detect(data: &[u8]) -> Self {
+        if !CONTAINER_LENS.contains(&data.len()) {
+            return SaveContainer::Raw;
+        }
+
+        let windows: Vec<&[u8]> = data.chunks(MIN_SAVE_LEN).collect();
+        if windows.iter().all(|w| *w == windows[0]) {
+            return SaveContainer::Mirrored {
+                copies: windows.len(),
+            };
+        }
+
+        let mut images = windows
+            .iter()
+            .enumerate()
+            .filter(|(_, w)| w.iter().any(|&b| b != 0xFF));
+        match (images.next(), images.next()) {
+            (Some((i, _)), None) => SaveContainer::Padded {
+                offset: i * MIN_SAVE_LEN,
+                len: data.len(),
+            },
+            _ => SaveContainer::Raw,
+        }
+    }
+
+    /// Detects the layout of the `len` byte file read by `reader`, like `detect`,
+    /// reading it in small chunks rather than all at once.
+    pub(crate) fn detect_stream<R: Read + Seek>(reader: &mut R, len: usize) -> io::Result<Self> {
+        if !CONTAINER_LENS.contains(&len) {
+            return Ok(SaveContainer::Raw);
+        }
+
+        let copies = len / MIN_SAVE_LEN;
+        let mut mirrored = true;
+        let mut non_padding = vec![false; copies];
+        let (mut first, mut other) = ([0; 0x1000], [0; 0x1000]);
+        for start in (0..MIN_SAVE_LEN).step_by(first.len()) {
+            for (window, non_padding) in non_padding.iter_mut().enumerate() {
+                let buf = if window == 0 { &mut first } else { &mut other };
+                reader.seek(SeekFrom::Start((window * MIN_SAVE_LEN + start) as u64))?;
+                reader.read_exact(buf)?;
+
+                *non_padding |= buf.iter().any(|&b| b != 0xFF);
+                if window > 0 {
+                    mirrored &= other == first;
+                }
+            }
+        }
+
+        if mirrored {
+            return Ok(SaveContainer::Mirrored { copies });
+        }
+        let mut images = non_padding.iter().enumerate().filter(|(_, &n)| n);
+        Ok(match (images.next(), images.next()) {
+            (Some((i, _)), None) => SaveContainer::Padded {
+                offset: i * MIN_SAVE_LEN,
+                len,
+            },
+            _ => SaveContainer::Raw,
+        })
+    }
+
+    /// The offsets of every copy of the save image in the file.
+    pub(crate) fn image_offsets(&self) -> Vec<usize> {
+        match *self {
+            SaveContainer::Raw => vec![0],
+            SaveContainer::Mirrored { copies } => (0..copies).map(|i| i * MIN_SAVE_LEN).collect(),
+            SaveContainer::Padded { offset, .. } => vec![offset],
+        }
+    }
+
+    /// Returns the save image held by `data`.
+    pub fn image<'a>(&self, data: &'a [u8]) -> &'a [u8] {
+        match *self {
+            SaveContainer::Raw => data,
+            SaveContainer::Mirrored { .. } => &data[..MIN_SAVE_LEN],
+            SaveContainer::Padded { offset, .. } => &data[offset..offset + MIN_SAVE_LEN],
+        }
+    }
+
+    /// The length of the file `wrap` builds around an image of `image_len` bytes.
+    pub fn file_len(&self, image_len: usize) -> usize {
+        match *self {
+            SaveContainer::Raw => image_len,
+            SaveContainer::Mirrored { copies } => image_len * copies,
+            SaveContainer::Padded { len, .. } => len,
+        }
+    }
+
+    /// Describes the layout around an image of `image_len` bytes,
+    /// raw images longer than a save carry trailing bytes, like a DeSmuME footer.
+    pub fn layout_name(&self, image_len: usize) -> String {
+        match *self {
+            SaveContainer::Raw if image_len > MIN_SAVE_LEN => format!(
+                "raw save image with {} trailing bytes",
+                image_len - MIN_SAVE_LEN
+            ),
+            SaveContainer::Raw => "raw save image".to_string(),
+            SaveContainer::Mirrored { copies } => format!("mirrored dump, {} copies", copies),
+            SaveContainer::Padded { offset, len } => {
+                format!("padded dump of {:#X} bytes, image at {:#X}", len, offset)
+            }
+        }
+    }
+
+    /// Rebuilds a file of this layout around `image`.
+    pub fn wrap(&self, image: &[u8]) -> Vec<u8> {
+        match *self {
+            SaveContainer::Raw => image.to_vec(),
+            SaveContainer::Mirrored { copies } => image.repeat(copies),
+            SaveContainer::Padded { offset, len } => {
+                let mut out = vec![0xFF; len];
+                out[offset..offset + image.len()].copy_from_slice(image);
+                out
+            }
+        }
+    }
+}
+
+#[test]
+fn test_detect_container() {
+    let image: Vec<u8> = (0..MIN_SAVE_LEN).map(|i| i as u8).collect();
+
+    let mirrored = image.repeat(4);
+    let container = SaveContainer::detect(&mirrored);
+    assert_eq!(container, SaveContainer::Mirrored { copies: 4 });
+    assert_eq!(container.image(&mirrored), image);
+    assert_eq!(container.wrap(&image), mirrored);
+    assert_eq!(container.file_len(image.len()), mirrored.len());
+
+    let mut padded = vec![0xFF; 0x40000];
+    padded[MIN_SAVE_LEN..].copy_from_slice(&image);
+    let container = SaveContainer::detect(&padded);
+    assert_eq!(
+        container,
+        SaveContainer::Padded {
+            offset: MIN_SAVE_LEN,
+            len: 0x40000
+        }
+    );
+    assert_eq!(container.image(&padded), image);
+    assert_eq!(container.wrap(&image), padded);
+    assert_eq!(container.file_len(image.len()), padded.len());
+
+    assert_eq!(SaveContainer::detect(&image), SaveContainer::Raw);
+
+    for data in [&mirrored, &padded, &image, &image.repeat(2)] {
+        let streamed =
+            SaveContainer::detect_stream(&mut io::Cursor::new(data), data.len()).unwrap();
+        assert_eq!(streamed, SaveContainer::detect(data));
+    }
+    assert_eq!(
+        SaveContainer::Raw.layout_name(MIN_SAVE_LEN + 0x7A),
+        "raw save image with 122 trailing bytes"
+    );
+}
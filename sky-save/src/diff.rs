@@ -0,0 +1,295 @@
+//! Lists the bytes of the active block that saving would change, and compares two saves.
+
+use crate::offsets::{active, general, stored};
+use crate::{
+    field_descriptors, ActivePokemon, FieldOwner, SaveBlock, SaveError, SkySave, Slot,
+    StoredPokemon, Value,
+};
+use bitvec::slice::BitSlice;
+use std::fmt::Display;
+use std::ops::Range;
+
+/// The general fields, as bit ranges relative to the start of the block.
+const GENERAL_FIELDS: [(&str, Range<usize>); 6] = [
+    (
+        "general::TEAM_NAME",
+        general::TEAM_NAME.start * 8..general::TEAM_NAME.end * 8,
+    ),
+    ("general::HELD_MONEY", general::HELD_MONEY_BITS.range()),
+    (
+        "general::SP_EPISODE_HELD_MONEY",
+        general::SP_EPISODE_HELD_MONEY_BITS.range(),
+    ),
+    ("general::STORED_MONEY", general::STORED_MONEY_BITS.range()),
+    (
+        "general::EXPLORER_RANK",
+        general::EXPLORER_RANK.start * 8..general::EXPLORER_RANK.end * 8,
+    ),
+    (
+        "general::NUMBER_OF_ADVENTURERS",
+        general::NUMBER_OF_ADVENTURERS.start * 8..general::NUMBER_OF_ADVENTURERS.end * 8,
+    ),
+];
+
+/// A run of bytes that saving would change.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AnnotatedChange {
+    /// The byte range in `SkySave::data`.
+    pub range: Range<usize>,
+    /// The field owning the first changed bit, such as `stored[12].level`, if it falls inside a known offset.
+    pub field: Option<String>,
+    pub old: Vec<u8>,
+    pub new: Vec<u8>,
+}
+
+impl Display for AnnotatedChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:#X}..{:#X} ({}): {:02X?} -> {:02X?}",
+            self.range.start,
+            self.range.end,
+            self.field.as_deref().unwrap_or("unknown"),
+            self.old,
+            self.new
+        )
+    }
+}
+
+/// Finds the field owning `bit`, relative to the start of the block.
+fn field_at(bit: usize) -> Option<String> {
+    if let Some((name, _)) = GENERAL_FIELDS.iter().find(|(_, r)| r.contains(&bit)) {
+        return Some(name.to_string());
+    }
+
+    let pokemon_field =
+        |kind: &str, region: Range<usize>, len: usize, fields: &[(&str, Range<usize>)]| {
+            if !region.contains(&bit) {
+                return None;
+            }
+
+            let idx = (bit - region.start) / len;
+            let rel = (bit - region.start) % len;
+            let field = fields.iter().find(|(_, r)| r.contains(&rel));
+            Some(match field {
+                Some((name, _)) => format!("{}[{}].{}", kind, idx, name),
+                None => format!("{}[{}]", kind, idx),
+            })
+        };
+
+    pokemon_field(
+        "stored",
+        stored::STORED_PKM_BITS,
+        stored::STORED_PKM_BIT_LEN,
+        StoredPokemon::FIELDS,
+    )
+    .or_else(|| {
+        pokemon_field(
+            "active",
+            active::ACTIVE_PKM_BITS,
+            active::ACTIVE_PKM_BIT_LEN,
+            ActivePokemon::FIELDS,
+        )
+    })
+}
+
+/// A general field with different values in two saves.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FieldDiff {
+    pub field: &'static str,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// How a stored or active slot differs between two saves.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SlotDiff {
+    /// The slot is only valid in the other save.
+    Added,
+    /// The slot is only valid in this save.
+    Removed,
+    /// The slot is valid in both saves, with these fields differing.
+    Changed(Vec<&'static str>),
+}
+
+/// The differences between two saves, see `SkySave::diff`.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct SaveDiff {
+    pub general: Vec<FieldDiff>,
+    pub stored: Vec<(usize, SlotDiff)>,
+    pub active: Vec<(usize, SlotDiff)>,
+}
+
+impl SaveDiff {
+    pub fn is_empty(&self) -> bool {
+        self.general.is_empty() && self.stored.is_empty() && self.active.is_empty()
+    }
+}
+
+/// Compares two slots field by field, empty slots are equal regardless of their contents.
+fn slot_diff(
+    (a_valid, a): (bool, &BitSlice<u8>),
+    (b_valid, b): (bool, &BitSlice<u8>),
+    fields: &[(&'static str, Range<usize>)],
+) -> Option<SlotDiff> {
+    match (a_valid, b_valid) {
+        (false, false) => None,
+        (false, true) => Some(SlotDiff::Added),
+        (true, false) => Some(SlotDiff::Removed),
+        (true, true) => {
+            let changed: Vec<_> = fields
+                .iter()
+                .filter(|(_, r)| a[r.clone()] != b[r.clone()])
+                .map(|(name, _)| *name)
+                .collect();
+            (!changed.is_empty()).then_some(SlotDiff::Changed(changed))
+        }
+    }
+}
+
+impl SkySave {
+    /// Compares the loaded values of this save with `other`, this save being the old side.
+    /// Unsaved edits of both saves are included, the raw data isn't compared.
+    pub fn diff(&self, other: &SkySave) -> SaveDiff {
+        let general = field_descriptors()
+            .into_iter()
+            .filter(|d| d.owner == FieldOwner::General)
+            .filter_map(|d| {
+                let old = d.get(self, Slot::None).ok()?;
+                let new = d.get(other, Slot::None).ok()?;
+                (old != new).then_some(FieldDiff {
+                    field: d.name,
+                    old,
+                    new,
+                })
+            })
+            .collect();
+
+        let stored = self
+            .stored_pokemon
+            .iter()
+            .zip(other.stored_pokemon.iter())
+            .enumerate()
+            .filter_map(|(i, (a, b))| {
+                let (a_bits, b_bits) = (a.to_bits(), b.to_bits());
+                slot_diff(
+                    (a.valid, a_bits.as_bitslice()),
+                    (b.valid, b_bits.as_bitslice()),
+                    StoredPokemon::FIELDS,
+                )
+                .map(|d| (i, d))
+            })
+            .collect();
+
+        let active = self
+            .active_pokemon
+            .iter()
+            .zip(&other.active_pokemon)
+            .enumerate()
+            .filter_map(|(i, (a, b))| {
+                let (a_bits, b_bits) = (a.to_bits(), b.to_bits());
+                slot_diff(
+                    (a.valid, a_bits.as_bitslice()),
+                    (b.valid, b_bits.as_bitslice()),
+                    ActivePokemon::FIELDS,
+                )
+                .map(|d| (i, d))
+            })
+            .collect();
+
+        SaveDiff {
+            general,
+            stored,
+            active,
+        }
+    }
+
+    /// Lists the byte ranges of the active block that `save` would change, without touching `data`.
+    /// Contiguous changed bytes are grouped while they belong to the same field.
+    /// Fails if a value doesn't fit in its field, see `save`.
+    pub fn pending_changes(&self) -> Result<Vec<AnnotatedChange>, SaveError> {
+        let mut scratch = self.data.clone();
+        self.store(&mut scratch)?;
+
+        let block = SaveBlock::from(self.active_save_block).range();
+        let mut changes: Vec<AnnotatedChange> = Vec::new();
+
+        for offset in block.clone() {
+            let (old, new) = (self.data[offset], scratch[offset]);
+            let diff = old ^ new;
+            if diff == 0 {
+                continue;
+            }
+
+            let bit = (offset - block.start) * 8 + diff.trailing_zeros() as usize;
+            let field = field_at(bit);
+
+            match changes.last_mut() {
+                Some(last) if last.range.end == offset && last.field == field => {
+                    last.range.end += 1;
+                    last.old.push(old);
+                    last.new.push(new);
+                }
+                _ => changes.push(AnnotatedChange {
+                    range: offset..offset + 1,
+                    field,
+                    old: vec![old],
+                    new: vec![new],
+                }),
+            }
+        }
+
+        Ok(changes)
+    }
+}
+
+#[test]
+fn test_pending_changes() {
+    let mut save = SkySave::from_slice(vec![0; 0x20000]).unwrap();
+    assert!(save.pending_changes().unwrap().is_empty());
+
+    save.general.held_money = 1;
+    save.stored_pokemon[12].level = 5;
+
+    let changes = save.pending_changes().unwrap();
+    let fields: Vec<_> = changes.iter().map(|c| c.field.as_deref()).collect();
+    assert_eq!(
+        fields,
+        [Some("stored[12].level"), Some("general::HELD_MONEY")]
+    );
+    assert_eq!(changes[1].range, 0x990C..0x990D);
+    assert_eq!(changes[1].new, [0x40]);
+}
+
+#[test]
+fn test_diff() {
+    let old = SkySave::from_slice(vec![0; 0x20000]).unwrap();
+    let mut new = SkySave::from_slice(vec![0; 0x20000]).unwrap();
+    assert!(old.diff(&new).is_empty());
+
+    new.general.held_money = 50;
+    new.stored_pokemon[6].valid = true;
+    new.stored_pokemon[9].level = 3;
+    new.active_pokemon[1].valid = true;
+
+    let mut changed = SkySave::from_slice(vec![0; 0x20000]).unwrap();
+    changed.active_pokemon[1].valid = true;
+    changed.active_pokemon[1].level = 7;
+
+    let diff = old.diff(&new);
+    assert_eq!(
+        diff.general,
+        [FieldDiff {
+            field: "held_money",
+            old: Value::Uint(0),
+            new: Value::Uint(50)
+        }]
+    );
+    assert_eq!(diff.stored, [(6, SlotDiff::Added)]);
+    assert_eq!(diff.active, [(1, SlotDiff::Added)]);
+    assert_eq!(new.diff(&old).active, [(1, SlotDiff::Removed)]);
+    assert_eq!(
+        new.diff(&changed).active,
+        [(1, SlotDiff::Changed(vec!["level"]))]
+    );
+}
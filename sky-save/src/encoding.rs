@@ -14,6 +14,7 @@ use bitvec::field::BitField;
 use bitvec::order::Lsb0;
 use bitvec::prelude::BitSlice;
 use std::fmt::Display;
+use std::ops::RangeInclusive;
 
 /// A single PMD-encoded character.
 /// Holds both the PMD encoded byte and its UTF-8 representation.
@@ -41,21 +42,29 @@ impl PmdChar {
 
     /// Converts a PMD character to its sequence representation.
     pub fn to_sequence(&self) -> String {
-        byte_to_pmd_seq(self.pmd).unwrap().to_string()
+        byte_to_pmd_seq(self.pmd).to_string()
     }
 }
 
 /// Converts a PMD-encoded byte to a PMD character.
+/// The byte is kept as is, even if another byte has the same sequence, so strings round-trip exactly.
 impl From<u8> for PmdChar {
     fn from(value: u8) -> Self {
-        let pmd = byte_to_pmd_seq(value).unwrap();
-        PmdChar::from_sequence(pmd).unwrap()
+        let seq = byte_to_pmd_seq(value);
+        let utf8 = match seq.chars().next() {
+            Some('[') | None => pmd_seq_to_byte(seq).unwrap_or(value) as char,
+            Some(c) => c,
+        };
+
+        PmdChar { pmd: value, utf8 }
     }
 }
 
 /// A string represented by the PMD character encoding, backed by an `ArrayVec`.
 /// Save file strings (team names, Pokémon names) have 10 byte memory location.
 /// The game stops displaying the strings when it reaches a null byte.
+/// Strings loaded from a save keep all 10 bytes, including leftovers after the first null byte,
+/// so they are written back unchanged unless replaced.
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct PmdString(ArrayVec<PmdChar, 10>);
 
@@ -80,6 +89,77 @@ impl PmdString {
             })
     }
 
+    /// Builds the name the game gives a Pokémon of the species `species`.
+    /// Characters without a PMD representation are skipped and the name is cut at 10 characters.
+    ///
+    /// The crate has no species table yet, so `species_name` looks up the name of a species ID.
+    /// Returns `None` if it doesn't know the species.
+    pub fn default_name_for<'a>(
+        species: u16,
+        species_name: impl FnOnce(u16) -> Option<&'a str>,
+    ) -> Option<Self> {
+        let mut result = PmdString::new();
+        let mut buf = [0; 4];
+
+        for c in species_name(species)?.chars() {
+            if result.0.is_full() {
+                break;
+            }
+
+            if let Ok(pmd) = pmd_seq_to_byte(c.encode_utf8(&mut buf)) {
+                result.0.push(PmdChar { pmd, utf8: c });
+            }
+        }
+
+        Some(result)
+    }
+
+    /// Parses as much of a sequence of PMD characters as fits in 10 bytes.
+    /// Special sequences are never split, and invalid characters or sequences are skipped.
+    /// Returns the string and `true` if any part of the input was left out.
+    pub fn truncate_to_fit(value: &str) -> (Self, bool) {
+        let mut result = PmdString::new();
+        let mut truncated = false;
+
+        for c in pmd_chars(value) {
+            match c {
+                Ok(c) if !result.0.is_full() => result.0.push(c),
+                _ => truncated = true,
+            }
+        }
+
+        (result, truncated)
+    }
+
+    /// Converts arbitrary text to a `PmdString`, replacing characters the encoding lacks with close equivalents.
+    /// Full-width forms become ASCII, accented letters missing from the table lose their accent,
+    /// and combining marks are dropped in favor of their base letter, see `TRANSLITERATIONS`.
+    /// The input is plain text, square brackets don't start special sequences.
+    ///
+    /// Returns the string and the characters that had no equivalent or didn't fit in 10 bytes.
+    pub fn from_str_lossy(value: &str) -> (Self, Vec<char>) {
+        let mut result = PmdString::new();
+        let mut dropped = Vec::new();
+
+        let mut chars = value.chars().peekable();
+        while let Some(mut c) = chars.next() {
+            if COMBINING_MARKS.contains(&c) {
+                continue;
+            }
+            if let Some(composed) = chars.peek().and_then(|&mark| compose(c, mark)) {
+                c = composed;
+                chars.next();
+            }
+
+            match transliterate(c) {
+                Some(pmd) if !result.0.is_full() => result.0.push(pmd),
+                _ => dropped.push(c),
+            }
+        }
+
+        (result, dropped)
+    }
+
     pub fn to_string_until_nul(&self) -> String {
         self.0
             .iter()
@@ -103,10 +183,11 @@ impl Default for PmdString {
 }
 
 /// Converts a PMD-encoded byte slice to a `PmdString`.
+/// Bytes past the tenth are dropped.
 impl From<&[u8]> for PmdString {
     fn from(value: &[u8]) -> Self {
         let mut result = PmdString::new();
-        for &b in value {
+        for &b in value.iter().take(result.0.capacity()) {
             result.0.push(PmdChar::from(b));
         }
 
@@ -116,10 +197,12 @@ impl From<&[u8]> for PmdString {
 
 impl From<&BitSlice<u8, Lsb0>> for PmdString {
     fn from(value: &BitSlice<u8, Lsb0>) -> Self {
-        let bytes: u128 = value.load_le();
-        let bytes = bytes.to_le_bytes();
+        let mut bytes = [0u8; 10];
+        for (b, chunk) in bytes.iter_mut().zip(value.chunks(8)) {
+            *b = chunk.load_le();
+        }
 
-        PmdString::from(&bytes.as_slice()[0..10])
+        PmdString::from(bytes.as_slice())
     }
 }
 
@@ -138,558 +221,177 @@ impl TryFrom<&str> for PmdString {
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         let mut result = PmdString::new();
-        let mut chars_iter = value.chars().peekable();
 
-        while let Some(c) = chars_iter.next() {
-            match c {
-                '[' => {
-                    let seq: String = chars_iter.by_ref().take_while(|&c| c != ']').collect();
-                    let pmd = pmd_seq_to_byte(&format!("[{}]", seq))?;
-                    result
-                        .0
-                        .try_push(PmdChar {
-                            utf8: pmd as char,
-                            pmd,
-                        })
-                        .map_err(|_| EncodingError::InvalidPmdStringLen)?;
-                }
-                _ => {
-                    let mut buf = [0; 4];
-                    let seq = c.encode_utf8(&mut buf);
-                    let pmd = pmd_seq_to_byte(seq)?;
-                    result
-                        .0
-                        .try_push(PmdChar { utf8: c, pmd })
-                        .map_err(|_| EncodingError::InvalidPmdStringLen)?;
-                }
-            }
+        for c in pmd_chars(value) {
+            result
+                .0
+                .try_push(c?)
+                .map_err(|_| EncodingError::InvalidPmdStringLen)?;
         }
 
         Ok(result)
     }
 }
 
-fn pmd_seq_to_byte(s: &str) -> Result<u8, EncodingError> {
-    match s {
-        "[END]" => Ok(0x00),
-        "[$01]" => Ok(0x01),
-        "[$02]" => Ok(0x02),
-        "[$03]" => Ok(0x03),
-        "[$04]" => Ok(0x04),
-        "[$05]" => Ok(0x05),
-        "[$06]" => Ok(0x06),
-        "[$07]" => Ok(0x07),
-        "[$08]" => Ok(0x08),
-        "[$09]" => Ok(0x09),
-        "[$0A]" => Ok(0x0A),
-        "[$0B]" => Ok(0x0B),
-        "[$0C]" => Ok(0x0C),
-        "[$0D]" => Ok(0x0D),
-        "[$0E]" => Ok(0x0E),
-        "[$0F]" => Ok(0x0F),
-        "[$10]" => Ok(0x10),
-        "[$11]" => Ok(0x11),
-        "[$12]" => Ok(0x12),
-        "[$13]" => Ok(0x13),
-        "[$14]" => Ok(0x14),
-        "[$15]" => Ok(0x15),
-        "[$16]" => Ok(0x16),
-        "[$17]" => Ok(0x17),
-        "[$18]" => Ok(0x18),
-        "[$19]" => Ok(0x19),
-        "[$1A]" => Ok(0x1A),
-        "[$1B]" => Ok(0x1B),
-        "[$1C]" => Ok(0x1C),
-        "[$1D]" => Ok(0x1D),
-        "[$1E]" => Ok(0x1E),
-        "[$1F]" => Ok(0x1F),
-        " " => Ok(0x20),
-        "!" => Ok(0x21),
-        "\"" => Ok(0x22),
-        "#" => Ok(0x23),
-        "$" => Ok(0x24),
-        "%" => Ok(0x25),
-        "&" => Ok(0x26),
-        "'" => Ok(0x27),
-        "(" => Ok(0x28),
-        ")" => Ok(0x29),
-        "*" => Ok(0x2A),
-        "+" => Ok(0x2B),
-        "," => Ok(0x2C),
-        "-" => Ok(0x2D),
-        "." => Ok(0x2E),
-        "/" => Ok(0x2F),
-        "0" => Ok(0x30),
-        "1" => Ok(0x31),
-        "2" => Ok(0x32),
-        "3" => Ok(0x33),
-        "4" => Ok(0x34),
-        "5" => Ok(0x35),
-        "6" => Ok(0x36),
-        "7" => Ok(0x37),
-        "8" => Ok(0x38),
-        "9" => Ok(0x39),
-        ":" => Ok(0x3A),
-        ";" => Ok(0x3B),
-        "<" => Ok(0x3C),
-        "=" => Ok(0x3D),
-        ">" => Ok(0x3E),
-        "?" => Ok(0x3F),
-        "@" => Ok(0x40),
-        "A" => Ok(0x41),
-        "B" => Ok(0x42),
-        "C" => Ok(0x43),
-        "D" => Ok(0x44),
-        "E" => Ok(0x45),
-        "F" => Ok(0x46),
-        "G" => Ok(0x47),
-        "H" => Ok(0x48),
-        "I" => Ok(0x49),
-        "J" => Ok(0x4A),
-        "K" => Ok(0x4B),
-        "L" => Ok(0x4C),
-        "M" => Ok(0x4D),
-        "N" => Ok(0x4E),
-        "O" => Ok(0x4F),
-        "P" => Ok(0x50),
-        "Q" => Ok(0x51),
-        "R" => Ok(0x52),
-        "S" => Ok(0x53),
-        "T" => Ok(0x54),
-        "U" => Ok(0x55),
-        "V" => Ok(0x56),
-        "W" => Ok(0x57),
-        "X" => Ok(0x58),
-        "Y" => Ok(0x59),
-        "Z" => Ok(0x5A),
-        "[$5B]" => Ok(0x5B),
-        "\\" => Ok(0x5C),
-        "]" => Ok(0x5D),
-        "^" => Ok(0x5E),
-        "_" => Ok(0x5F),
-        "`" => Ok(0x60),
-        "a" => Ok(0x61),
-        "b" => Ok(0x62),
-        "c" => Ok(0x63),
-        "d" => Ok(0x64),
-        "e" => Ok(0x65),
-        "f" => Ok(0x66),
-        "g" => Ok(0x67),
-        "h" => Ok(0x68),
-        "i" => Ok(0x69),
-        "j" => Ok(0x6A),
-        "k" => Ok(0x6B),
-        "l" => Ok(0x6C),
-        "m" => Ok(0x6D),
-        "n" => Ok(0x6E),
-        "o" => Ok(0x6F),
-        "p" => Ok(0x70),
-        "q" => Ok(0x71),
-        "r" => Ok(0x72),
-        "s" => Ok(0x73),
-        "t" => Ok(0x74),
-        "u" => Ok(0x75),
-        "v" => Ok(0x76),
-        "w" => Ok(0x77),
-        "x" => Ok(0x78),
-        "y" => Ok(0x79),
-        "z" => Ok(0x7A),
-        "{" => Ok(0x7B),
-        "|" => Ok(0x7C),
-        "}" => Ok(0x7D),
-        "[$7E]" => Ok(0x7E),
-        "[$7F]" => Ok(0x7F),
-        "€" => Ok(0x80),
-        "[$81]" => Ok(0x81),
-        "[$82]" => Ok(0x82),
-        "[$83]" => Ok(0x83),
-        "[$84]" => Ok(0x84),
-        "…" => Ok(0x85),
-        "†" => Ok(0x86),
-        "[$87]" => Ok(0x87),
-        "ˆ" => Ok(0x88),
-        "‰" => Ok(0x89),
-        "Š" => Ok(0x8A),
-        "‹" => Ok(0x8B),
-        "Œ" => Ok(0x8C),
-        "[e]" => Ok(0x8D),
-        "Ž" => Ok(0x8E),
-        "[è]" => Ok(0x8F),
-        // "•" => Ok(0x90), // Duplicate
-        "‘" => Ok(0x91),
-        "’" => Ok(0x92),
-        "“" => Ok(0x93),
-        "”" => Ok(0x94),
-        // "•" => Ok(0x95), // Duplicate
-        "[er]" => Ok(0x96),
-        "[re]" => Ok(0x97),
-        "~" => Ok(0x98),
-        "™" => Ok(0x99),
-        "š" => Ok(0x9A),
-        "›" => Ok(0x9B),
-        "œ" => Ok(0x9C),
-        "•" => Ok(0x9D),
-        "ž" => Ok(0x9E),
-        "Ÿ" => Ok(0x9F),
-        // " " => Ok(0xA0), // Duplicate
-        "¡" => Ok(0xA1),
-        "¢" => Ok(0xA2),
-        "£" => Ok(0xA3),
-        "¤" => Ok(0xA4),
-        "¥" => Ok(0xA5),
-        "¦" => Ok(0xA6),
-        "§" => Ok(0xA7),
-        "¨" => Ok(0xA8),
-        "©" => Ok(0xA9),
-        "ª" => Ok(0xAA),
-        "«" => Ok(0xAB),
-        "¬" => Ok(0xAC),
-        "\u{00AD}" => Ok(0xAD),
-        "®" => Ok(0xAE),
-        "¯" => Ok(0xAF),
-        "°" => Ok(0xB0),
-        "±" => Ok(0xB1),
-        "²" => Ok(0xB2),
-        "³" => Ok(0xB3),
-        "´" => Ok(0xB4),
-        "µ" => Ok(0xB5),
-        "¶" => Ok(0xB6),
-        "„" => Ok(0xB7),
-        "‚" => Ok(0xB8),
-        "¹" => Ok(0xB9),
-        "º" => Ok(0xBA),
-        "»" => Ok(0xBB),
-        "←" => Ok(0xBC),
-        "♂" => Ok(0xBD),
-        "♀" => Ok(0xBE),
-        "¿" => Ok(0xBF),
-        "À" => Ok(0xC0),
-        "Á" => Ok(0xC1),
-        "Â" => Ok(0xC2),
-        "Ã" => Ok(0xC3),
-        "Ä" => Ok(0xC4),
-        "Å" => Ok(0xC5),
-        "Æ" => Ok(0xC6),
-        "Ç" => Ok(0xC7),
-        "È" => Ok(0xC8),
-        "É" => Ok(0xC9),
-        "Ê" => Ok(0xCA),
-        "Ë" => Ok(0xCB),
-        "Ì" => Ok(0xCC),
-        "Í" => Ok(0xCD),
-        "Î" => Ok(0xCE),
-        "Ï" => Ok(0xCF),
-        "Ð" => Ok(0xD0),
-        "Ñ" => Ok(0xD1),
-        "Ò" => Ok(0xD2),
-        "Ó" => Ok(0xD3),
-        "Ô" => Ok(0xD4),
-        "Õ" => Ok(0xD5),
-        "Ö" => Ok(0xD6),
-        "×" => Ok(0xD7),
-        "Ø" => Ok(0xD8),
-        "Ù" => Ok(0xD9),
-        "Ú" => Ok(0xDA),
-        "Û" => Ok(0xDB),
-        "Ü" => Ok(0xDC),
-        "Ý" => Ok(0xDD),
-        "Þ" => Ok(0xDE),
-        "ß" => Ok(0xDF),
-        "à" => Ok(0xE0),
-        "á" => Ok(0xE1),
-        "â" => Ok(0xE2),
-        "ã" => Ok(0xE3),
-        "ä" => Ok(0xE4),
-        "å" => Ok(0xE5),
-        "æ" => Ok(0xE6),
-        "ç" => Ok(0xE7),
-        "è" => Ok(0xE8),
-        "é" => Ok(0xE9),
-        "ê" => Ok(0xEA),
-        "ë" => Ok(0xEB),
-        "ì" => Ok(0xEC),
-        "í" => Ok(0xED),
-        "î" => Ok(0xEE),
-        "ï" => Ok(0xEF),
-        "ð" => Ok(0xF0),
-        "ñ" => Ok(0xF1),
-        "ò" => Ok(0xF2),
-        "ó" => Ok(0xF3),
-        "ô" => Ok(0xF4),
-        "õ" => Ok(0xF5),
-        "ö" => Ok(0xF6),
-        "÷" => Ok(0xF7),
-        "ø" => Ok(0xF8),
-        "ù" => Ok(0xF9),
-        "ú" => Ok(0xFA),
-        "û" => Ok(0xFB),
-        "ü" => Ok(0xFC),
-        "ý" => Ok(0xFD),
-        "þ" => Ok(0xFE),
-        "ÿ" => Ok(0xFF),
-        _ => Err(EncodingError::InvalidPmdCharacter(s.to_string())),
-    }
+/// Splits a sequence of PMD characters into `PmdChar`s, one per character or special sequence.
+fn pmd_chars(value: &str) -> impl Iterator<Item = Result<PmdChar, EncodingError>> + '_ {
+    let mut chars_iter = value.chars();
+
+    std::iter::from_fn(move || {
+        let c = chars_iter.next()?;
+        let parsed = match c {
+            '[' => {
+                let seq: String = chars_iter.by_ref().take_while(|&c| c != ']').collect();
+                pmd_seq_to_byte(&format!("[{}]", seq)).map(|pmd| PmdChar {
+                    utf8: pmd as char,
+                    pmd,
+                })
+            }
+            _ => {
+                let mut buf = [0; 4];
+                let seq = c.encode_utf8(&mut buf);
+                pmd_seq_to_byte(seq).map(|pmd| PmdChar { utf8: c, pmd })
+            }
+        };
+
+        Some(parsed)
+    })
+}
+
+/// The canonical Western PMD character table, indexed by byte.
+/// Each entry is a single character, or a special sequence wrapped in square brackets.
+#[rustfmt::skip]
+pub const PMD_CHAR_TABLE: [&str; 256] = [
+    "[END]", "[$01]", "[$02]", "[$03]", "[$04]", "[$05]", "[$06]", "[$07]",
+    "[$08]", "[$09]", "[$0A]", "[$0B]", "[$0C]", "[$0D]", "[$0E]", "[$0F]",
+    "[$10]", "[$11]", "[$12]", "[$13]", "[$14]", "[$15]", "[$16]", "[$17]",
+    "[$18]", "[$19]", "[$1A]", "[$1B]", "[$1C]", "[$1D]", "[$1E]", "[$1F]",
+    " ", "!", "\"", "#", "$", "%", "&", "'",
+    "(", ")", "*", "+", ",", "-", ".", "/",
+    "0", "1", "2", "3", "4", "5", "6", "7",
+    "8", "9", ":", ";", "<", "=", ">", "?",
+    "@", "A", "B", "C", "D", "E", "F", "G",
+    "H", "I", "J", "K", "L", "M", "N", "O",
+    "P", "Q", "R", "S", "T", "U", "V", "W",
+    "X", "Y", "Z", "[$5B]", "\\", "]", "^", "_",
+    "`", "a", "b", "c", "d", "e", "f", "g",
+    "h", "i", "j", "k", "l", "m", "n", "o",
+    "p", "q", "r", "s", "t", "u", "v", "w",
+    "x", "y", "z", "{", "|", "}", "[$7E]", "[$7F]",
+    "€", "[$81]", "[$82]", "[$83]", "[$84]", "…", "†", "[$87]",
+    "ˆ", "‰", "Š", "‹", "Œ", "[e]", "Ž", "[è]",
+    "•", "‘", "’", "“", "”", "•", "[er]", "[re]",
+    "~", "™", "š", "›", "œ", "•", "ž", "Ÿ",
+    " ", "¡", "¢", "£", "¤", "¥", "¦", "§",
+    "¨", "©", "ª", "«", "¬", "\u{00AD}", "®", "¯",
+    "°", "±", "²", "³", "´", "µ", "¶", "„",
+    "‚", "¹", "º", "»", "←", "♂", "♀", "¿",
+    "À", "Á", "Â", "Ã", "Ä", "Å", "Æ", "Ç",
+    "È", "É", "Ê", "Ë", "Ì", "Í", "Î", "Ï",
+    "Ð", "Ñ", "Ò", "Ó", "Ô", "Õ", "Ö", "×",
+    "Ø", "Ù", "Ú", "Û", "Ü", "Ý", "Þ", "ß",
+    "à", "á", "â", "ã", "ä", "å", "æ", "ç",
+    "è", "é", "ê", "ë", "ì", "í", "î", "ï",
+    "ð", "ñ", "ò", "ó", "ô", "õ", "ö", "÷",
+    "ø", "ù", "ú", "û", "ü", "ý", "þ", "ÿ",
+];
+
+/// Characters missing from `PMD_CHAR_TABLE` and the character `PmdString::from_str_lossy` uses instead.
+#[rustfmt::skip]
+pub const TRANSLITERATIONS: [(char, char); 13] = [
+    ('\u{00A0}', ' '), ('\u{3000}', ' '), ('\u{00B7}', '•'),
+    ('\u{2010}', '-'), ('\u{2011}', '-'), ('\u{2013}', '-'), ('\u{2014}', '-'), ('\u{2212}', '-'),
+    ('\u{201B}', '\''), ('\u{2032}', '\''), ('\u{2033}', '"'),
+    ('\u{00B8}', ','), ('\u{0131}', 'i'),
+];
+
+/// The full-width forms of `!` to `~`, which map to ASCII by subtracting `FULLWIDTH_OFFSET`.
+const FULLWIDTH_FORMS: RangeInclusive<char> = '\u{FF01}'..='\u{FF5E}';
+const FULLWIDTH_OFFSET: u32 = 0xFEE0;
+
+/// The base letter of each character in Latin Extended-A, U+0100 to U+017F, by offset.
+/// `-` marks characters without one, or already in `PMD_CHAR_TABLE`.
+const LATIN_EXTENDED_A_BASES: &str = concat!(
+    "AaAaAaCcCcCcCcDd",
+    "DdEeEeEeEeEeGgGg",
+    "GgGgHhHhIiIiIiIi",
+    "Ii--JjKk-LlLlLlL",
+    "lLlNnNnNn---OoOo",
+    "Oo--RrRrRrSsSsSs",
+    "--TtTtTtUuUuUuUu",
+    "UuUuWwYy-ZzZz--s",
+);
+
+/// Combining diacritical marks, dropped by `PmdString::from_str_lossy` so the base letter is kept alone
+/// unless `COMPOSITIONS` has a precomposed character for the pair.
+const COMBINING_MARKS: RangeInclusive<char> = '\u{0300}'..='\u{036F}';
+
+/// The combining marks with precomposed Latin-1 letters, as the mark, its base letters
+/// and the composed letters in the same order.
+#[rustfmt::skip]
+const COMPOSITIONS: [(char, &str, &str); 7] = [
+    ('\u{0300}', "AEIOUaeiou", "ÀÈÌÒÙàèìòù"),
+    ('\u{0301}', "AEIOUYaeiouy", "ÁÉÍÓÚÝáéíóúý"),
+    ('\u{0302}', "AEIOUaeiou", "ÂÊÎÔÛâêîôû"),
+    ('\u{0303}', "ANOano", "ÃÑÕãñõ"),
+    ('\u{0308}', "AEIOUaeiouy", "ÄËÏÖÜäëïöüÿ"),
+    ('\u{030A}', "Aa", "Åå"),
+    ('\u{0327}', "Cc", "Çç"),
+];
+
+/// The precomposed form of `base` followed by the combining `mark`, if it's in `COMPOSITIONS`.
+fn compose(base: char, mark: char) -> Option<char> {
+    let (_, bases, composed) = COMPOSITIONS.iter().find(|&&(m, _, _)| m == mark)?;
+    let i = bases.chars().position(|b| b == base)?;
+    composed.chars().nth(i)
 }
 
-fn byte_to_pmd_seq(byte: u8) -> Result<&'static str, EncodingError> {
-    match byte {
-        0x00 => Ok("[END]"),
-        0x01 => Ok("[$01]"),
-        0x02 => Ok("[$02]"),
-        0x03 => Ok("[$03]"),
-        0x04 => Ok("[$04]"),
-        0x05 => Ok("[$05]"),
-        0x06 => Ok("[$06]"),
-        0x07 => Ok("[$07]"),
-        0x08 => Ok("[$08]"),
-        0x09 => Ok("[$09]"),
-        0x0A => Ok("[$0A]"),
-        0x0B => Ok("[$0B]"),
-        0x0C => Ok("[$0C]"),
-        0x0D => Ok("[$0D]"),
-        0x0E => Ok("[$0E]"),
-        0x0F => Ok("[$0F]"),
-        0x10 => Ok("[$10]"),
-        0x11 => Ok("[$11]"),
-        0x12 => Ok("[$12]"),
-        0x13 => Ok("[$13]"),
-        0x14 => Ok("[$14]"),
-        0x15 => Ok("[$15]"),
-        0x16 => Ok("[$16]"),
-        0x17 => Ok("[$17]"),
-        0x18 => Ok("[$18]"),
-        0x19 => Ok("[$19]"),
-        0x1A => Ok("[$1A]"),
-        0x1B => Ok("[$1B]"),
-        0x1C => Ok("[$1C]"),
-        0x1D => Ok("[$1D]"),
-        0x1E => Ok("[$1E]"),
-        0x1F => Ok("[$1F]"),
-        0x20 => Ok(" "),
-        0x21 => Ok("!"),
-        0x22 => Ok("\""),
-        0x23 => Ok("#"),
-        0x24 => Ok("$"),
-        0x25 => Ok("%"),
-        0x26 => Ok("&"),
-        0x27 => Ok("'"),
-        0x28 => Ok("("),
-        0x29 => Ok(")"),
-        0x2A => Ok("*"),
-        0x2B => Ok("+"),
-        0x2C => Ok(","),
-        0x2D => Ok("-"),
-        0x2E => Ok("."),
-        0x2F => Ok("/"),
-        0x30 => Ok("0"),
-        0x31 => Ok("1"),
-        0x32 => Ok("2"),
-        0x33 => Ok("3"),
-        0x34 => Ok("4"),
-        0x35 => Ok("5"),
-        0x36 => Ok("6"),
-        0x37 => Ok("7"),
-        0x38 => Ok("8"),
-        0x39 => Ok("9"),
-        0x3A => Ok(":"),
-        0x3B => Ok(";"),
-        0x3C => Ok("<"),
-        0x3D => Ok("="),
-        0x3E => Ok(">"),
-        0x3F => Ok("?"),
-        0x40 => Ok("@"),
-        0x41 => Ok("A"),
-        0x42 => Ok("B"),
-        0x43 => Ok("C"),
-        0x44 => Ok("D"),
-        0x45 => Ok("E"),
-        0x46 => Ok("F"),
-        0x47 => Ok("G"),
-        0x48 => Ok("H"),
-        0x49 => Ok("I"),
-        0x4A => Ok("J"),
-        0x4B => Ok("K"),
-        0x4C => Ok("L"),
-        0x4D => Ok("M"),
-        0x4E => Ok("N"),
-        0x4F => Ok("O"),
-        0x50 => Ok("P"),
-        0x51 => Ok("Q"),
-        0x52 => Ok("R"),
-        0x53 => Ok("S"),
-        0x54 => Ok("T"),
-        0x55 => Ok("U"),
-        0x56 => Ok("V"),
-        0x57 => Ok("W"),
-        0x58 => Ok("X"),
-        0x59 => Ok("Y"),
-        0x5A => Ok("Z"),
-        0x5B => Ok("[$5B]"),
-        0x5C => Ok("\\"),
-        0x5D => Ok("]"),
-        0x5E => Ok("^"),
-        0x5F => Ok("_"),
-        0x60 => Ok("`"),
-        0x61 => Ok("a"),
-        0x62 => Ok("b"),
-        0x63 => Ok("c"),
-        0x64 => Ok("d"),
-        0x65 => Ok("e"),
-        0x66 => Ok("f"),
-        0x67 => Ok("g"),
-        0x68 => Ok("h"),
-        0x69 => Ok("i"),
-        0x6A => Ok("j"),
-        0x6B => Ok("k"),
-        0x6C => Ok("l"),
-        0x6D => Ok("m"),
-        0x6E => Ok("n"),
-        0x6F => Ok("o"),
-        0x70 => Ok("p"),
-        0x71 => Ok("q"),
-        0x72 => Ok("r"),
-        0x73 => Ok("s"),
-        0x74 => Ok("t"),
-        0x75 => Ok("u"),
-        0x76 => Ok("v"),
-        0x77 => Ok("w"),
-        0x78 => Ok("x"),
-        0x79 => Ok("y"),
-        0x7A => Ok("z"),
-        0x7B => Ok("{"),
-        0x7C => Ok("|"),
-        0x7D => Ok("}"),
-        0x7E => Ok("[$7E]"),
-        0x7F => Ok("[$7F]"),
-        0x80 => Ok("€"),
-        0x81 => Ok("[$81]"),
-        0x82 => Ok("[$82]"),
-        0x83 => Ok("[$83]"),
-        0x84 => Ok("[$84]"),
-        0x85 => Ok("…"),
-        0x86 => Ok("†"),
-        0x87 => Ok("[$87]"),
-        0x88 => Ok("ˆ"),
-        0x89 => Ok("‰"),
-        0x8A => Ok("Š"),
-        0x8B => Ok("‹"),
-        0x8C => Ok("Œ"),
-        0x8D => Ok("[e]"),
-        0x8E => Ok("Ž"),
-        0x8F => Ok("[è]"),
-        0x90 => Ok("•"),
-        0x91 => Ok("‘"),
-        0x92 => Ok("’"),
-        0x93 => Ok("“"),
-        0x94 => Ok("”"),
-        0x95 => Ok("•"),
-        0x96 => Ok("[er]"),
-        0x97 => Ok("[re]"),
-        0x98 => Ok("~"),
-        0x99 => Ok("™"),
-        0x9A => Ok("š"),
-        0x9B => Ok("›"),
-        0x9C => Ok("œ"),
-        0x9D => Ok("•"),
-        0x9E => Ok("ž"),
-        0x9F => Ok("Ÿ"),
-        0xA0 => Ok(" "),
-        0xA1 => Ok("¡"),
-        0xA2 => Ok("¢"),
-        0xA3 => Ok("£"),
-        0xA4 => Ok("¤"),
-        0xA5 => Ok("¥"),
-        0xA6 => Ok("¦"),
-        0xA7 => Ok("§"),
-        0xA8 => Ok("¨"),
-        0xA9 => Ok("©"),
-        0xAA => Ok("ª"),
-        0xAB => Ok("«"),
-        0xAC => Ok("¬"),
-        0xAD => Ok("\u{00AD}"),
-        0xAE => Ok("®"),
-        0xAF => Ok("¯"),
-        0xB0 => Ok("°"),
-        0xB1 => Ok("±"),
-        0xB2 => Ok("²"),
-        0xB3 => Ok("³"),
-        0xB4 => Ok("´"),
-        0xB5 => Ok("µ"),
-        0xB6 => Ok("¶"),
-        0xB7 => Ok("„"),
-        0xB8 => Ok("‚"),
-        0xB9 => Ok("¹"),
-        0xBA => Ok("º"),
-        0xBB => Ok("»"),
-        0xBC => Ok("←"),
-        0xBD => Ok("♂"),
-        0xBE => Ok("♀"),
-        0xBF => Ok("¿"),
-        0xC0 => Ok("À"),
-        0xC1 => Ok("Á"),
-        0xC2 => Ok("Â"),
-        0xC3 => Ok("Ã"),
-        0xC4 => Ok("Ä"),
-        0xC5 => Ok("Å"),
-        0xC6 => Ok("Æ"),
-        0xC7 => Ok("Ç"),
-        0xC8 => Ok("È"),
-        0xC9 => Ok("É"),
-        0xCA => Ok("Ê"),
-        0xCB => Ok("Ë"),
-        0xCC => Ok("Ì"),
-        0xCD => Ok("Í"),
-        0xCE => Ok("Î"),
-        0xCF => Ok("Ï"),
-        0xD0 => Ok("Ð"),
-        0xD1 => Ok("Ñ"),
-        0xD2 => Ok("Ò"),
-        0xD3 => Ok("Ó"),
-        0xD4 => Ok("Ô"),
-        0xD5 => Ok("Õ"),
-        0xD6 => Ok("Ö"),
-        0xD7 => Ok("×"),
-        0xD8 => Ok("Ø"),
-        0xD9 => Ok("Ù"),
-        0xDA => Ok("Ú"),
-        0xDB => Ok("Û"),
-        0xDC => Ok("Ü"),
-        0xDD => Ok("Ý"),
-        0xDE => Ok("Þ"),
-        0xDF => Ok("ß"),
-        0xE0 => Ok("à"),
-        0xE1 => Ok("á"),
-        0xE2 => Ok("â"),
-        0xE3 => Ok("ã"),
-        0xE4 => Ok("ä"),
-        0xE5 => Ok("å"),
-        0xE6 => Ok("æ"),
-        0xE7 => Ok("ç"),
-        0xE8 => Ok("è"),
-        0xE9 => Ok("é"),
-        0xEA => Ok("ê"),
-        0xEB => Ok("ë"),
-        0xEC => Ok("ì"),
-        0xED => Ok("í"),
-        0xEE => Ok("î"),
-        0xEF => Ok("ï"),
-        0xF0 => Ok("ð"),
-        0xF1 => Ok("ñ"),
-        0xF2 => Ok("ò"),
-        0xF3 => Ok("ó"),
-        0xF4 => Ok("ô"),
-        0xF5 => Ok("õ"),
-        0xF6 => Ok("ö"),
-        0xF7 => Ok("÷"),
-        0xF8 => Ok("ø"),
-        0xF9 => Ok("ù"),
-        0xFA => Ok("ú"),
-        0xFB => Ok("û"),
-        0xFC => Ok("ü"),
-        0xFD => Ok("ý"),
-        0xFE => Ok("þ"),
-        0xFF => Ok("ÿ"),
+/// Finds the PMD character for `c`, or for its closest equivalent.
+fn transliterate(c: char) -> Option<PmdChar> {
+    let mut buf = [0; 4];
+    if let Ok(pmd) = pmd_seq_to_byte(c.encode_utf8(&mut buf)) {
+        return Some(PmdChar { pmd, utf8: c });
     }
+
+    let close = TRANSLITERATIONS
+        .iter()
+        .find(|&&(from, _)| from == c)
+        .map(|&(_, to)| to)
+        .or_else(|| {
+            FULLWIDTH_FORMS
+                .contains(&c)
+                .then(|| char::from_u32(c as u32 - FULLWIDTH_OFFSET))
+                .flatten()
+        })
+        .or_else(|| {
+            let offset = (c as u32).checked_sub(0x100)?;
+            LATIN_EXTENDED_A_BASES
+                .chars()
+                .nth(offset as usize)
+                .filter(|&b| b != '-')
+        })?;
+
+    let pmd = pmd_seq_to_byte(close.encode_utf8(&mut buf)).ok()?;
+    Some(PmdChar { pmd, utf8: close })
+}
+
+/// Bytes that display the same as another byte, parsing never produces them.
+/// 0x90 and 0x95 display as 0x9D, 0xA0 displays as 0x20.
+const PMD_CHAR_ALIASES: [u8; 3] = [0x90, 0x95, 0xA0];
+
+/// Looks up the byte of a single character or special sequence in `PMD_CHAR_TABLE`.
+pub fn pmd_seq_to_byte(seq: &str) -> Result<u8, EncodingError> {
+    PMD_CHAR_TABLE
+        .iter()
+        .enumerate()
+        .find(|&(b, &s)| s == seq && !PMD_CHAR_ALIASES.contains(&(b as u8)))
+        .map(|(b, _)| b as u8)
+        .ok_or_else(|| EncodingError::InvalidPmdCharacter(seq.to_string()))
+}
+
+/// Looks up the character or special sequence of a byte in `PMD_CHAR_TABLE`.
+pub fn byte_to_pmd_seq(byte: u8) -> &'static str {
+    PMD_CHAR_TABLE[byte as usize]
 }
 
 #[test]
@@ -712,7 +414,6 @@ fn test_char_special_trip() {
 
 #[test]
 #[should_panic]
-
 fn test_char_invalid_sequence() {
     PmdChar::from_sequence("[LOL]").unwrap();
 }
@@ -763,3 +464,125 @@ fn test_pmd_string_to_vec() {
 
     assert_eq!(vec.as_slice(), &[0xC4, 0x88, 0x7E]);
 }
+
+#[test]
+fn test_pmd_string_default_name_for() {
+    let names = |id| match id {
+        29 => Some("Nidoran♀"),
+        1 => Some("Crabominable"),
+        _ => None,
+    };
+
+    assert_eq!(
+        PmdString::default_name_for(29, names).unwrap().to_string(),
+        "Nidoran♀"
+    );
+    assert_eq!(
+        PmdString::default_name_for(1, names).unwrap().to_string(),
+        "Crabominab"
+    );
+    assert!(PmdString::default_name_for(2, names).is_none());
+}
+
+#[test]
+fn test_pmd_string_truncate_to_fit() {
+    let (pmd, truncated) = PmdString::truncate_to_fit("Oak");
+    assert_eq!(pmd.to_string(), "Oak");
+    assert!(!truncated);
+
+    let (pmd, truncated) = PmdString::truncate_to_fit("Bulbasaur[e][e]");
+    assert_eq!(pmd.to_sequence(), "Bulbasaur[e]");
+    assert!(truncated);
+
+    let (pmd, truncated) = PmdString::truncate_to_fit("A[LOL]B");
+    assert_eq!(pmd.to_string(), "AB");
+    assert!(truncated);
+}
+
+#[test]
+fn test_char_from_byte_round_trip() {
+    for b in 0..=u8::MAX {
+        assert_eq!(PmdChar::from(b).pmd, b);
+    }
+    assert_eq!(PmdChar::from(0xA0).utf8, ' ');
+}
+
+#[test]
+fn test_pmd_char_table() {
+    for b in 0..=u8::MAX {
+        let seq = byte_to_pmd_seq(b);
+        let parsed = pmd_seq_to_byte(seq).unwrap();
+        if PMD_CHAR_ALIASES.contains(&b) {
+            assert_eq!(byte_to_pmd_seq(parsed), seq);
+        } else {
+            assert_eq!(parsed, b);
+        }
+    }
+    assert_eq!(pmd_seq_to_byte("•").unwrap(), 0x9D);
+    assert_eq!(pmd_seq_to_byte(" ").unwrap(), 0x20);
+}
+
+#[test]
+fn test_pmd_string_from_str_lossy() {
+    let lossy = |s: &str| {
+        let (string, dropped) = PmdString::from_str_lossy(s);
+        (string.to_string_until_nul(), dropped)
+    };
+
+    assert_eq!(lossy("Pika’s"), ("Pika’s".to_string(), vec![]));
+    assert_eq!(lossy("Ｐｉｋａ１"), ("Pika1".to_string(), vec![]));
+    assert_eq!(lossy("Łukasz–Ōno"), ("Lukasz-Ono".to_string(), vec![]));
+    assert_eq!(lossy("Re\u{301}mi"), ("Rémi".to_string(), vec![]));
+    assert_eq!(lossy("Zoe\u{308}\u{301}"), ("Zoë".to_string(), vec![]));
+    assert_eq!(lossy("Wu\u{30A}"), ("Wu".to_string(), vec![]));
+    assert!(COMPOSITIONS
+        .iter()
+        .all(|(_, bases, composed)| bases.chars().count() == composed.chars().count()));
+    assert_eq!(lossy("Zoë"), ("Zoë".to_string(), vec![]));
+    assert_eq!(lossy("Pika😀ピ"), ("Pika".to_string(), vec!['😀', 'ピ']));
+    assert_eq!(
+        lossy("ABCDEFGHIJKL"),
+        ("ABCDEFGHIJ".to_string(), vec!['K', 'L'])
+    );
+}
+
+#[test]
+fn test_transliteration_table() {
+    assert_eq!(LATIN_EXTENDED_A_BASES.chars().count(), 0x80);
+
+    let mut buf = [0; 4];
+    for (from, to) in TRANSLITERATIONS {
+        assert!(
+            pmd_seq_to_byte(from.encode_utf8(&mut buf)).is_err(),
+            "{:?}",
+            from
+        );
+        assert!(
+            pmd_seq_to_byte(to.encode_utf8(&mut buf)).is_ok(),
+            "{:?}",
+            to
+        );
+    }
+    for c in LATIN_EXTENDED_A_BASES.chars().filter(|&c| c != '-') {
+        assert!(c.is_ascii_alphabetic(), "{:?}", c);
+    }
+}
+
+#[test]
+fn test_any_bytes_decode() {
+    use bitvec::view::BitView;
+
+    for b in 0..=u8::MAX {
+        assert_eq!(PmdChar::from(b).pmd, b);
+    }
+
+    let long: Vec<u8> = (0..=u8::MAX).collect();
+    let s = PmdString::from(long.as_slice());
+    assert_eq!(s.to_save_bytes(), long[..10]);
+
+    let bits = &long.view_bits::<Lsb0>()[..36];
+    assert_eq!(
+        PmdString::from(bits).to_save_bytes(),
+        [0, 1, 2, 3, 4, 0, 0, 0, 0, 0]
+    );
+}
@@ -1,6 +1,8 @@
 //! The library's error type.
 
+use crate::{ActiveSaveBlock, Region};
 use std::io;
+use std::path::PathBuf;
 use thiserror::Error;
 
 /// An error that can occur when loading a save file.
@@ -13,6 +15,13 @@ pub enum SaveError {
     #[error("Error loading save file: {0:?}")]
     Io(#[from] io::Error),
 
+    #[error("Error writing {dest:?} through the temporary file {temp:?}: {source}")]
+    AtomicWrite {
+        temp: PathBuf,
+        dest: PathBuf,
+        source: io::Error,
+    },
+
     #[error("File size must be at least 128Kib.")]
     InvalidSize,
 
@@ -27,6 +36,60 @@ pub enum SaveError {
         bak_expected: [u8; 4],
         bak_found: [u8; 4],
     },
+
+    #[error(
+        "Invalid checksum in the {block:?} save block: expected {expected:?}, found {found:?}"
+    )]
+    InvalidBlockChecksum {
+        block: ActiveSaveBlock,
+        expected: [u8; 4],
+        found: [u8; 4],
+    },
+
+    #[error("The value of {field} doesn't fit in {bits} bits.")]
+    FieldOverflow { field: &'static str, bits: usize },
+
+    #[error("Invalid value for {0}.")]
+    InvalidFieldValue(&'static str),
+
+    #[error("The slot doesn't match the owner of {0}.")]
+    FieldSlotMismatch(&'static str),
+
+    #[error("Slot index {0} is out of range.")]
+    InvalidSlot(usize),
+
+    #[error("Slot {0} is empty.")]
+    EmptySlot(usize),
+
+    #[error("Slot {0} is already occupied.")]
+    SlotOccupied(usize),
+
+    #[error("The team is full.")]
+    TeamFull,
+
+    #[error("Team slot {0} holds the hero or the partner, which can't leave the team in-game.")]
+    ProtectedTeamMember(usize),
+
+    #[error("The max PP of move {0} is unknown.")]
+    UnknownMaxPp(u16),
+
+    #[error("Not enough free slots, {needed} needed but only {free} available.")]
+    NotEnoughSlots { needed: usize, free: usize },
+
+    #[error("Invalid team file: {0}")]
+    InvalidTeamFile(&'static str),
+
+    #[error("Unsupported team file version {found}, the newest supported version is {supported}.")]
+    UnsupportedTeamVersion { found: u16, supported: u16 },
+
+    #[error("The team file is from a {found:?} save, but this save is {expected:?}.")]
+    TeamRegionMismatch { found: Region, expected: Region },
+
+    #[error("Malformed save data: {context}")]
+    Malformed { context: &'static str },
+
+    #[error("A packed record must be {expected} bytes long, found {found}.")]
+    InvalidPackedLength { expected: usize, found: usize },
 }
 
 /// An error that can occur when encoding or decoding PMD strings.
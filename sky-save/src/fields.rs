@@ -0,0 +1,439 @@
+//! Describes the editable fields, so editors can be driven by a table instead of a widget per field.
+//!
+//! Every field of `General`, `StoredPokemon`, `ActivePokemon` and their moves has a `FieldDescriptor`,
+//! see `field_descriptors`.
+
+use crate::offsets::general;
+use crate::{AuditEntry, IqMap, PmdString, SaveError, SkySave};
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+use std::time::SystemTime;
+
+/// The kind of value a field holds.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FieldKind {
+    Bool,
+    Uint,
+    Int,
+    /// A PMD string, as a sequence, see `PmdString::to_sequence`.
+    String,
+    /// A raw array of bits.
+    Bits,
+}
+
+/// The value of a field.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Uint(u64),
+    Int(i64),
+    String(String),
+    Bits(Vec<bool>),
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Bool(v) => write!(f, "{}", v),
+            Value::Uint(v) => write!(f, "{}", v),
+            Value::Int(v) => write!(f, "{}", v),
+            Value::String(v) => write!(f, "{}", v),
+            Value::Bits(bits) => bits
+                .iter()
+                .try_for_each(|&b| write!(f, "{}", if b { '1' } else { '0' })),
+        }
+    }
+}
+
+/// The structure a field belongs to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FieldOwner {
+    General,
+    StoredPokemon,
+    ActivePokemon,
+    StoredMove,
+    ActiveMove,
+}
+
+/// Selects which structure a field is read from or written to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Slot {
+    /// For `General` fields.
+    None,
+    /// The index of a stored or active Pokémon.
+    Pokemon(usize),
+    /// One of the four moves of a stored or active Pokémon.
+    Move { pokemon: usize, slot: usize },
+}
+
+/// A value type usable in a `FieldDescriptor`.
+pub(crate) trait FieldValue: Sized {
+    /// `None` for nested structures, which are described separately.
+    const KIND: Option<FieldKind>;
+
+    fn to_value(&self) -> Option<Value>;
+    fn from_value(value: Value) -> Option<Self>;
+}
+
+impl FieldValue for bool {
+    const KIND: Option<FieldKind> = Some(FieldKind::Bool);
+
+    fn to_value(&self) -> Option<Value> {
+        Some(Value::Bool(*self))
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Bool(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! uint_field_value {
+    ($($t:ty),*) => {
+        $(
+            impl FieldValue for $t {
+                const KIND: Option<FieldKind> = Some(FieldKind::Uint);
+
+                fn to_value(&self) -> Option<Value> {
+                    Some(Value::Uint(*self as u64))
+                }
+
+                fn from_value(value: Value) -> Option<Self> {
+                    match value {
+                        Value::Uint(v) => v.try_into().ok(),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+uint_field_value!(u8, u16, u32);
+
+/// Wider than `Value::Uint`, so exposed as raw bits.
+impl FieldValue for u128 {
+    const KIND: Option<FieldKind> = Some(FieldKind::Bits);
+
+    fn to_value(&self) -> Option<Value> {
+        Some(Value::Bits((0..128).map(|i| self >> i & 1 == 1).collect()))
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Bits(bits) if bits.len() <= 128 => Some(
+                bits.iter()
+                    .enumerate()
+                    .fold(0, |acc, (i, &b)| acc | (b as u128) << i),
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl FieldValue for PmdString {
+    const KIND: Option<FieldKind> = Some(FieldKind::String);
+
+    fn to_value(&self) -> Option<Value> {
+        Some(Value::String(self.to_sequence()))
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::String(s) => PmdString::try_from(s.as_str()).ok(),
+            _ => None,
+        }
+    }
+}
+
+impl FieldValue for IqMap {
+    const KIND: Option<FieldKind> = Some(FieldKind::Bits);
+
+    fn to_value(&self) -> Option<Value> {
+        Some(Value::Bits(self.as_bitslice().iter().by_vals().collect()))
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Bits(bits) if bits.len() <= crate::IQ_MAP_BIT_LEN => {
+                let mut iq = IqMap::default();
+                for (i, b) in bits.into_iter().enumerate() {
+                    iq.set(i, b);
+                }
+                Some(iq)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Describes a single editable field.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct FieldDescriptor {
+    pub owner: FieldOwner,
+    pub name: &'static str,
+    pub kind: FieldKind,
+    /// The width of the field in the save, in bits.
+    pub bits: usize,
+}
+
+impl FieldDescriptor {
+    /// The values the field can hold, for numeric fields.
+    pub fn range(&self) -> Option<RangeInclusive<i64>> {
+        match self.kind {
+            FieldKind::Uint => Some(0..=(1i64 << self.bits) - 1),
+            FieldKind::Int => Some(-(1i64 << (self.bits - 1))..=(1i64 << (self.bits - 1)) - 1),
+            _ => None,
+        }
+    }
+
+    /// Reads the field from the structure selected by `slot`.
+    pub fn get(&self, save: &SkySave, slot: Slot) -> Result<Value, SaveError> {
+        let value = match (self.owner, slot) {
+            (FieldOwner::General, Slot::None) => get_general(save, self.name),
+            (FieldOwner::StoredPokemon, Slot::Pokemon(i)) => stored(save, i)?.get_field(self.name),
+            (FieldOwner::ActivePokemon, Slot::Pokemon(i)) => active(save, i)?.get_field(self.name),
+            (FieldOwner::StoredMove, Slot::Move { pokemon, slot }) => stored(save, pokemon)?
+                .moves()
+                .get(slot)
+                .ok_or(SaveError::InvalidSlot(slot))?
+                .get_field(self.name),
+            (FieldOwner::ActiveMove, Slot::Move { pokemon, slot }) => active(save, pokemon)?
+                .moves()
+                .get(slot)
+                .ok_or(SaveError::InvalidSlot(slot))?
+                .get_field(self.name),
+            _ => return Err(SaveError::FieldSlotMismatch(self.name)),
+        };
+
+        value.ok_or(SaveError::InvalidFieldValue(self.name))
+    }
+
+    /// Writes the field to the structure selected by `slot`.
+    /// Fails without changing anything if the value has the wrong kind or doesn't fit in the field.
+    /// The edit is recorded if the save has an audit log, see `SkySave::enable_audit_log`.
+    pub fn set(&self, save: &mut SkySave, slot: Slot, value: Value) -> Result<(), SaveError> {
+        let old = match save.audit_log {
+            Some(_) => Some(self.get(save, slot)?),
+            None => None,
+        };
+
+        self.set_unlogged(save, slot, value.clone())?;
+
+        if let (Some(log), Some(old)) = (save.audit_log.as_mut(), old) {
+            log.push(AuditEntry {
+                owner: self.owner,
+                field: self.name,
+                slot,
+                old,
+                new: value,
+                timestamp: SystemTime::now(),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn set_unlogged(&self, save: &mut SkySave, slot: Slot, value: Value) -> Result<(), SaveError> {
+        match (self.owner, slot) {
+            (FieldOwner::General, Slot::None) => set_general(save, self.name, value),
+            (FieldOwner::StoredPokemon, Slot::Pokemon(i)) => save
+                .stored_pokemon
+                .get_mut(i)
+                .ok_or(SaveError::InvalidSlot(i))?
+                .set_field(self.name, value),
+            (FieldOwner::ActivePokemon, Slot::Pokemon(i)) => save
+                .active_pokemon
+                .get_mut(i)
+                .ok_or(SaveError::InvalidSlot(i))?
+                .set_field(self.name, value),
+            (FieldOwner::StoredMove, Slot::Move { pokemon, slot }) => save
+                .stored_pokemon
+                .get_mut(pokemon)
+                .ok_or(SaveError::InvalidSlot(pokemon))?
+                .moves_mut()
+                .into_iter()
+                .nth(slot)
+                .ok_or(SaveError::InvalidSlot(slot))?
+                .set_field(self.name, value),
+            (FieldOwner::ActiveMove, Slot::Move { pokemon, slot }) => save
+                .active_pokemon
+                .get_mut(pokemon)
+                .ok_or(SaveError::InvalidSlot(pokemon))?
+                .moves_mut()
+                .into_iter()
+                .nth(slot)
+                .ok_or(SaveError::InvalidSlot(slot))?
+                .set_field(self.name, value),
+            _ => Err(SaveError::FieldSlotMismatch(self.name)),
+        }
+    }
+}
+
+fn stored(save: &SkySave, i: usize) -> Result<&crate::StoredPokemon, SaveError> {
+    save.stored_pokemon.get(i).ok_or(SaveError::InvalidSlot(i))
+}
+
+fn active(save: &SkySave, i: usize) -> Result<&crate::ActivePokemon, SaveError> {
+    save.active_pokemon.get(i).ok_or(SaveError::InvalidSlot(i))
+}
+
+const GENERAL_FIELDS: [(&str, FieldKind, usize); 6] = [
+    (
+        "team_name",
+        FieldKind::String,
+        (general::TEAM_NAME.end - general::TEAM_NAME.start) * 8,
+    ),
+    (
+        "held_money",
+        FieldKind::Uint,
+        general::HELD_MONEY_BITS.len(),
+    ),
+    (
+        "sp_episode_held_money",
+        FieldKind::Uint,
+        general::SP_EPISODE_HELD_MONEY_BITS.len(),
+    ),
+    (
+        "stored_money",
+        FieldKind::Uint,
+        general::STORED_MONEY_BITS.len(),
+    ),
+    (
+        "number_of_adventures",
+        FieldKind::Int,
+        (general::NUMBER_OF_ADVENTURERS.end - general::NUMBER_OF_ADVENTURERS.start) * 8,
+    ),
+    (
+        "explorer_rank",
+        FieldKind::Uint,
+        (general::EXPLORER_RANK.end - general::EXPLORER_RANK.start) * 8,
+    ),
+];
+
+fn get_general(save: &SkySave, name: &str) -> Option<Value> {
+    let g = &save.general;
+    match name {
+        "team_name" => g.team_name.to_value(),
+        "held_money" => g.held_money.to_value(),
+        "sp_episode_held_money" => g.sp_episode_held_money.to_value(),
+        "stored_money" => g.stored_money.to_value(),
+        "number_of_adventures" => Some(Value::Int(g.number_of_adventures as i64)),
+        "explorer_rank" => g.explorer_rank.to_value(),
+        _ => None,
+    }
+}
+
+fn set_general(save: &mut SkySave, name: &'static str, value: Value) -> Result<(), SaveError> {
+    let g = &mut save.general;
+    let mismatch = SaveError::InvalidFieldValue(name);
+    // Every money field is 24 bits wide.
+    let money = |value: Value| -> Result<u32, SaveError> {
+        let v = u32::from_value(value).ok_or(SaveError::InvalidFieldValue(name))?;
+        if v >> general::HELD_MONEY_BITS.len() != 0 {
+            return Err(SaveError::FieldOverflow {
+                field: name,
+                bits: general::HELD_MONEY_BITS.len(),
+            });
+        }
+        Ok(v)
+    };
+
+    match name {
+        "team_name" => g.team_name = PmdString::from_value(value).ok_or(mismatch)?,
+        "held_money" => g.held_money = money(value)?,
+        "sp_episode_held_money" => g.sp_episode_held_money = money(value)?,
+        "stored_money" => g.stored_money = money(value)?,
+        "number_of_adventures" => {
+            g.number_of_adventures = match value {
+                Value::Int(v) => v.try_into().map_err(|_| mismatch)?,
+                _ => return Err(mismatch),
+            }
+        }
+        "explorer_rank" => g.explorer_rank = u32::from_value(value).ok_or(mismatch)?,
+        _ => return Err(mismatch),
+    }
+
+    Ok(())
+}
+
+/// Returns a descriptor for every editable field, grouped by owner.
+pub fn field_descriptors() -> Vec<FieldDescriptor> {
+    let describe = |owner: FieldOwner, meta: Vec<(&'static str, FieldKind, usize)>| {
+        meta.into_iter()
+            .map(move |(name, kind, bits)| FieldDescriptor {
+                owner,
+                name,
+                kind,
+                bits,
+            })
+    };
+
+    describe(FieldOwner::General, GENERAL_FIELDS.to_vec())
+        .chain(describe(
+            FieldOwner::StoredPokemon,
+            crate::StoredPokemon::field_meta(),
+        ))
+        .chain(describe(
+            FieldOwner::StoredMove,
+            crate::StoredMove::field_meta(),
+        ))
+        .chain(describe(
+            FieldOwner::ActivePokemon,
+            crate::ActivePokemon::field_meta(),
+        ))
+        .chain(describe(
+            FieldOwner::ActiveMove,
+            crate::ActiveMove::field_meta(),
+        ))
+        .collect()
+}
+
+/// Finds the descriptor of `owner`'s field called `name`.
+pub fn field_descriptor(owner: FieldOwner, name: &str) -> Option<FieldDescriptor> {
+    field_descriptors()
+        .into_iter()
+        .find(|d| d.owner == owner && d.name == name)
+}
+
+#[test]
+fn test_field_descriptors() {
+    let mut save = SkySave::from_slice(vec![0; 0x20000]).unwrap();
+
+    let level = field_descriptor(FieldOwner::StoredPokemon, "level").unwrap();
+    assert_eq!(level.kind, FieldKind::Uint);
+    assert_eq!(level.range(), Some(0..=127));
+    level
+        .set(&mut save, Slot::Pokemon(3), Value::Uint(42))
+        .unwrap();
+    assert_eq!(save.stored_pokemon[3].level, 42);
+    assert!(matches!(
+        level.set(&mut save, Slot::Pokemon(3), Value::Uint(200)),
+        Err(SaveError::FieldOverflow { .. })
+    ));
+    assert_eq!(level.get(&save, Slot::Pokemon(3)).unwrap(), Value::Uint(42));
+
+    let pp = field_descriptor(FieldOwner::ActiveMove, "pp").unwrap();
+    pp.set(
+        &mut save,
+        Slot::Move {
+            pokemon: 1,
+            slot: 2,
+        },
+        Value::Uint(15),
+    )
+    .unwrap();
+    assert_eq!(save.active_pokemon[1].move_3.pp, 15);
+
+    let money = field_descriptor(FieldOwner::General, "held_money").unwrap();
+    money.set(&mut save, Slot::None, Value::Uint(999)).unwrap();
+    assert_eq!(save.general.held_money, 999);
+    assert!(matches!(
+        money.get(&save, Slot::Pokemon(0)),
+        Err(SaveError::FieldSlotMismatch(_))
+    ));
+
+    assert!(field_descriptor(FieldOwner::StoredPokemon, "move_1").is_none());
+}
@@ -0,0 +1,365 @@
+//! Builds synthetic saves for tests, so no real save file has to be checked in.
+
+use crate::save::MIN_SAVE_LEN;
+use crate::{
+    field_descriptors, ActiveMove, ActivePokemon, FieldDescriptor, FieldKind, FieldOwner,
+    PmdString, SkySave, Slot, StoredMove, StoredPokemon, Value,
+};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{env, fs, process};
+
+/// A path in the temp directory, unique to the test process and the call.
+/// The file or directory at the path is removed when dropped, even if the test panics.
+pub(crate) struct TempPath(PathBuf);
+
+impl TempPath {
+    pub(crate) fn new(name: &str) -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let n = NEXT.fetch_add(1, Ordering::Relaxed);
+
+        Self(env::temp_dir().join(format!("sky_save_test_{}_{}_{}", process::id(), n, name)))
+    }
+}
+
+impl Deref for TempPath {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempPath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0).or_else(|_| fs::remove_dir_all(&self.0));
+    }
+}
+
+/// Builds a valid save with a team name, money, three stored Pokémon and a team of two.
+pub(crate) fn fixture_save() -> Vec<u8> {
+    let mut save = SkySave::from_slice(vec![0; MIN_SAVE_LEN]).unwrap();
+    save.general.team_name = PmdString::try_from("Fixture").unwrap();
+    save.general.held_money = 1234;
+    save.general.stored_money = 56789;
+    save.general.sp_episode_held_money = 42;
+    save.general.number_of_adventures = 17;
+    save.general.explorer_rank = 3500;
+
+    for (i, name) in ["Hero", "Partner", "Recruit"].into_iter().enumerate() {
+        let mut pkm = StoredPokemon {
+            valid: true,
+            level: 5 + i as u8 * 10,
+            id: 1 + i as u16 * 3,
+            met_at: 1,
+            met_floor: i as u8,
+            iq: 100 + i as u16,
+            hp: 20 + i as u16,
+            attack: 10,
+            sp_attack: 11,
+            defense: 12,
+            sp_defense: 13,
+            exp: 1000 * i as u32,
+            tactic: i as u8,
+            name: PmdString::try_from(name).unwrap(),
+            ..Default::default()
+        };
+        pkm.iq_map.set(i, true);
+        pkm.move_1 = StoredMove {
+            valid: true,
+            id: 33 + i as u16,
+            ..Default::default()
+        };
+
+        save.stored_pokemon[[0, 1, 5][i]] = pkm;
+    }
+
+    for i in 0..2 {
+        let stored = &save.stored_pokemon[i];
+        save.active_pokemon[i] = ActivePokemon {
+            roaster_number: i as u16 + 1,
+            current_hp: stored.hp,
+            max_hp: stored.hp,
+            ..ActivePokemon::from(stored)
+        };
+        save.active_pokemon[i].move_1 = ActiveMove {
+            valid: true,
+            id: save.stored_pokemon[i].move_1.id,
+            pp: 25,
+            ..Default::default()
+        };
+    }
+
+    save.export_canonical().unwrap()
+}
+
+/// A SplitMix64 generator, so generated saves only depend on the seed.
+pub(crate) struct FixtureRng(u64);
+
+impl FixtureRng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// A value in `range`, both ends included.
+    pub(crate) fn range(&mut self, lo: i64, hi: i64) -> i64 {
+        lo + (self.next_u64() % ((hi - lo) as u64 + 1)) as i64
+    }
+
+    pub(crate) fn chance(&mut self, percent: u64) -> bool {
+        self.next_u64() % 100 < percent
+    }
+
+    /// A name of up to 10 random non-zero bytes, zero-padded after the terminator.
+    /// Full-length names are left unterminated, like the game does.
+    fn name(&mut self) -> PmdString {
+        let len = self.range(0, 10) as usize;
+        let mut bytes = [0; 10];
+        for b in &mut bytes[..len] {
+            *b = self.range(1, 255) as u8;
+        }
+
+        PmdString::from(&bytes[..])
+    }
+
+    /// A random value of the field, within its width, with levels and IQ kept legal.
+    fn value(&mut self, d: &FieldDescriptor) -> Option<Value> {
+        let value = match (d.name, d.kind) {
+            ("level", _) => Value::Uint(self.range(1, 100) as u64),
+            ("iq", _) => Value::Uint(self.range(0, 999) as u64),
+            (_, FieldKind::Bool) => Value::Bool(self.chance(50)),
+            (_, FieldKind::Uint | FieldKind::Int) => {
+                let range = d.range()?;
+                let v = self.range(*range.start(), *range.end());
+                match d.kind {
+                    FieldKind::Uint => Value::Uint(v as u64),
+                    _ => Value::Int(v),
+                }
+            }
+            (_, FieldKind::Bits) => Value::Bits((0..d.bits).map(|_| self.chance(50)).collect()),
+            (_, FieldKind::String) => return None,
+        };
+
+        Some(value)
+    }
+}
+
+/// Builds a save with random but legal content from `seed`: random money and team name,
+/// random box occupancy, random Pokémon with random moves and names, and a random team.
+/// Every field is set through its descriptor, so values never exceed their width.
+pub(crate) fn random_save(seed: u64) -> Vec<u8> {
+    let mut rng = FixtureRng::new(seed);
+    let mut save = SkySave::from_slice(vec![0; MIN_SAVE_LEN]).unwrap();
+    let descriptors = field_descriptors();
+    let fill = |rng: &mut FixtureRng, save: &mut SkySave, owner: FieldOwner, slot: Slot| {
+        for d in descriptors.iter().filter(|d| d.owner == owner) {
+            if let Some(value) = rng.value(d) {
+                d.set(save, slot, value).unwrap();
+            }
+        }
+    };
+
+    fill(&mut rng, &mut save, FieldOwner::General, Slot::None);
+    save.general.team_name = rng.name();
+
+    let occupancy = rng.range(0, 100) as u64;
+    for i in 0..save.stored_pokemon.len() {
+        if !rng.chance(occupancy) {
+            continue;
+        }
+
+        fill(
+            &mut rng,
+            &mut save,
+            FieldOwner::StoredPokemon,
+            Slot::Pokemon(i),
+        );
+        for slot in 0..4 {
+            let moves = Slot::Move { pokemon: i, slot };
+            fill(&mut rng, &mut save, FieldOwner::StoredMove, moves);
+        }
+        save.stored_pokemon[i].valid = true;
+        save.stored_pokemon[i].name = rng.name();
+    }
+
+    for i in 0..save.active_pokemon.len() {
+        if !rng.chance(70) {
+            continue;
+        }
+
+        fill(
+            &mut rng,
+            &mut save,
+            FieldOwner::ActivePokemon,
+            Slot::Pokemon(i),
+        );
+        for slot in 0..4 {
+            let moves = Slot::Move { pokemon: i, slot };
+            fill(&mut rng, &mut save, FieldOwner::ActiveMove, moves);
+        }
+        save.active_pokemon[i].valid = true;
+        save.active_pokemon[i].name = rng.name();
+    }
+
+    save.export_canonical().unwrap()
+}
+
+/// Runs 16 seeds by default, set `SKY_SAVE_SEEDS` to run more.
+#[test]
+fn test_random_save_round_trips() {
+    let seeds = std::env::var("SKY_SAVE_SEEDS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(16);
+
+    for seed in 0..seeds {
+        let data = random_save(seed);
+        let save = SkySave::from_slice(&data)
+            .unwrap_or_else(|e| panic!("seed {}: failed to parse: {:?}", seed, e));
+        let saved = save
+            .export_canonical()
+            .unwrap_or_else(|e| panic!("seed {}: failed to save: {:?}", seed, e));
+
+        assert!(saved == data, "seed {}: saving changed the data", seed);
+        assert_eq!(
+            SkySave::from_slice(&saved).unwrap().diff(&save),
+            Default::default(),
+            "seed {}",
+            seed
+        );
+    }
+}
+
+#[test]
+fn test_fixture_snapshots() {
+    let save = SkySave::from_slice(fixture_save()).unwrap();
+
+    insta::assert_debug_snapshot!("general", save.general);
+    insta::assert_debug_snapshot!("stored", &save.stored_pokemon[..6]);
+    insta::assert_debug_snapshot!("active", &save.active_pokemon[..]);
+}
+
+/// Checks the parsed structures against the raw bits of the active block, field by field,
+/// and that serializing them reproduces those bits exactly.
+#[test]
+fn test_fields_match_raw_bits() {
+    use crate::offsets::{active, stored};
+    use crate::{field_descriptor, FieldOwner, ParseOptions, Slot, Value};
+    use bitvec::field::BitField;
+    use bitvec::order::Lsb0;
+    use bitvec::slice::BitSlice;
+    use bitvec::view::BitView;
+    use std::ops::Range;
+
+    struct Check<'a> {
+        save: &'a SkySave,
+        owner: FieldOwner,
+        slot: Slot,
+    }
+
+    impl Check<'_> {
+        fn run(
+            &self,
+            raw: &BitSlice<u8, Lsb0>,
+            serialized: &BitSlice<u8, Lsb0>,
+            fields: &[(&str, Range<usize>)],
+        ) {
+            for (name, range) in fields {
+                let at = format!("{:?} {:?}.{}", self.owner, self.slot, name);
+                let bits = &raw[range.clone()];
+                assert_eq!(&serialized[range.clone()], bits, "{}", at);
+
+                let Some(d) = field_descriptor(self.owner, name) else {
+                    continue;
+                };
+                match d.get(self.save, self.slot).unwrap() {
+                    Value::Bool(v) => assert_eq!(bits[0], v, "{}", at),
+                    Value::Uint(v) => assert_eq!(bits.load_le::<u64>(), v, "{}", at),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    let noise: Vec<u8> = (0..MIN_SAVE_LEN).map(|i| (i * 73 % 255) as u8).collect();
+    let options = ParseOptions {
+        ignore_checksums: true,
+        ..Default::default()
+    };
+
+    for data in [fixture_save(), noise] {
+        let save = SkySave::from_slice_with(&data, options).unwrap();
+        let block = &data.view_bits::<Lsb0>()[save.active_save_block as usize * 8..];
+
+        for (i, p) in save.stored_pokemon.iter().enumerate() {
+            let start = stored::STORED_PKM_BITS.start + i * stored::STORED_PKM_BIT_LEN;
+            let check = Check {
+                save: &save,
+                owner: FieldOwner::StoredPokemon,
+                slot: Slot::Pokemon(i),
+            };
+            check.run(
+                &block[start..start + stored::STORED_PKM_BIT_LEN],
+                &p.to_bits(),
+                StoredPokemon::FIELDS,
+            );
+        }
+
+        for (i, p) in save.active_pokemon.iter().enumerate() {
+            let start = active::ACTIVE_PKM_BITS.start + i * active::ACTIVE_PKM_BIT_LEN;
+            let check = Check {
+                save: &save,
+                owner: FieldOwner::ActivePokemon,
+                slot: Slot::Pokemon(i),
+            };
+            check.run(
+                &block[start..start + active::ACTIVE_PKM_BIT_LEN],
+                &p.to_bits(),
+                ActivePokemon::FIELDS,
+            );
+        }
+    }
+}
+
+/// Saving an untouched save must reproduce the input exactly, even when the blocks differ
+/// or the save was loaded from a dump.
+#[test]
+fn test_untouched_save_is_byte_identical() {
+    use crate::offsets::save;
+    use crate::SaveContainer;
+
+    let mut stale_backup = random_save(1);
+    let older = random_save(2);
+    stale_backup[save::BACKUP_SAVE].copy_from_slice(&older[save::BACKUP_SAVE]);
+
+    let mut bad_quicksave = fixture_save();
+    bad_quicksave[save::QUICKSAVE_CHECKSUM.start] ^= 0xFF;
+
+    let mirrored = SaveContainer::Mirrored { copies: 2 }.wrap(&fixture_save());
+
+    for (name, data) in [
+        ("fixture", fixture_save()),
+        ("random", random_save(3)),
+        ("stale backup", stale_backup),
+        ("bad quicksave", bad_quicksave),
+        ("mirrored", mirrored),
+    ] {
+        let mut save = SkySave::from_slice(&data).unwrap();
+        assert!(save.save_to_vec().unwrap() == data, "{}", name);
+    }
+}
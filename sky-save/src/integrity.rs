@@ -0,0 +1,215 @@
+//! Reports semantically odd data that the checksums can't catch.
+
+use crate::{RosterProblem, SkySave};
+use std::fmt::Display;
+
+/// Whether an anomaly is in the stored or the active Pokémon.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PokemonKind {
+    Stored,
+    Active,
+}
+
+impl Display for PokemonKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PokemonKind::Stored => write!(f, "Stored"),
+            PokemonKind::Active => write!(f, "Active"),
+        }
+    }
+}
+
+/// How likely an anomaly is to cause trouble in game.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// A single finding of `SkySave::verify_integrity`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Anomaly {
+    /// A valid Pokémon has a level of zero.
+    LevelZero { kind: PokemonKind, slot: usize },
+    /// A valid Pokémon has a level above 100.
+    LevelTooHigh {
+        kind: PokemonKind,
+        slot: usize,
+        level: u8,
+    },
+    /// A valid move has an ID of zero.
+    EmptyMove {
+        kind: PokemonKind,
+        slot: usize,
+        move_slot: usize,
+    },
+    /// A problem with the roster numbers, see `SkySave::validate_roster`.
+    Roster(RosterProblem),
+}
+
+impl Anomaly {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Anomaly::LevelZero { .. } | Anomaly::LevelTooHigh { .. } => Severity::Error,
+            Anomaly::EmptyMove { .. } | Anomaly::Roster(_) => Severity::Warning,
+        }
+    }
+}
+
+impl Display for Anomaly {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Anomaly::LevelZero { kind, slot } => write!(f, "{} Pokémon {} has level 0", kind, slot),
+            Anomaly::LevelTooHigh { kind, slot, level } => {
+                write!(f, "{} Pokémon {} has level {}", kind, slot, level)
+            }
+            Anomaly::EmptyMove {
+                kind,
+                slot,
+                move_slot,
+            } => write!(
+                f,
+                "{} Pokémon {} has a valid move {} with ID 0",
+                kind, slot, move_slot
+            ),
+            Anomaly::Roster(problem) => write!(f, "{}", problem),
+        }
+    }
+}
+
+/// The findings of `SkySave::verify_integrity`. None of them prevent loading or saving.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct IntegrityReport {
+    pub anomalies: Vec<Anomaly>,
+}
+
+impl IntegrityReport {
+    /// Returns `true` if nothing was found.
+    pub fn is_clean(&self) -> bool {
+        self.anomalies.is_empty()
+    }
+
+    /// Returns the anomalies of at least `severity`.
+    pub fn at_least(&self, severity: Severity) -> impl Iterator<Item = &Anomaly> {
+        self.anomalies
+            .iter()
+            .filter(move |a| a.severity() >= severity)
+    }
+}
+
+impl Display for IntegrityReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for a in &self.anomalies {
+            writeln!(f, "[{:?}] {}", a.severity(), a)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn check_pokemon(
+    anomalies: &mut Vec<Anomaly>,
+    kind: PokemonKind,
+    slot: usize,
+    level: u8,
+    moves: [(bool, u16); 4],
+) {
+    if level == 0 {
+        anomalies.push(Anomaly::LevelZero { kind, slot });
+    } else if level > 100 {
+        anomalies.push(Anomaly::LevelTooHigh { kind, slot, level });
+    }
+
+    for (move_slot, (valid, id)) in moves.into_iter().enumerate() {
+        if valid && id == 0 {
+            anomalies.push(Anomaly::EmptyMove {
+                kind,
+                slot,
+                move_slot,
+            });
+        }
+    }
+}
+
+impl SkySave {
+    /// Checks the valid Pokémon and the roster for data the game is unlikely to have written.
+    /// Species IDs aren't checked, the crate has no species table.
+    pub fn verify_integrity(&self) -> IntegrityReport {
+        let mut anomalies = Vec::new();
+
+        for (i, p) in self
+            .stored_pokemon
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.valid)
+        {
+            check_pokemon(
+                &mut anomalies,
+                PokemonKind::Stored,
+                i,
+                p.level,
+                p.moves().map(|m| (m.valid, m.id)),
+            );
+        }
+
+        for (i, p) in self
+            .active_pokemon
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.valid)
+        {
+            check_pokemon(
+                &mut anomalies,
+                PokemonKind::Active,
+                i,
+                p.level,
+                p.moves().map(|m| (m.valid, m.id)),
+            );
+        }
+
+        anomalies.extend(self.validate_roster().into_iter().map(Anomaly::Roster));
+
+        IntegrityReport { anomalies }
+    }
+}
+
+#[test]
+fn test_verify_integrity() {
+    let mut save = SkySave::from_slice(vec![0; 0x20000]).unwrap();
+    assert!(save.verify_integrity().is_clean());
+
+    save.stored_pokemon[6].valid = true;
+    save.stored_pokemon[6].move_2.valid = true;
+
+    let report = save.verify_integrity();
+    assert_eq!(
+        report.anomalies,
+        [
+            Anomaly::LevelZero {
+                kind: PokemonKind::Stored,
+                slot: 6
+            },
+            Anomaly::EmptyMove {
+                kind: PokemonKind::Stored,
+                slot: 6,
+                move_slot: 1
+            },
+        ]
+    );
+    assert_eq!(report.at_least(Severity::Error).count(), 1);
+}
+
+#[test]
+fn test_full_length_name_is_clean() {
+    let mut save = SkySave::from_slice(vec![0; 0x20000]).unwrap();
+    save.stored_pokemon[6] = crate::StoredPokemon {
+        valid: true,
+        level: 5,
+        name: crate::PmdString::try_from("Charmander").unwrap(),
+        ..Default::default()
+    };
+
+    assert!(!save.stored_pokemon[6].name.to_save_bytes().contains(&0));
+    assert!(save.verify_integrity().is_clean());
+}
@@ -0,0 +1,101 @@
+//! Handles the IQ skill map of Pokémon.
+
+use crate::layout::LayoutField;
+use bitvec::prelude::*;
+use std::fmt::{Debug, Display};
+use std::ops::{Deref, DerefMut};
+
+/// The number of IQ skill bits in an `IqMap`.
+pub const IQ_MAP_BIT_LEN: usize = 69;
+
+/// A static `BitArray` representing the bits of an `IqMap`.
+pub type IqMapBits = BitArr!(for IQ_MAP_BIT_LEN, in u8, Lsb0);
+
+/// The IQ skills a Pokémon has enabled, one bit per skill ID.
+/// Dereferences to the underlying `IqMapBits`.
+#[derive(Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct IqMap(pub IqMapBits);
+
+impl IqMap {
+    /// Copies the first `IQ_MAP_BIT_LEN` bits of `bits`.
+    pub fn from_bitslice(bits: &BitSlice<u8, Lsb0>) -> Self {
+        let mut iq = IqMapBits::ZERO;
+        iq[0..IQ_MAP_BIT_LEN].copy_from_bitslice(&bits[0..IQ_MAP_BIT_LEN]);
+        Self(iq)
+    }
+
+    /// Returns the `IQ_MAP_BIT_LEN` bits of the map.
+    pub fn as_bitslice(&self) -> &BitSlice<u8, Lsb0> {
+        &self.0[0..IQ_MAP_BIT_LEN]
+    }
+
+    /// Returns the IDs of the enabled IQ skills.
+    pub fn enabled(&self) -> impl Iterator<Item = usize> + '_ {
+        self.as_bitslice().iter_ones()
+    }
+}
+
+impl Deref for IqMap {
+    type Target = IqMapBits;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for IqMap {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<IqMapBits> for IqMap {
+    fn from(value: IqMapBits) -> Self {
+        Self(value)
+    }
+}
+
+impl From<IqMap> for IqMapBits {
+    fn from(value: IqMap) -> Self {
+        value.0
+    }
+}
+
+/// Displays the enabled IQ skill IDs, comma-separated.
+/// The crate has no IQ skill name table yet.
+impl Display for IqMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ids: Vec<String> = self.enabled().map(|id| id.to_string()).collect();
+        write!(f, "{}", ids.join(", "))
+    }
+}
+
+/// Displays the raw bits.
+impl Debug for IqMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "IqMap({})", self.as_bitslice())
+    }
+}
+
+impl LayoutField for IqMap {
+    const MAX_BITS: usize = IQ_MAP_BIT_LEN;
+
+    fn load(bits: &BitSlice<u8, Lsb0>) -> Self {
+        IqMap::from_bitslice(bits)
+    }
+
+    fn store(&self, bits: &mut BitSlice<u8, Lsb0>) {
+        bits.copy_from_bitslice(self.as_bitslice());
+    }
+}
+
+#[test]
+fn test_iq_map_display() {
+    let mut iq = IqMap::default();
+    iq.set(1, true);
+    iq.set(12, true);
+    iq.set(68, true);
+
+    assert_eq!(iq.to_string(), "1, 12, 68");
+    assert_eq!(IqMap::from_bitslice(iq.as_bitslice()), iq);
+}
@@ -0,0 +1,224 @@
+//! Generates the bit-level loading and storing of the Pokémon structures.
+//!
+//! Each structure is declared once with `bit_layout!`, pairing every field with its offset constant,
+//! and `from_bitslice`, `to_bits` and the `FIELDS` table are generated from that declaration.
+//!
+//! Fields are accessed through `load_field` and `store_field`, which fail to compile if an offset is wider than its field type.
+//! Values too large for their offset are caught at runtime by `check_field`.
+
+use crate::offsets::BitRange;
+use crate::{PmdString, SaveError};
+use bitvec::prelude::*;
+
+/// A field that can be loaded from and stored to a range of bits.
+pub(crate) trait LayoutField: Sized {
+    /// The widest range of bits the field can be loaded from.
+    const MAX_BITS: usize;
+
+    fn load(bits: &BitSlice<u8, Lsb0>) -> Self;
+    fn store(&self, bits: &mut BitSlice<u8, Lsb0>);
+
+    /// Checks that the value can be stored in `len` bits without being truncated.
+    fn check(&self, _len: usize, _field: &'static str) -> Result<(), SaveError> {
+        Ok(())
+    }
+}
+
+/// Loads a field from `bits`.
+pub(crate) fn load_field<T: LayoutField, const LEN: usize>(
+    bits: &BitSlice<u8, Lsb0>,
+    range: BitRange<LEN>,
+) -> T {
+    const { assert!(LEN <= T::MAX_BITS, "bit range is wider than its field type") };
+    T::load(&bits[range.range()])
+}
+
+/// Stores a field to `bits`. Values too large for the range are truncated, see `check_field`.
+pub(crate) fn store_field<T: LayoutField, const LEN: usize>(
+    bits: &mut BitSlice<u8, Lsb0>,
+    range: BitRange<LEN>,
+    value: &T,
+) {
+    const { assert!(LEN <= T::MAX_BITS, "bit range is wider than its field type") };
+    value.store(&mut bits[range.range()]);
+}
+
+/// Checks that a field fits in its range of bits.
+pub(crate) fn check_field<T: LayoutField, const LEN: usize>(
+    _range: BitRange<LEN>,
+    value: &T,
+    field: &'static str,
+) -> Result<(), SaveError> {
+    value.check(LEN, field)
+}
+
+impl LayoutField for bool {
+    const MAX_BITS: usize = 1;
+
+    fn load(bits: &BitSlice<u8, Lsb0>) -> Self {
+        bits[0]
+    }
+
+    fn store(&self, bits: &mut BitSlice<u8, Lsb0>) {
+        bits.set(0, *self);
+    }
+}
+
+macro_rules! int_layout_field {
+    ($($t:ty),*) => {
+        $(
+            impl LayoutField for $t {
+                const MAX_BITS: usize = <$t>::BITS as usize;
+
+                fn load(bits: &BitSlice<u8, Lsb0>) -> Self {
+                    bits.load_le()
+                }
+
+                fn store(&self, bits: &mut BitSlice<u8, Lsb0>) {
+                    bits.store_le(*self);
+                }
+
+                fn check(&self, len: usize, field: &'static str) -> Result<(), SaveError> {
+                    if len < Self::MAX_BITS && *self >> len != 0 {
+                        return Err(SaveError::FieldOverflow { field, bits: len });
+                    }
+
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+int_layout_field!(u8, u16, u32, u128);
+
+impl LayoutField for PmdString {
+    const MAX_BITS: usize = 80;
+
+    fn load(bits: &BitSlice<u8, Lsb0>) -> Self {
+        PmdString::from(bits)
+    }
+
+    fn store(&self, bits: &mut BitSlice<u8, Lsb0>) {
+        bits.copy_from_bitslice(self.to_save_bytes().view_bits::<Lsb0>());
+    }
+}
+
+/// Declares a structure whose fields are packed at the given bit offsets,
+/// and generates `from_bitslice`, `to_bits` and a `LayoutField` implementation so it can be nested.
+macro_rules! bit_layout {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident($bits:ty) {
+            $(
+                $(#[$field_meta:meta])*
+                pub $field:ident: $ty:ty = $offset:expr,
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        pub struct $name {
+            $(
+                $(#[$field_meta])*
+                pub $field: $ty,
+            )*
+        }
+
+        impl $name {
+            /// The name and bit range of every field, in declaration order.
+            pub const FIELDS: &'static [(&'static str, std::ops::Range<usize>)] =
+                &[$((stringify!($field), $offset.range()),)*];
+
+            pub fn from_bitslice(value: &BitSlice<u8, Lsb0>) -> Self {
+                Self {
+                    $($field: $crate::layout::load_field(value, $offset),)*
+                }
+            }
+
+            /// Packs the structure to bits. Values too large for their field are truncated, see `check_widths`.
+            pub fn to_bits(&self) -> $bits {
+                let mut bits = <$bits>::ZERO;
+                $($crate::layout::store_field(&mut bits, $offset, &self.$field);)*
+
+                bits
+            }
+
+            /// The name, kind and bit width of every field that isn't a nested structure.
+            pub(crate) fn field_meta() -> Vec<(&'static str, $crate::fields::FieldKind, usize)> {
+                [$((
+                    stringify!($field),
+                    <$ty as $crate::fields::FieldValue>::KIND,
+                    $offset.len(),
+                ),)*]
+                .into_iter()
+                .filter_map(|(name, kind, bits)| Some((name, kind?, bits)))
+                .collect()
+            }
+
+            /// Reads the field called `name`, see `fields::FieldDescriptor`.
+            pub(crate) fn get_field(&self, name: &str) -> Option<$crate::fields::Value> {
+                match name {
+                    $(stringify!($field) => $crate::fields::FieldValue::to_value(&self.$field),)*
+                    _ => None,
+                }
+            }
+
+            /// Writes the field called `name`, failing without changing it if the value doesn't fit.
+            pub(crate) fn set_field(
+                &mut self,
+                name: &'static str,
+                value: $crate::fields::Value,
+            ) -> Result<(), $crate::SaveError> {
+                match name {
+                    $(stringify!($field) => {
+                        let v: $ty = $crate::fields::FieldValue::from_value(value)
+                            .ok_or($crate::SaveError::InvalidFieldValue(name))?;
+                        $crate::layout::check_field($offset, &v, name)?;
+                        self.$field = v;
+                    })*
+                    _ => return Err($crate::SaveError::InvalidFieldValue(name)),
+                }
+
+                Ok(())
+            }
+
+            /// Checks that every field fits in its range of bits.
+            pub fn check_widths(&self) -> Result<(), $crate::SaveError> {
+                $($crate::layout::check_field($offset, &self.$field, stringify!($field))?;)*
+
+                Ok(())
+            }
+        }
+
+        impl $crate::fields::FieldValue for $name {
+            const KIND: Option<$crate::fields::FieldKind> = None;
+
+            fn to_value(&self) -> Option<$crate::fields::Value> {
+                None
+            }
+
+            fn from_value(_value: $crate::fields::Value) -> Option<Self> {
+                None
+            }
+        }
+
+        impl $crate::layout::LayoutField for $name {
+            const MAX_BITS: usize = std::mem::size_of::<$bits>() * 8;
+
+            fn load(bits: &BitSlice<u8, Lsb0>) -> Self {
+                Self::from_bitslice(bits)
+            }
+
+            fn store(&self, bits: &mut BitSlice<u8, Lsb0>) {
+                let len = bits.len();
+                bits.copy_from_bitslice(&self.to_bits()[0..len]);
+            }
+
+            fn check(&self, _len: usize, _field: &'static str) -> Result<(), $crate::SaveError> {
+                self.check_widths()
+            }
+        }
+    };
+}
+
+pub(crate) use bit_layout;
@@ -1,12 +1,56 @@
+//! A library for loading and editing Pokémon Mystery Dungeon - Explorers of Sky save files.
+//!
+//! Save data is always little-endian. Every field is read and written through `bitvec` with `u8` storage
+//! and `Lsb0` ordering, and integers are converted with `load_le`/`store_le` and `{from,to}_le_bytes`,
+//! so parsing and saving produce the same results regardless of the host's byte order.
+//!
+//! Downstream tools that need a stable API should import from `sky_save::stable`.
+
 pub mod active;
+#[cfg(feature = "tokio")]
+pub mod async_fs;
+pub mod audit;
+pub mod bulk;
+pub mod checksum;
+pub mod container;
+pub mod diff;
 pub mod encoding;
 pub mod error;
+pub mod fields;
+#[cfg(test)]
+mod fixture;
+pub mod integrity;
+pub mod iq;
+mod layout;
+pub mod normalize;
 pub mod offsets;
+pub mod rank;
+pub mod report;
+pub mod roster;
 pub mod save;
+pub mod save_mut;
+pub mod stable;
+pub mod stats;
 pub mod stored;
+pub mod team;
 
 pub use active::*;
+pub use audit::*;
+pub use bulk::*;
+pub use checksum::*;
+pub use container::*;
+pub use diff::*;
 pub use encoding::*;
 pub use error::*;
+pub use fields::*;
+pub use integrity::*;
+pub use iq::*;
+pub use normalize::*;
+pub use rank::*;
+pub use report::*;
+pub use roster::*;
 pub use save::*;
+pub use save_mut::*;
+pub use stats::*;
 pub use stored::*;
+pub use team::*;
@@ -0,0 +1,201 @@
+//! Applies safe fixes to corrupted saves, see `SkySave::normalize`.
+
+use crate::{ActivePokemon, PokemonKind, SkySave, StoredPokemon};
+use std::fmt::Display;
+
+/// Selects the fixes `SkySave::normalize` applies.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct NormalizePolicy {
+    /// Clamps the level of valid Pokémon to 1-100.
+    pub clamp_levels: bool,
+    /// Clears the valid flag of slots whose other bits are all zero.
+    pub clear_empty_slots: bool,
+    /// Renumbers the roster to 1..=N if `SkySave::validate_roster` finds a problem.
+    pub renumber_roster: bool,
+}
+
+impl NormalizePolicy {
+    /// Every fix.
+    pub const ALL: Self = Self {
+        clamp_levels: true,
+        clear_empty_slots: true,
+        renumber_roster: true,
+    };
+}
+
+impl Default for NormalizePolicy {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+/// A change made by `SkySave::normalize`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum NormalizeChange {
+    LevelClamped {
+        kind: PokemonKind,
+        slot: usize,
+        from: u8,
+        to: u8,
+    },
+    SlotCleared {
+        kind: PokemonKind,
+        slot: usize,
+    },
+    RosterRenumbered,
+}
+
+impl Display for NormalizeChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NormalizeChange::LevelClamped {
+                kind,
+                slot,
+                from,
+                to,
+            } => write!(
+                f,
+                "{} Pokémon {}: level {} clamped to {}",
+                kind, slot, from, to
+            ),
+            NormalizeChange::SlotCleared { kind, slot } => {
+                write!(f, "{} Pokémon {}: empty slot marked invalid", kind, slot)
+            }
+            NormalizeChange::RosterRenumbered => write!(f, "Roster renumbered"),
+        }
+    }
+}
+
+/// Applies the level fix to the valid Pokémon at `slot`.
+fn normalize_pokemon(
+    changes: &mut Vec<NormalizeChange>,
+    policy: NormalizePolicy,
+    kind: PokemonKind,
+    slot: usize,
+    level: &mut u8,
+) {
+    if policy.clamp_levels {
+        let to = (*level).clamp(1, 100);
+        if to != *level {
+            changes.push(NormalizeChange::LevelClamped {
+                kind,
+                slot,
+                from: *level,
+                to,
+            });
+            *level = to;
+        }
+    }
+}
+
+impl SkySave {
+    /// Applies the fixes selected by `policy` and returns the changes made.
+    /// Only the parsed structures are changed, regions the crate doesn't understand are never touched.
+    /// Meant for rescuing saves that crash the game, see `verify_integrity`.
+    pub fn normalize(&mut self, policy: NormalizePolicy) -> Vec<NormalizeChange> {
+        let mut changes = Vec::new();
+        let empty_stored = StoredPokemon::default().to_bits();
+        let empty_active = ActivePokemon::default().to_bits();
+
+        for (i, p) in self
+            .stored_pokemon
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, p)| p.valid)
+        {
+            let is_empty = StoredPokemon {
+                valid: false,
+                ..p.clone()
+            }
+            .to_bits()
+                == empty_stored;
+            if policy.clear_empty_slots && is_empty {
+                p.valid = false;
+                changes.push(NormalizeChange::SlotCleared {
+                    kind: PokemonKind::Stored,
+                    slot: i,
+                });
+                continue;
+            }
+
+            normalize_pokemon(&mut changes, policy, PokemonKind::Stored, i, &mut p.level);
+        }
+
+        for (i, p) in self
+            .active_pokemon
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, p)| p.valid)
+        {
+            let is_empty = ActivePokemon {
+                valid: false,
+                ..p.clone()
+            }
+            .to_bits()
+                == empty_active;
+            if policy.clear_empty_slots && is_empty {
+                p.valid = false;
+                changes.push(NormalizeChange::SlotCleared {
+                    kind: PokemonKind::Active,
+                    slot: i,
+                });
+                continue;
+            }
+
+            normalize_pokemon(&mut changes, policy, PokemonKind::Active, i, &mut p.level);
+        }
+
+        if policy.renumber_roster && !self.validate_roster().is_empty() {
+            let before: Vec<u16> = self
+                .active_pokemon
+                .iter()
+                .map(|p| p.roaster_number)
+                .collect();
+            self.normalize_roster();
+            if self
+                .active_pokemon
+                .iter()
+                .map(|p| p.roaster_number)
+                .ne(before)
+            {
+                changes.push(NormalizeChange::RosterRenumbered);
+            }
+        }
+
+        changes
+    }
+}
+
+#[test]
+fn test_normalize() {
+    let mut save = SkySave::from_slice(vec![0; 0x20000]).unwrap();
+    save.stored_pokemon[5].valid = true;
+    save.stored_pokemon[6].valid = true;
+    save.stored_pokemon[6].level = 120;
+    save.stored_pokemon[6].name = crate::PmdString::truncate_to_fit("Abcdefghij").0;
+
+    let changes = save.normalize(NormalizePolicy::default());
+    assert_eq!(
+        changes,
+        [
+            NormalizeChange::SlotCleared {
+                kind: PokemonKind::Stored,
+                slot: 5
+            },
+            NormalizeChange::LevelClamped {
+                kind: PokemonKind::Stored,
+                slot: 6,
+                from: 120,
+                to: 100
+            },
+        ]
+    );
+    assert!(!save.stored_pokemon[5].valid);
+
+    // Full-length names are valid without a terminator and are left alone.
+    assert!(save.normalize(NormalizePolicy::ALL).is_empty());
+    assert_eq!(
+        save.stored_pokemon[6].name.to_string_until_nul(),
+        "Abcdefghij"
+    );
+}
@@ -1,10 +1,11 @@
 //! General save data offsets
 
+use crate::offsets::{byte_range, BitRange};
 use std::ops::Range;
 
-pub const TEAM_NAME: Range<usize> = 0x994E..0x9958;
-pub const HELD_MONEY_BITS: Range<usize> = 0x990C * 8 + 6..0x990C * 8 + 6 + 24;
-pub const SP_EPISODE_HELD_MONEY_BITS: Range<usize> = 0x990F * 8 + 6..0x990F * 8 + 6 + 24;
-pub const STORED_MONEY_BITS: Range<usize> = 0x9915 * 8 + 6..0x9915 * 8 + 6 + 24;
-pub const EXPLORER_RANK: Range<usize> = 0x9958..0x995C;
-pub const NUMBER_OF_ADVENTURERS: Range<usize> = 0x8B70..0x8B74;
+pub const TEAM_NAME: Range<usize> = byte_range(0x994E, 10);
+pub const HELD_MONEY_BITS: BitRange<24> = BitRange::at(0x990C, 6);
+pub const SP_EPISODE_HELD_MONEY_BITS: BitRange<24> = BitRange::at(0x990F, 6);
+pub const STORED_MONEY_BITS: BitRange<24> = BitRange::at(0x9915, 6);
+pub const EXPLORER_RANK: Range<usize> = byte_range(0x9958, 4);
+pub const NUMBER_OF_ADVENTURERS: Range<usize> = byte_range(0x8B70, 4);
@@ -1,6 +1,82 @@
 //! Holds all the offsets for the save data, divided into categories.
 
+use std::ops::Range;
+
 pub mod active;
 pub mod general;
 pub mod save;
 pub mod stored;
+
+/// The `len_bits` bits starting at bit `bit` of byte `byte`, bits counted from the least significant.
+/// Fails to compile when used in a constant with `bit` past 7.
+pub const fn bit_range(byte: usize, bit: usize, len_bits: usize) -> Range<usize> {
+    assert!(bit < 8, "the bit must be within the byte");
+    let start = byte * 8 + bit;
+    start..start + len_bits
+}
+
+/// The `len` bytes starting at `byte`.
+pub const fn byte_range(byte: usize, len: usize) -> Range<usize> {
+    byte..byte + len
+}
+
+/// A range of bits with a length known at compile time.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct BitRange<const LEN: usize> {
+    pub start: usize,
+}
+
+impl<const LEN: usize> BitRange<LEN> {
+    pub const fn new(start: usize) -> Self {
+        Self { start }
+    }
+
+    /// The range starting at bit `bit` of byte `byte`, see `bit_range`.
+    pub const fn at(byte: usize, bit: usize) -> Self {
+        Self::new(bit_range(byte, bit, LEN).start)
+    }
+
+    pub const fn end(&self) -> usize {
+        self.start + LEN
+    }
+
+    pub const fn len(&self) -> usize {
+        LEN
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        LEN == 0
+    }
+
+    pub const fn range(&self) -> Range<usize> {
+        self.start..self.start + LEN
+    }
+}
+
+impl<const LEN: usize> From<BitRange<LEN>> for Range<usize> {
+    fn from(value: BitRange<LEN>) -> Self {
+        value.range()
+    }
+}
+
+#[test]
+fn test_rewritten_offsets_unchanged() {
+    assert_eq!(active::ACTIVE_PKM_BITS, 270025..272209);
+    assert_eq!(stored::STORED_PKM_BITS, 8992..208092);
+    assert_eq!(general::TEAM_NAME, 0x994E..0x9958);
+    assert_eq!(general::HELD_MONEY_BITS.start, 0x990C * 8 + 6);
+    assert_eq!(general::SP_EPISODE_HELD_MONEY_BITS.start, 0x990F * 8 + 6);
+    assert_eq!(general::STORED_MONEY_BITS.start, 0x9915 * 8 + 6);
+    assert_eq!(general::EXPLORER_RANK, 0x9958..0x995C);
+    assert_eq!(general::NUMBER_OF_ADVENTURERS, 0x8B70..0x8B74);
+
+    assert_eq!(save::PRIMARY_SAVE, 0x0..0xB65C);
+    assert_eq!(save::PRIMARY_READ_CHECKSUM, 0x0..0x4);
+    assert_eq!(save::PRIMARY_CHECKSUM, 0x4..0xB65C);
+    assert_eq!(save::BACKUP_SAVE, 0xC800..0x17E5C);
+    assert_eq!(save::BACKUP_READ_CHECKSUM, 0xC800..0xC804);
+    assert_eq!(save::BACKUP_CHECKSUM, 0xC804..0x17E5C);
+    assert_eq!(save::QUICKSAVE, 0x19000..0x1E800);
+    assert_eq!(save::QUICKSAVE_READ_CHECKSUM, 0x19000..0x19004);
+    assert_eq!(save::QUICKSAVE_CHECKSUM, 0x19004..0x1E800);
+}
@@ -1,15 +1,26 @@
 //! Save file offsets
 
+use crate::offsets::byte_range;
 use std::ops::Range;
 
-pub const PRIMARY_SAVE: Range<usize> = 0x0..0xB65C;
-pub const PRIMARY_READ_CHECKSUM: Range<usize> = PRIMARY_SAVE.start..PRIMARY_SAVE.start + 4;
-pub const PRIMARY_CHECKSUM: Range<usize> = PRIMARY_SAVE.start + 4..PRIMARY_SAVE.end;
+/// The length of the primary and the backup save blocks.
+const SAVE_BLOCK_LEN: usize = 0xB65C;
+/// The length of the checksum at the start of each block.
+const CHECKSUM_LEN: usize = 4;
 
-pub const BACKUP_SAVE: Range<usize> = 0xC800..0x17E5C;
-pub const BACKUP_READ_CHECKSUM: Range<usize> = BACKUP_SAVE.start..BACKUP_SAVE.start + 4;
-pub const BACKUP_CHECKSUM: Range<usize> = BACKUP_SAVE.start + 4..BACKUP_SAVE.end;
+/// The bytes of `block` covered by its checksum, everything after the checksum itself.
+const fn checksummed(block: Range<usize>) -> Range<usize> {
+    block.start + CHECKSUM_LEN..block.end
+}
 
-pub const QUICKSAVE: Range<usize> = 0x19000..0x1E800;
-pub const QUICKSAVE_READ_CHECKSUM: Range<usize> = QUICKSAVE.start..QUICKSAVE.start + 4;
-pub const QUICKSAVE_CHECKSUM: Range<usize> = QUICKSAVE.start + 4..QUICKSAVE.end;
+pub const PRIMARY_SAVE: Range<usize> = byte_range(0x0, SAVE_BLOCK_LEN);
+pub const PRIMARY_READ_CHECKSUM: Range<usize> = byte_range(PRIMARY_SAVE.start, CHECKSUM_LEN);
+pub const PRIMARY_CHECKSUM: Range<usize> = checksummed(PRIMARY_SAVE);
+
+pub const BACKUP_SAVE: Range<usize> = byte_range(0xC800, SAVE_BLOCK_LEN);
+pub const BACKUP_READ_CHECKSUM: Range<usize> = byte_range(BACKUP_SAVE.start, CHECKSUM_LEN);
+pub const BACKUP_CHECKSUM: Range<usize> = checksummed(BACKUP_SAVE);
+
+pub const QUICKSAVE: Range<usize> = byte_range(0x19000, 0x5800);
+pub const QUICKSAVE_READ_CHECKSUM: Range<usize> = byte_range(QUICKSAVE.start, CHECKSUM_LEN);
+pub const QUICKSAVE_CHECKSUM: Range<usize> = checksummed(QUICKSAVE);
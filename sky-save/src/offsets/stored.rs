@@ -1,47 +1,51 @@
 //! Stored Pokemon offsets
 
+use crate::offsets::bit_range;
 use std::ops::Range;
 
 pub const STORED_PKM_BIT_LEN: usize = 362;
+/// `STORED_PKM_BIT_LEN` rounded up to whole bytes, see `StoredPokemon::to_packed_bytes`.
+pub const STORED_PKM_BYTE_LEN: usize = STORED_PKM_BIT_LEN.div_ceil(8);
 pub const STORED_MOVE_BIT_LEN: usize = 21;
 pub const STORED_PKM_COUNT: usize = 550;
+/// Chimecho's Assembly, starting at byte `0x464` of the block.
 pub const STORED_PKM_BITS: Range<usize> =
-    0x464 * 8..(0x464 * 8 + STORED_PKM_BIT_LEN * STORED_PKM_COUNT);
+    bit_range(0x464, 0, STORED_PKM_BIT_LEN * STORED_PKM_COUNT);
 
 pub mod pokemon {
-    use std::ops::Range;
+    use crate::offsets::BitRange;
 
-    pub const VALID: usize = 0;
-    pub const LEVEL: Range<usize> = 1..8;
-    pub const ID: Range<usize> = 8..19;
-    pub const MET_AT: Range<usize> = 19..27;
-    pub const MET_FLOOR: Range<usize> = 27..34;
-    pub const UNKNOWN: usize = 34;
-    pub const EVOLVED_AT_1: Range<usize> = 35..42;
-    pub const EVOLVED_AT_2: Range<usize> = 42..49;
-    pub const IQ: Range<usize> = 49..59;
-    pub const HP: Range<usize> = 59..69;
-    pub const ATTACK: Range<usize> = 69..77;
-    pub const SP_ATTACK: Range<usize> = 77..85;
-    pub const DEFENSE: Range<usize> = 85..93;
-    pub const SP_DEFENSE: Range<usize> = 93..101;
-    pub const EXP: Range<usize> = 101..125;
-    pub const IQ_MAP: Range<usize> = 125..194;
-    pub const TACTIC: Range<usize> = 194..198;
-    pub const MOVE_1: Range<usize> = 198..219;
-    pub const MOVE_2: Range<usize> = 219..240;
-    pub const MOVE_3: Range<usize> = 240..261;
-    pub const MOVE_4: Range<usize> = 261..282;
-    pub const NAME: Range<usize> = 282..362;
+    pub const VALID: BitRange<1> = BitRange::new(0);
+    pub const LEVEL: BitRange<7> = BitRange::new(1);
+    pub const ID: BitRange<11> = BitRange::new(8);
+    pub const MET_AT: BitRange<8> = BitRange::new(19);
+    pub const MET_FLOOR: BitRange<7> = BitRange::new(27);
+    pub const UNKNOWN: BitRange<1> = BitRange::new(34);
+    pub const EVOLVED_AT_1: BitRange<7> = BitRange::new(35);
+    pub const EVOLVED_AT_2: BitRange<7> = BitRange::new(42);
+    pub const IQ: BitRange<10> = BitRange::new(49);
+    pub const HP: BitRange<10> = BitRange::new(59);
+    pub const ATTACK: BitRange<8> = BitRange::new(69);
+    pub const SP_ATTACK: BitRange<8> = BitRange::new(77);
+    pub const DEFENSE: BitRange<8> = BitRange::new(85);
+    pub const SP_DEFENSE: BitRange<8> = BitRange::new(93);
+    pub const EXP: BitRange<24> = BitRange::new(101);
+    pub const IQ_MAP: BitRange<69> = BitRange::new(125);
+    pub const TACTIC: BitRange<4> = BitRange::new(194);
+    pub const MOVE_1: BitRange<21> = BitRange::new(198);
+    pub const MOVE_2: BitRange<21> = BitRange::new(219);
+    pub const MOVE_3: BitRange<21> = BitRange::new(240);
+    pub const MOVE_4: BitRange<21> = BitRange::new(261);
+    pub const NAME: BitRange<80> = BitRange::new(282);
 }
 
 pub mod moves {
-    use std::ops::Range;
+    use crate::offsets::BitRange;
 
-    pub const VALID: usize = 0;
-    pub const LINKED: usize = 1;
-    pub const SWITCHED: usize = 2;
-    pub const SET: usize = 3;
-    pub const ID: Range<usize> = 4..14;
-    pub const POWER_BOOST: Range<usize> = 14..21;
+    pub const VALID: BitRange<1> = BitRange::new(0);
+    pub const LINKED: BitRange<1> = BitRange::new(1);
+    pub const SWITCHED: BitRange<1> = BitRange::new(2);
+    pub const SET: BitRange<1> = BitRange::new(3);
+    pub const ID: BitRange<10> = BitRange::new(4);
+    pub const POWER_BOOST: BitRange<7> = BitRange::new(14);
 }
@@ -0,0 +1,141 @@
+//! Names the explorer rank tiers, see `General::rank_tier`.
+
+use crate::General;
+use std::fmt::Display;
+
+/// The explorer rank tiers, from lowest to highest.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ExplorerRank {
+    Normal,
+    Bronze,
+    Silver,
+    Gold,
+    Diamond,
+    Super,
+    Ultra,
+    Hyper,
+    Master,
+    MasterOneStar,
+    MasterTwoStars,
+    MasterThreeStars,
+    Guildmaster,
+}
+
+impl ExplorerRank {
+    pub const ALL: [ExplorerRank; 13] = [
+        ExplorerRank::Normal,
+        ExplorerRank::Bronze,
+        ExplorerRank::Silver,
+        ExplorerRank::Gold,
+        ExplorerRank::Diamond,
+        ExplorerRank::Super,
+        ExplorerRank::Ultra,
+        ExplorerRank::Hyper,
+        ExplorerRank::Master,
+        ExplorerRank::MasterOneStar,
+        ExplorerRank::MasterTwoStars,
+        ExplorerRank::MasterThreeStars,
+        ExplorerRank::Guildmaster,
+    ];
+
+    /// The rank points needed to reach the tier.
+    pub fn min_points(self) -> u32 {
+        match self {
+            ExplorerRank::Normal => 0,
+            ExplorerRank::Bronze => 100,
+            ExplorerRank::Silver => 400,
+            ExplorerRank::Gold => 1600,
+            ExplorerRank::Diamond => 3200,
+            ExplorerRank::Super => 5000,
+            ExplorerRank::Ultra => 7500,
+            ExplorerRank::Hyper => 10500,
+            ExplorerRank::Master => 13500,
+            ExplorerRank::MasterOneStar => 17000,
+            ExplorerRank::MasterTwoStars => 23000,
+            ExplorerRank::MasterThreeStars => 30000,
+            ExplorerRank::Guildmaster => 100000,
+        }
+    }
+
+    /// The tier reached with `points`.
+    pub fn from_points(points: u32) -> Self {
+        Self::ALL
+            .into_iter()
+            .rev()
+            .find(|r| points >= r.min_points())
+            .unwrap_or(ExplorerRank::Normal)
+    }
+
+    /// The tier above this one, `None` for `Guildmaster`.
+    pub fn next(self) -> Option<Self> {
+        Self::ALL.get(self as usize + 1).copied()
+    }
+}
+
+impl Display for ExplorerRank {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ExplorerRank::Normal => "Normal",
+            ExplorerRank::Bronze => "Bronze",
+            ExplorerRank::Silver => "Silver",
+            ExplorerRank::Gold => "Gold",
+            ExplorerRank::Diamond => "Diamond",
+            ExplorerRank::Super => "Super",
+            ExplorerRank::Ultra => "Ultra",
+            ExplorerRank::Hyper => "Hyper",
+            ExplorerRank::Master => "Master",
+            ExplorerRank::MasterOneStar => "Master★",
+            ExplorerRank::MasterTwoStars => "Master★★",
+            ExplorerRank::MasterThreeStars => "Master★★★",
+            ExplorerRank::Guildmaster => "Guildmaster",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl General {
+    /// The tier of `explorer_rank`.
+    pub fn rank_tier(&self) -> ExplorerRank {
+        ExplorerRank::from_points(self.explorer_rank)
+    }
+
+    /// The points missing to reach the next tier, `None` at `Guildmaster`.
+    pub fn points_to_next_rank(&self) -> Option<u32> {
+        let next = self.rank_tier().next()?;
+        Some(next.min_points() - self.explorer_rank)
+    }
+
+    /// Sets `explorer_rank` to the minimum points of `rank`.
+    pub fn set_rank_tier(&mut self, rank: ExplorerRank) {
+        self.explorer_rank = rank.min_points();
+    }
+}
+
+#[test]
+fn test_rank_tiers() {
+    use crate::SkySave;
+
+    let thresholds: Vec<u32> = ExplorerRank::ALL.iter().map(|r| r.min_points()).collect();
+    assert_eq!(
+        thresholds,
+        [0, 100, 400, 1600, 3200, 5000, 7500, 10500, 13500, 17000, 23000, 30000, 100000]
+    );
+
+    let mut general = SkySave::new_blank().general;
+    assert_eq!(general.rank_tier(), ExplorerRank::Normal);
+    assert_eq!(general.points_to_next_rank(), Some(100));
+
+    general.explorer_rank = 1599;
+    assert_eq!(general.rank_tier(), ExplorerRank::Silver);
+    assert_eq!(general.points_to_next_rank(), Some(1));
+
+    for rank in ExplorerRank::ALL {
+        general.set_rank_tier(rank);
+        assert_eq!(general.rank_tier(), rank);
+    }
+    assert_eq!(general.points_to_next_rank(), None);
+
+    general.explorer_rank = u32::MAX;
+    assert_eq!(general.rank_tier(), ExplorerRank::Guildmaster);
+    assert_eq!(ExplorerRank::MasterTwoStars.to_string(), "Master★★");
+}
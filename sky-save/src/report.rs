@@ -0,0 +1,173 @@
+//! Builds a human-readable report of the whole save, for bug reports and sharing.
+
+use crate::{SkySave, FIRST_RECRUIT_SLOT};
+use std::fmt::Write;
+
+/// The format of `SkySave::report`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ReportFormat {
+    Markdown,
+    PlainText,
+}
+
+impl SkySave {
+    /// Builds a report covering the general information, the team and the valid stored Pokémon.
+    /// Species, moves and IQ skills are listed by ID, the crate has no name tables yet.
+    pub fn report(&self, format: ReportFormat) -> String {
+        let mut out = String::new();
+        let md = format == ReportFormat::Markdown;
+        let heading = |out: &mut String, level: usize, text: &str| {
+            if md {
+                let _ = writeln!(out, "{} {}\n", "#".repeat(level), text);
+            } else {
+                let _ = writeln!(out, "{}\n{}\n", text, "=".repeat(text.chars().count()));
+            }
+        };
+        let item = if md { "- " } else { "  " };
+
+        let g = &self.general;
+        heading(&mut out, 1, &g.team_name.to_string_until_nul());
+        heading(&mut out, 2, "General");
+        let _ = writeln!(
+            out,
+            "{}Explorer rank: {} ({} points)",
+            item,
+            g.rank_tier(),
+            g.explorer_rank
+        );
+        let _ = writeln!(out, "{}Held money: {}", item, g.held_money);
+        let _ = writeln!(out, "{}Stored money: {}", item, g.stored_money);
+        let _ = writeln!(
+            out,
+            "{}Special episode held money: {}",
+            item, g.sp_episode_held_money
+        );
+        let _ = writeln!(out, "{}Adventures: {}", item, g.number_of_adventures);
+        let _ = writeln!(out, "{}Loaded block: {:?}\n", item, self.active_save_block);
+
+        heading(&mut out, 2, "Team");
+        for p in self.active_pokemon.iter().filter(|p| p.valid) {
+            heading(
+                &mut out,
+                3,
+                &format!("{} (Lv. {})", p.name.to_string_until_nul(), p.level),
+            );
+            let _ = writeln!(out, "{}Species ID: {}", item, p.id);
+            let _ = writeln!(out, "{}HP: {}/{}", item, p.current_hp, p.max_hp);
+            let _ = writeln!(
+                out,
+                "{}Attack: {}, Sp. Attack: {}, Defense: {}, Sp. Defense: {}",
+                item, p.attack, p.sp_attack, p.defense, p.sp_defense
+            );
+            let _ = writeln!(out, "{}EXP: {}, IQ: {}", item, p.exp, p.iq);
+            let moves: Vec<String> = p
+                .moves()
+                .iter()
+                .filter(|m| m.valid)
+                .map(|m| format!("{} ({} PP)", m.id, m.pp))
+                .collect();
+            let _ = writeln!(out, "{}Moves: {}", item, moves.join(", "));
+            let _ = writeln!(out, "{}IQ skills: {}\n", item, p.iq_map);
+        }
+
+        heading(&mut out, 2, "Stored Pokémon");
+        let rows: Vec<[String; 6]> = self
+            .stored_pokemon
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.valid)
+            .map(|(i, p)| {
+                [
+                    i.to_string(),
+                    p.name.to_string_until_nul(),
+                    p.id.to_string(),
+                    p.level.to_string(),
+                    p.iq.to_string(),
+                    p.hp.to_string(),
+                ]
+            })
+            .collect();
+        let header = ["Slot", "Name", "Species", "Level", "IQ", "HP"];
+
+        if md {
+            let _ = writeln!(out, "| {} |", header.join(" | "));
+            let _ = writeln!(out, "|{}", "---|".repeat(header.len()));
+            for r in &rows {
+                let _ = writeln!(out, "| {} |", r.join(" | "));
+            }
+        } else {
+            let widths: Vec<usize> = (0..header.len())
+                .map(|c| {
+                    rows.iter()
+                        .map(|r| r[c].chars().count())
+                        .chain([header[c].len()])
+                        .max()
+                        .unwrap_or(0)
+                })
+                .collect();
+            let line = |cells: Vec<&str>| {
+                cells
+                    .iter()
+                    .zip(&widths)
+                    .map(|(c, w)| format!("{:w$}", c, w = w))
+                    .collect::<Vec<_>>()
+                    .join("  ")
+            };
+
+            let _ = writeln!(out, "{}", line(header.to_vec()).trim_end());
+            for r in &rows {
+                let _ = writeln!(
+                    out,
+                    "{}",
+                    line(r.iter().map(String::as_str).collect()).trim_end()
+                );
+            }
+        }
+
+        let _ = writeln!(
+            out,
+            "\n{} of {} recruit slots used.",
+            self.stored_pokemon[FIRST_RECRUIT_SLOT..]
+                .iter()
+                .filter(|p| p.valid)
+                .count(),
+            self.stored_pokemon.len() - FIRST_RECRUIT_SLOT
+        );
+
+        let stats = self.box_stats();
+        let _ = writeln!(out);
+        heading(&mut out, 2, "Box statistics");
+        let _ = writeln!(out, "{}Recruited: {}", item, stats.recruited);
+        let _ = writeln!(out, "{}Species: {}", item, stats.species.len());
+        if let Some(iq) = stats.average_iq {
+            let _ = writeln!(out, "{}Average IQ: {:.1}", item, iq);
+        }
+        let levels: Vec<String> = stats
+            .levels
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| **n > 0)
+            .map(|(i, n)| format!("{}-{}: {}", i * 10 + 1, i * 10 + 10, n))
+            .collect();
+        let _ = writeln!(out, "{}Levels: {}", item, levels.join(", "));
+
+        out
+    }
+}
+
+#[test]
+fn test_report() {
+    let mut save = SkySave::from_slice(vec![0; 0x20000]).unwrap();
+    save.stored_pokemon[7].valid = true;
+    save.stored_pokemon[7].level = 12;
+    save.active_pokemon[0].valid = true;
+
+    let md = save.report(ReportFormat::Markdown);
+    assert!(md.contains("## Team"));
+    assert!(md.contains("| 7 |  | 0 | 12 | 0 | 0 |"));
+    assert!(md.contains("## Box statistics\n\n- Recruited: 1"));
+
+    let text = save.report(ReportFormat::PlainText);
+    assert!(text.contains("Stored Pokémon\n=============="));
+    assert!(!text.contains('|'));
+}
@@ -0,0 +1,222 @@
+//! Handles validating the roster numbers of the active team.
+
+use crate::{ActivePokemon, SkySave};
+use std::fmt::Display;
+use std::mem;
+
+/// A problem with the roster numbers of the active team.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum RosterProblem {
+    /// Several team members share the same roster number.
+    Duplicate {
+        roster_number: u16,
+        slots: Vec<usize>,
+    },
+    /// A valid team member has a roster number of zero.
+    Zero { slot: usize },
+    /// A roster number is larger than the size of the team.
+    OutOfRange {
+        slot: usize,
+        roster_number: u16,
+        team_size: usize,
+    },
+    /// A team member has no single matching valid stored Pokémon, see `SkySave::linked_stored_index`.
+    Unlinked { slot: usize },
+}
+
+impl RosterProblem {
+    /// Returns the team slots involved in the problem.
+    pub fn slots(&self) -> Vec<usize> {
+        match self {
+            RosterProblem::Duplicate { slots, .. } => slots.clone(),
+            RosterProblem::Zero { slot }
+            | RosterProblem::OutOfRange { slot, .. }
+            | RosterProblem::Unlinked { slot } => vec![*slot],
+        }
+    }
+}
+
+impl Display for RosterProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RosterProblem::Duplicate {
+                roster_number,
+                slots,
+            } => write!(
+                f,
+                "Roster number {} is used by slots {:?}",
+                roster_number, slots
+            ),
+            RosterProblem::Zero { slot } => write!(f, "Slot {} has a roster number of 0", slot),
+            RosterProblem::OutOfRange {
+                slot,
+                roster_number,
+                team_size,
+            } => write!(
+                f,
+                "Slot {} has roster number {}, but the team only has {} members",
+                slot, roster_number, team_size
+            ),
+            RosterProblem::Unlinked { slot } => {
+                write!(f, "Slot {} has no single matching stored Pokémon", slot)
+            }
+        }
+    }
+}
+
+impl SkySave {
+    /// Checks the roster numbers of the valid team members.
+    /// They should be unique, contiguous from 1, and each member should be linked to a valid stored Pokémon.
+    pub fn validate_roster(&self) -> Vec<RosterProblem> {
+        let mut problems = Vec::new();
+        let team_size = self.team_size();
+        let members: Vec<(usize, u16)> = self
+            .active_pokemon
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.valid)
+            .map(|(i, p)| (i, p.roaster_number))
+            .collect();
+
+        for &(slot, roster_number) in &members {
+            if roster_number == 0 {
+                problems.push(RosterProblem::Zero { slot });
+            } else if roster_number as usize > team_size {
+                problems.push(RosterProblem::OutOfRange {
+                    slot,
+                    roster_number,
+                    team_size,
+                });
+            }
+        }
+
+        let mut seen = Vec::new();
+        for &(_, roster_number) in &members {
+            if roster_number == 0 || seen.contains(&roster_number) {
+                continue;
+            }
+            seen.push(roster_number);
+
+            let slots: Vec<usize> = members
+                .iter()
+                .filter(|(_, r)| *r == roster_number)
+                .map(|(s, _)| *s)
+                .collect();
+            if slots.len() > 1 {
+                problems.push(RosterProblem::Duplicate {
+                    roster_number,
+                    slots,
+                });
+            }
+        }
+
+        for &(slot, _) in &members {
+            if self.linked_stored_index(slot).is_none() {
+                problems.push(RosterProblem::Unlinked { slot });
+            }
+        }
+
+        problems
+    }
+
+    /// Reassigns the roster numbers of the valid team members to 1..=N, in slot order.
+    pub fn normalize_roster(&mut self) {
+        for (i, p) in self
+            .active_pokemon
+            .iter_mut()
+            .filter(|p| p.valid)
+            .enumerate()
+        {
+            p.roaster_number = i as u16 + 1;
+        }
+    }
+
+    /// The team slots in roster order: valid members by roster number, with a roster number of 0 last,
+    /// then the empty slots. Ties keep slot order.
+    fn roster_order(&self) -> Vec<usize> {
+        let mut slots: Vec<usize> = (0..self.active_pokemon.len()).collect();
+        slots.sort_by_key(|&i| {
+            let p = &self.active_pokemon[i];
+            (!p.valid, p.roaster_number == 0, p.roaster_number)
+        });
+
+        slots
+    }
+
+    /// Returns the valid team members in roster order, see `sort_team_slots_by_roster`.
+    pub fn team_in_roster_order(&self) -> Vec<&ActivePokemon> {
+        self.roster_order()
+            .into_iter()
+            .map(|i| &self.active_pokemon[i])
+            .filter(|p| p.valid)
+            .collect()
+    }
+
+    /// Moves the team members so their slots follow their roster numbers, leaving everything else as is.
+    /// Members are ordered by roster number, members with a roster number of 0 come after the others,
+    /// and members sharing a roster number keep their relative slot order. Empty slots are moved to the end.
+    pub fn sort_team_slots_by_roster(&mut self) {
+        let order = self.roster_order();
+        let mut sorted: Vec<ActivePokemon> = order
+            .iter()
+            .map(|&i| mem::take(&mut self.active_pokemon[i]))
+            .collect();
+
+        for (slot, p) in self.active_pokemon.iter_mut().zip(sorted.drain(..)) {
+            *slot = p;
+        }
+    }
+}
+
+#[test]
+fn test_validate_roster() {
+    let mut save = SkySave::from_slice(vec![0; 0x20000]).unwrap();
+    for (slot, roster_number) in [(0, 1), (1, 1), (2, 0), (3, 7)] {
+        save.active_pokemon[slot].valid = true;
+        save.active_pokemon[slot].roaster_number = roster_number;
+    }
+
+    let problems = save.validate_roster();
+    assert!(problems.contains(&RosterProblem::Zero { slot: 2 }));
+    assert!(problems.contains(&RosterProblem::OutOfRange {
+        slot: 3,
+        roster_number: 7,
+        team_size: 4
+    }));
+    assert!(problems.contains(&RosterProblem::Duplicate {
+        roster_number: 1,
+        slots: vec![0, 1]
+    }));
+    assert!(problems.contains(&RosterProblem::Unlinked { slot: 0 }));
+
+    save.normalize_roster();
+    let roster: Vec<u16> = save
+        .active_pokemon
+        .iter()
+        .map(|p| p.roaster_number)
+        .collect();
+    assert_eq!(roster, [1, 2, 3, 4]);
+}
+
+#[test]
+fn test_sort_team_slots_by_roster() {
+    let mut save = SkySave::from_slice(vec![0; 0x20000]).unwrap();
+    let team = [(true, 3, 10), (true, 0, 20), (false, 1, 30), (true, 3, 40)];
+    for (p, (valid, roster_number, level)) in save.active_pokemon.iter_mut().zip(team) {
+        p.valid = valid;
+        p.roaster_number = roster_number;
+        p.level = level;
+    }
+
+    let levels: Vec<u8> = save
+        .team_in_roster_order()
+        .iter()
+        .map(|p| p.level)
+        .collect();
+    assert_eq!(levels, [10, 40, 20]);
+
+    save.sort_team_slots_by_roster();
+    let levels: Vec<u8> = save.active_pokemon.iter().map(|p| p.level).collect();
+    assert_eq!(levels, [10, 40, 20, 30]);
+    assert!(!save.active_pokemon[3].valid);
+}
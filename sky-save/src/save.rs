@@ -1,29 +1,122 @@
 //! Handles loading and storing the save data.
 
 use crate::error::SaveError;
-use crate::offsets::{active, general, save, stored};
-use crate::{ActivePokemon, PmdString, StoredPokemon};
+use crate::layout::{check_field, load_field, store_field, LayoutField};
+use crate::offsets::{active, general, save, stored, BitRange};
+use crate::{ActivePokemon, AuditLog, PmdString, SaveContainer, StoredPokemon, FIRST_RECRUIT_SLOT};
 use arrayvec::ArrayVec;
 use bitvec::bitarr;
-use bitvec::field::BitField;
 use bitvec::order::Lsb0;
 use bitvec::slice::BitSlice;
 use bitvec::view::BitView;
+use log::{debug, warn};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fs;
+use std::io::{self, Write};
 use std::ops::Range;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// File size must be at least 128Kib.
-const MIN_SAVE_LEN: usize = 0x20000;
+pub(crate) const MIN_SAVE_LEN: usize = 0x20000;
 
-fn checksum(data: &[u8], data_range: Range<usize>) -> [u8; 4] {
-    (data[data_range]
-        .chunks(4)
-        .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap())) // Safe, four bytes.O
-        .fold(0u64, |acc, u| acc + u as u64) as u32)
+/// Sums the little-endian words of `data_range`.
+/// A trailing partial word is zero-extended, as the game does.
+///
+/// Words are summed 16 bytes at a time into four independent lanes, which the compiler vectorizes,
+/// wrapping addition gives the same low 32 bits as summing into a wider integer.
+pub(crate) fn checksum(data: &[u8], data_range: Range<usize>) -> [u8; 4] {
+    let data = &data[data_range];
+    let word = |b: &[u8]| u32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+
+    let mut strides = data.chunks_exact(16);
+    let mut lanes = [0u32; 4];
+    for stride in &mut strides {
+        lanes[0] = lanes[0].wrapping_add(word(&stride[0..4]));
+        lanes[1] = lanes[1].wrapping_add(word(&stride[4..8]));
+        lanes[2] = lanes[2].wrapping_add(word(&stride[8..12]));
+        lanes[3] = lanes[3].wrapping_add(word(&stride[12..16]));
+    }
+
+    let tail = strides.remainder().chunks(4).fold(0u32, |acc, chunk| {
+        let mut w = [0; 4];
+        w[..chunk.len()].copy_from_slice(chunk);
+        acc.wrapping_add(u32::from_le_bytes(w))
+    });
+
+    lanes
+        .into_iter()
+        .fold(tail, u32::wrapping_add)
         .to_le_bytes()
 }
 
+/// Cheaply checks whether `data` looks like a valid save file.
+/// Only the length and the primary and backup checksums are checked, no structure is parsed.
+///
+/// This is a heuristic, a `true` result doesn't guarantee `SkySave::from_slice` succeeds,
+/// it's meant for filtering files before attempting a full load.
+pub fn is_valid_save(data: &[u8]) -> bool {
+    if data.len() < MIN_SAVE_LEN {
+        return false;
+    }
+
+    let data = SaveContainer::detect(data).image(data);
+    data[save::PRIMARY_READ_CHECKSUM] == checksum(data, save::PRIMARY_CHECKSUM)
+        || data[save::BACKUP_READ_CHECKSUM] == checksum(data, save::BACKUP_CHECKSUM)
+}
+
+/// Reads a file and checks it with `is_valid_save`.
+/// Returns `false` if the file can't be read.
+pub fn quick_probe<P: AsRef<Path>>(filename: P) -> bool {
+    fs::read(filename).is_ok_and(|data| is_valid_save(&data))
+}
+
+pub(crate) fn fix_data_checksums(data: &mut [u8]) {
+    let pri_sum = checksum(data, save::PRIMARY_CHECKSUM);
+    let backup_sum = checksum(data, save::BACKUP_CHECKSUM);
+    let quick_sum = checksum(data, save::QUICKSAVE_CHECKSUM);
+
+    data[save::PRIMARY_READ_CHECKSUM].copy_from_slice(&pri_sum);
+    data[save::BACKUP_READ_CHECKSUM].copy_from_slice(&backup_sum);
+    data[save::QUICKSAVE_READ_CHECKSUM].copy_from_slice(&quick_sum);
+}
+
+fn warn_suspicious(kind: &str, index: usize, level: u8, name: &PmdString) {
+    if level > 100 {
+        warn!("{} Pokémon {} has level {}", kind, index, level);
+    }
+    if !name.to_save_bytes().contains(&0) {
+        warn!("{} Pokémon {} has a name with no terminator", kind, index);
+    }
+}
+
+fn load_save_field<T: LayoutField, const LEN: usize>(
+    data: &[u8],
+    active_save_block: ActiveSaveBlock,
+    range: BitRange<LEN>,
+) -> T {
+    load_field(&data.view_bits()[active_save_block as usize * 8..], range)
+}
+
+/// Stores a field, failing instead of truncating values too large for `range`.
+fn store_save_field<T: LayoutField, const LEN: usize>(
+    data: &mut [u8],
+    active_save_block: ActiveSaveBlock,
+    range: BitRange<LEN>,
+    value: &T,
+    field: &'static str,
+) -> Result<(), SaveError> {
+    check_field(range, value, field)?;
+    store_field(
+        &mut data.view_bits_mut()[active_save_block as usize * 8..],
+        range,
+        value,
+    );
+
+    Ok(())
+}
+
 fn load_save_slice(data: &[u8], active_save_block: ActiveSaveBlock, range: Range<usize>) -> &[u8] {
     &data[range.start + active_save_block as usize..range.end + active_save_block as usize]
 }
@@ -38,7 +131,7 @@ fn store_save_slice(
         .copy_from_slice(value);
 }
 
-fn load_save_bits(
+pub(crate) fn load_save_bits(
     data: &BitSlice<u8, Lsb0>,
     active_save_block: ActiveSaveBlock,
     range: Range<usize>,
@@ -46,7 +139,7 @@ fn load_save_bits(
     &data[range.start + active_save_block as usize * 8..range.end + active_save_block as usize * 8]
 }
 
-fn store_save_bits(
+pub(crate) fn store_save_bits(
     data: &mut BitSlice<u8, Lsb0>,
     active_save_block: ActiveSaveBlock,
     range: Range<usize>,
@@ -56,6 +149,26 @@ fn store_save_bits(
         .copy_from_bitslice(value);
 }
 
+/// The bits of slot `index` in an image of at least `data_len` bytes,
+/// within `bits` holding `count` slots of `len` bits.
+pub(crate) fn slot_bits(
+    data_len: usize,
+    bits: Range<usize>,
+    len: usize,
+    count: usize,
+    index: usize,
+) -> Result<Range<usize>, SaveError> {
+    if data_len < MIN_SAVE_LEN {
+        return Err(SaveError::InvalidSize);
+    }
+    if index >= count {
+        return Err(SaveError::InvalidSlot(index));
+    }
+
+    let start = bits.start + index * len;
+    Ok(start..start + len)
+}
+
 /// The current active save block.
 /// Holds it's start offset.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -65,6 +178,270 @@ pub enum ActiveSaveBlock {
     Backup = save::BACKUP_SAVE.start,
 }
 
+/// One of the three blocks of the save file.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SaveBlock {
+    Primary,
+    Backup,
+    Quicksave,
+}
+
+impl SaveBlock {
+    /// The byte range of the block, including its checksum.
+    pub fn range(&self) -> Range<usize> {
+        match self {
+            SaveBlock::Primary => save::PRIMARY_SAVE,
+            SaveBlock::Backup => save::BACKUP_SAVE,
+            SaveBlock::Quicksave => save::QUICKSAVE,
+        }
+    }
+
+    /// The byte range of the block's checksum.
+    pub fn checksum_range(&self) -> Range<usize> {
+        match self {
+            SaveBlock::Primary => save::PRIMARY_READ_CHECKSUM,
+            SaveBlock::Backup => save::BACKUP_READ_CHECKSUM,
+            SaveBlock::Quicksave => save::QUICKSAVE_READ_CHECKSUM,
+        }
+    }
+
+    /// The byte range the block's checksum is calculated over.
+    pub fn data_range(&self) -> Range<usize> {
+        match self {
+            SaveBlock::Primary => save::PRIMARY_CHECKSUM,
+            SaveBlock::Backup => save::BACKUP_CHECKSUM,
+            SaveBlock::Quicksave => save::QUICKSAVE_CHECKSUM,
+        }
+    }
+
+    /// Recalculates the checksum of this block in the raw save image `data`.
+    /// Meant for patching single structures with `General::save` or `StoredPokemon::write_to_slot`.
+    pub fn fix_checksum(&self, data: &mut [u8]) -> Result<(), SaveError> {
+        if data.len() < MIN_SAVE_LEN {
+            return Err(SaveError::InvalidSize);
+        }
+
+        let sum = checksum(data, self.data_range());
+        data[self.checksum_range()].copy_from_slice(&sum);
+        Ok(())
+    }
+}
+
+impl From<ActiveSaveBlock> for SaveBlock {
+    fn from(value: ActiveSaveBlock) -> Self {
+        match value {
+            ActiveSaveBlock::Primary => SaveBlock::Primary,
+            ActiveSaveBlock::Backup => SaveBlock::Backup,
+        }
+    }
+}
+
+/// Options controlling how `SkySave::from_slice_with` loads a save.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct ParseOptions {
+    /// Loads the save even if neither the primary nor the backup checksum is valid.
+    pub ignore_checksums: bool,
+    /// Loads this block instead of letting the primary block win when both are valid.
+    /// Loading fails if its checksum is invalid, unless `ignore_checksums` is set.
+    /// When both checksums are invalid and `ignore_checksums` is set, the primary block is loaded by default.
+    pub preferred_block: Option<ActiveSaveBlock>,
+}
+
+/// Options controlling how `SkySave::save_with` writes a save.
+#[derive(Debug, Copy, Clone)]
+pub struct SaveOptions {
+    /// Writes to a temporary file in the target's directory and renames it over the target,
+    /// so an interrupted write leaves the old file intact. Enabled by default.
+    /// Disable for targets that can't be renamed over, such as pipes or device files.
+    pub atomic: bool,
+}
+
+impl Default for SaveOptions {
+    fn default() -> Self {
+        Self { atomic: true }
+    }
+}
+
+/// Writes `data` to a temporary file next to `dest`, syncs it and renames it over `dest`.
+/// If the rename fails, the temporary file is copied over `dest` instead, which isn't atomic.
+/// The temporary file is named uniquely per call, so concurrent saves of the same file don't share it,
+/// and it's removed if any step fails.
+pub(crate) fn write_atomic(dest: &Path, data: &[u8]) -> Result<(), SaveError> {
+    write_atomic_with(dest, data, || Ok(()))
+}
+
+/// Like `write_atomic`, calling `before_replace` once the temporary file is written and synced,
+/// right before it replaces `dest`. If `before_replace` fails, `dest` is left alone.
+fn write_atomic_with(
+    dest: &Path,
+    data: &[u8],
+    before_replace: impl FnOnce() -> Result<(), SaveError>,
+) -> Result<(), SaveError> {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let name = dest.file_name().unwrap_or_default().to_string_lossy();
+    let temp = dest.with_file_name(format!(
+        ".{}.{}.{}.tmp",
+        name,
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    ));
+    let error = |source| SaveError::AtomicWrite {
+        temp: temp.clone(),
+        dest: dest.to_path_buf(),
+        source,
+    };
+
+    let written = fs::File::create(&temp).and_then(|mut f| {
+        f.write_all(data)?;
+        f.sync_all()
+    });
+    if let Err(e) = written {
+        let _ = fs::remove_file(&temp);
+        return Err(error(e));
+    }
+    if let Err(e) = before_replace() {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+
+    if let Err(e) = fs::rename(&temp, dest) {
+        warn!(
+            "Couldn't rename {:?} over {:?} ({}), copying instead",
+            temp, dest, e
+        );
+        let copied = fs::copy(&temp, dest);
+        let _ = fs::remove_file(&temp);
+        copied.map_err(error)?;
+    }
+
+    Ok(())
+}
+
+/// The path of the `n`th numbered backup of `path`, e.g. `file.sav.2`.
+fn numbered_backup(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", n));
+    path.with_file_name(name)
+}
+
+/// Shifts the numbered backups of `path` up by one, dropping the `keep`th,
+/// then copies `path` to the first. Missing backups are skipped.
+/// Only `path.1` to `path.keep` are touched, so backups of backups are never created.
+fn rotate_backups(path: &Path, keep: usize) -> Result<(), SaveError> {
+    if keep == 0 {
+        return Ok(());
+    }
+    let skip_missing = |result: io::Result<()>| match result {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(SaveError::Io(e)),
+        _ => Ok(()),
+    };
+
+    skip_missing(fs::remove_file(numbered_backup(path, keep)))?;
+    for n in (1..keep).rev() {
+        skip_missing(fs::rename(
+            numbered_backup(path, n),
+            numbered_backup(path, n + 1),
+        ))?;
+    }
+    // Copied rather than renamed, the original stays in place if the write fails.
+    skip_missing(fs::copy(path, numbered_backup(path, 1)).map(|_| ()))?;
+    debug!("Rotated up to {} backups of {:?}", keep, path);
+
+    Ok(())
+}
+
+/// The result of `validate`.
+pub(crate) struct Validated {
+    pub(crate) active_save_block: ActiveSaveBlock,
+    /// Whether the active block's checksum is valid.
+    pub(crate) checksums_ok: bool,
+    pub(crate) quicksave_valid: bool,
+}
+
+/// Checks the checksums of the save image `data` and selects the block to load, see `SkySave::from_slice_with`.
+/// Every offset the crate reads lies within the first `MIN_SAVE_LEN` bytes, so nothing past this check can go out of bounds.
+pub(crate) fn validate(data: &[u8], options: ParseOptions) -> Result<Validated, SaveError> {
+    if data.len() < MIN_SAVE_LEN {
+        return Err(SaveError::Malformed {
+            context: "the save image is shorter than 128KiB",
+        });
+    }
+
+    let read = |range: Range<usize>| -> Result<[u8; 4], SaveError> {
+        data.get(range)
+            .and_then(|b| b.try_into().ok())
+            .ok_or(SaveError::Malformed {
+                context: "a stored checksum is out of bounds",
+            })
+    };
+    let pri_read = read(save::PRIMARY_READ_CHECKSUM)?;
+    let backup_read = read(save::BACKUP_READ_CHECKSUM)?;
+    let quick_read = read(save::QUICKSAVE_READ_CHECKSUM)?;
+
+    let pri_sum = checksum(data, save::PRIMARY_CHECKSUM);
+    let backup_sum = checksum(data, save::BACKUP_CHECKSUM);
+    let quick_sum = checksum(data, save::QUICKSAVE_CHECKSUM);
+
+    let pri_matches = pri_sum == pri_read;
+    let backup_matches = backup_sum == backup_read;
+    let quick_matches = quick_sum == quick_read;
+
+    if !pri_matches && !backup_matches && !options.ignore_checksums {
+        return Err(SaveError::InvalidChecksum {
+            pri_expected: pri_read,
+            pri_found: pri_sum,
+            bak_expected: backup_read,
+            bak_found: backup_sum,
+        });
+    }
+
+    debug!(
+        "Checksums: primary read {:02X?} computed {:02X?}, backup read {:02X?} computed {:02X?}, quicksave read {:02X?} computed {:02X?}",
+        pri_read, pri_sum, backup_read, backup_sum, quick_read, quick_sum
+    );
+
+    let active_save_block = match options.preferred_block {
+        Some(block) => block,
+        None if !pri_matches && backup_matches => ActiveSaveBlock::Backup,
+        None => ActiveSaveBlock::Primary,
+    };
+
+    let (block_read, block_sum) = match active_save_block {
+        ActiveSaveBlock::Primary => (pri_read, pri_sum),
+        ActiveSaveBlock::Backup => (backup_read, backup_sum),
+    };
+    if block_read != block_sum && !options.ignore_checksums {
+        return Err(SaveError::InvalidBlockChecksum {
+            block: active_save_block,
+            expected: block_read,
+            found: block_sum,
+        });
+    }
+
+    debug!(
+        "Selected the {:?} block ({})",
+        active_save_block,
+        match (options.preferred_block, pri_matches) {
+            (Some(_), _) => "preferred by the parse options",
+            (None, true) => "primary checksum is valid",
+            (None, false) if backup_matches => "primary checksum is invalid, backup is valid",
+            (None, false) => "both checksums are invalid, checksums are ignored",
+        }
+    );
+    if block_read != block_sum {
+        warn!(
+            "Loading the {:?} block despite its invalid checksum",
+            active_save_block
+        );
+    }
+
+    Ok(Validated {
+        active_save_block,
+        checksums_ok: block_read == block_sum,
+        quicksave_valid: quick_matches,
+    })
+}
+
 /// Holds general information about the saved game.
 #[derive(Debug)]
 pub struct General {
@@ -77,23 +454,12 @@ pub struct General {
 }
 
 impl General {
-    fn load(data: &[u8], active_save_block: ActiveSaveBlock) -> Self {
+    /// Decodes the general information from `active_save_block` of the raw save image `data`,
+    /// without parsing anything else. The checksums aren't checked.
+    ///
+    /// Panics if `data` is shorter than 128KiB.
+    pub fn load(data: &[u8], active_save_block: ActiveSaveBlock) -> Self {
         let team_name = load_save_slice(data, active_save_block, general::TEAM_NAME);
-        let held_money = load_save_bits(
-            data.view_bits(),
-            active_save_block,
-            general::HELD_MONEY_BITS,
-        );
-        let sp_episode_held_money = load_save_bits(
-            data.view_bits(),
-            active_save_block,
-            general::SP_EPISODE_HELD_MONEY_BITS,
-        );
-        let stored_money = load_save_bits(
-            data.view_bits(),
-            active_save_block,
-            general::STORED_MONEY_BITS,
-        );
         let number_of_adventures =
             load_save_slice(data, active_save_block, general::NUMBER_OF_ADVENTURERS)
                 .try_into()
@@ -104,15 +470,45 @@ impl General {
 
         Self {
             team_name: PmdString::from(team_name),
-            held_money: held_money.load_le(),
-            sp_episode_held_money: sp_episode_held_money.load_le(),
-            stored_money: stored_money.load_le(),
+            held_money: load_save_field(data, active_save_block, general::HELD_MONEY_BITS),
+            sp_episode_held_money: load_save_field(
+                data,
+                active_save_block,
+                general::SP_EPISODE_HELD_MONEY_BITS,
+            ),
+            stored_money: load_save_field(data, active_save_block, general::STORED_MONEY_BITS),
             number_of_adventures: i32::from_le_bytes(number_of_adventures),
             explorer_rank: u32::from_le_bytes(explorer_rank),
         }
     }
 
-    fn save(&self, data: &mut [u8], active_save_block: ActiveSaveBlock) {
+    /// Checks that every field fits, so `save` can't fail halfway.
+    pub fn check_widths(&self) -> Result<(), SaveError> {
+        check_field(general::HELD_MONEY_BITS, &self.held_money, "held_money")?;
+        check_field(
+            general::SP_EPISODE_HELD_MONEY_BITS,
+            &self.sp_episode_held_money,
+            "sp_episode_held_money",
+        )?;
+        check_field(
+            general::STORED_MONEY_BITS,
+            &self.stored_money,
+            "stored_money",
+        )
+    }
+
+    /// Encodes the general information into `active_save_block` of the raw save image `data`.
+    /// The block's checksum is left stale, see `SaveBlock::fix_checksum`.
+    /// Fails without writing anything if a value is too large for its field, see `check_widths`.
+    ///
+    /// Panics if `data` is shorter than 128KiB.
+    pub fn save(
+        &self,
+        data: &mut [u8],
+        active_save_block: ActiveSaveBlock,
+    ) -> Result<(), SaveError> {
+        self.check_widths()?;
+
         store_save_slice(
             data,
             active_save_block,
@@ -120,24 +516,27 @@ impl General {
             self.team_name.to_save_bytes().as_slice(),
         );
 
-        store_save_bits(
-            data.view_bits_mut(),
+        store_save_field(
+            data,
             active_save_block,
             general::HELD_MONEY_BITS,
-            &self.held_money.to_le_bytes().view_bits::<Lsb0>()[0..24],
-        );
-        store_save_bits(
-            data.view_bits_mut(),
+            &self.held_money,
+            "held_money",
+        )?;
+        store_save_field(
+            data,
             active_save_block,
             general::SP_EPISODE_HELD_MONEY_BITS,
-            &self.sp_episode_held_money.to_le_bytes().view_bits::<Lsb0>()[0..24],
-        );
-        store_save_bits(
-            data.view_bits_mut(),
+            &self.sp_episode_held_money,
+            "sp_episode_held_money",
+        )?;
+        store_save_field(
+            data,
             active_save_block,
             general::STORED_MONEY_BITS,
-            &self.stored_money.to_le_bytes().view_bits::<Lsb0>()[0..24],
-        );
+            &self.stored_money,
+            "stored_money",
+        )?;
         store_save_slice(
             data,
             active_save_block,
@@ -150,6 +549,8 @@ impl General {
             general::EXPLORER_RANK,
             &self.explorer_rank.to_le_bytes(),
         );
+
+        Ok(())
     }
 }
 
@@ -160,11 +561,23 @@ impl General {
 pub struct SkySave {
     pub data: Vec<u8>,
     pub active_save_block: ActiveSaveBlock,
+    /// `false` if the save was loaded with `ParseOptions::ignore_checksums` and the active block's checksum is invalid.
+    /// Saving mirrors the active block to the other one once it changed.
+    pub checksums_ok: bool,
     pub quicksave_valid: bool,
+    /// The layout of the file the save was loaded from, reproduced by `save`.
+    pub container: SaveContainer,
 
     pub general: General,
-    pub stored_pokemon: ArrayVec<StoredPokemon, 550>,
-    pub active_pokemon: ArrayVec<ActivePokemon, 4>,
+    /// Boxed to keep `SkySave` small and cheap to move.
+    pub stored_pokemon: Box<ArrayVec<StoredPokemon, { stored::STORED_PKM_COUNT }>>,
+    pub active_pokemon: ArrayVec<ActivePokemon, { active::ACTIVE_PKM_COUNT }>,
+
+    /// Records edits made through `FieldDescriptor::set` when enabled, see `enable_audit_log`.
+    pub audit_log: Option<AuditLog>,
+
+    /// The image as last loaded or saved, see `prepare_image`.
+    saved_image: Vec<u8>,
 }
 
 impl SkySave {
@@ -179,63 +592,100 @@ impl SkySave {
     ///
     /// After validation, every structure is parsed from the save data.
     pub fn from_slice<S: AsRef<[u8]>>(data: S) -> Result<Self, SaveError> {
+        Self::from_slice_with(data, ParseOptions::default())
+    }
+
+    /// Loads the save data like `from_slice`, with the given options.
+    /// With `ignore_checksums`, saves with two invalid checksums are loaded anyway and `checksums_ok` is set to `false`.
+    /// Saving such a save recalculates the checksums as usual.
+    pub fn from_slice_with<S: AsRef<[u8]>>(
+        data: S,
+        options: ParseOptions,
+    ) -> Result<Self, SaveError> {
         let data = data.as_ref();
 
         if data.len() < MIN_SAVE_LEN {
             return Err(SaveError::InvalidSize);
         }
 
-        let pri_read: [u8; 4] = data[save::PRIMARY_READ_CHECKSUM].try_into().unwrap(); // Safe, four bytes.
-        let backup_read: [u8; 4] = data[save::BACKUP_READ_CHECKSUM].try_into().unwrap(); // Safe, four bytes.
-        let quick_read: [u8; 4] = data[save::QUICKSAVE_READ_CHECKSUM].try_into().unwrap(); // Safe, four bytes.
-
-        let pri_sum = checksum(data, save::PRIMARY_CHECKSUM);
-        let backup_sum = checksum(data, save::BACKUP_CHECKSUM);
-        let quick_sum = checksum(data, save::QUICKSAVE_CHECKSUM);
-
-        let pri_matches = pri_sum == pri_read;
-        let backup_matches = backup_sum == backup_read;
-        let quick_matches = quick_sum == quick_read;
-
-        if !pri_matches && !backup_matches {
-            return Err(SaveError::InvalidChecksum {
-                pri_expected: pri_read,
-                pri_found: pri_sum,
-                bak_expected: backup_read,
-                bak_found: backup_sum,
-            });
+        let container = SaveContainer::detect(data);
+        if container != SaveContainer::Raw {
+            debug!("Loading the save image from a {:?} dump", container);
         }
+        let data = container.image(data);
 
-        let active_save_block = if pri_matches {
-            ActiveSaveBlock::Primary
-        } else {
-            ActiveSaveBlock::Backup
-        };
+        let Validated {
+            active_save_block,
+            checksums_ok,
+            quicksave_valid,
+        } = validate(data, options)?;
 
         let general = General::load(data, active_save_block);
         let bits = load_save_bits(data.view_bits(), active_save_block, stored::STORED_PKM_BITS);
 
-        let stored_pokemon: ArrayVec<StoredPokemon, 550> = bits
-            .chunks(stored::STORED_PKM_BIT_LEN)
-            .map(StoredPokemon::from_bitslice)
-            .collect();
+        let mut stored_pokemon: Box<ArrayVec<StoredPokemon, { stored::STORED_PKM_COUNT }>> =
+            Box::default();
+        stored_pokemon.extend(
+            bits.chunks(stored::STORED_PKM_BIT_LEN)
+                .map(StoredPokemon::from_bitslice),
+        );
 
         let bits = load_save_bits(data.view_bits(), active_save_block, active::ACTIVE_PKM_BITS);
-        let active_pokemon: ArrayVec<ActivePokemon, 4> = bits
+        let active_pokemon: ArrayVec<ActivePokemon, { active::ACTIVE_PKM_COUNT }> = bits
             .chunks(active::ACTIVE_PKM_BIT_LEN)
             .map(ActivePokemon::from_bitslice)
             .collect();
 
+        debug!(
+            "Parsed {} valid stored and {} valid active Pokémon",
+            stored_pokemon.iter().filter(|p| p.valid).count(),
+            active_pokemon.iter().filter(|p| p.valid).count()
+        );
+        for (i, p) in stored_pokemon.iter().enumerate().filter(|(_, p)| p.valid) {
+            warn_suspicious("stored", i, p.level, &p.name);
+        }
+        for (i, p) in active_pokemon.iter().enumerate().filter(|(_, p)| p.valid) {
+            warn_suspicious("active", i, p.level, &p.name);
+        }
+
         Ok(SkySave {
             data: data.to_vec(),
             active_save_block,
-            quicksave_valid: quick_matches,
+            checksums_ok,
+            quicksave_valid,
+            container,
             general,
             stored_pokemon,
             active_pokemon,
+            audit_log: None,
+            saved_image: data.to_vec(),
         })
     }
 
+    /// Loads the save data from the given block, even if the other block is valid too.
+    /// Saving afterward mirrors this block forward, see `ParseOptions::preferred_block`.
+    pub fn from_slice_block<S: AsRef<[u8]>>(
+        data: S,
+        block: ActiveSaveBlock,
+    ) -> Result<Self, SaveError> {
+        Self::from_slice_with(
+            data,
+            ParseOptions {
+                preferred_block: Some(block),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Creates a save with every byte zeroed and valid checksums, loaded from the primary block.
+    /// Regions the crate doesn't model stay zeroed, the game may need to save once to initialize them.
+    pub fn new_blank() -> Self {
+        let mut data = vec![0; MIN_SAVE_LEN];
+        fix_data_checksums(&mut data);
+
+        Self::from_slice(data).expect("a zeroed save with fixed checksums is valid")
+    }
+
     /// Loads save data from a file.
     pub fn open<P: AsRef<Path>>(filename: P) -> Result<Self, SaveError> {
         let data = fs::read(filename).map_err(SaveError::Io)?;
@@ -245,28 +695,280 @@ impl SkySave {
     /// Recalculates the checksums for each save block.
     /// Writes the checksums to the save data.
     pub fn fix_checksums(&mut self) {
-        let pri_sum = checksum(&self.data, save::PRIMARY_CHECKSUM);
-        let backup_sum = checksum(&self.data, save::BACKUP_CHECKSUM);
-        let quick_sum = checksum(&self.data, save::QUICKSAVE_CHECKSUM);
+        fix_data_checksums(&mut self.data);
+    }
 
-        self.data[save::PRIMARY_READ_CHECKSUM].copy_from_slice(&pri_sum);
-        self.data[save::BACKUP_READ_CHECKSUM].copy_from_slice(&backup_sum);
-        self.data[save::QUICKSAVE_READ_CHECKSUM].copy_from_slice(&quick_sum);
+    /// Returns `true` if the checksum stored in `block` matches its data.
+    pub fn block_checksum_ok(&self, block: SaveBlock) -> bool {
+        self.data[block.checksum_range()] == checksum(&self.data, block.data_range())
     }
 
-    /// Saves all changes to `data`. Recalculates the checksums and writes to a file.
-    pub fn save<P: AsRef<Path>>(&mut self, filename: P) -> Result<(), SaveError> {
-        let active_range = match self.active_save_block {
-            ActiveSaveBlock::Primary => save::PRIMARY_SAVE,
-            ActiveSaveBlock::Backup => save::BACKUP_SAVE,
+    /// Copies the raw active block over the other one and recalculates the checksums.
+    /// Used to repair a corrupted primary block from the backup, changes to the parsed structures aren't written.
+    pub fn repair_from_active_block(&mut self) {
+        let (active, other) = match self.active_save_block {
+            ActiveSaveBlock::Primary => (SaveBlock::Primary, SaveBlock::Backup),
+            ActiveSaveBlock::Backup => (SaveBlock::Backup, SaveBlock::Primary),
+        };
+
+        self.data.copy_within(active.range(), other.range().start);
+        self.fix_checksums();
+        self.checksums_ok = true;
+        debug!("Repaired the {:?} block from the {:?} block", other, active);
+    }
+
+    /// Copies the stored Pokémon at `from` to the stored slot `to`, leaving the source untouched.
+    /// Fails if the source is empty, or if the destination is occupied and `overwrite` is `false`.
+    pub fn clone_stored(
+        &mut self,
+        from: usize,
+        to: usize,
+        overwrite: bool,
+    ) -> Result<(), SaveError> {
+        let src = self
+            .stored_pokemon
+            .get(from)
+            .ok_or(SaveError::InvalidSlot(from))?;
+        let dst = self
+            .stored_pokemon
+            .get(to)
+            .ok_or(SaveError::InvalidSlot(to))?;
+
+        if !src.valid {
+            return Err(SaveError::EmptySlot(from));
+        }
+        if dst.valid && !overwrite && from != to {
+            return Err(SaveError::SlotOccupied(to));
+        }
+
+        self.stored_pokemon[to] = self.stored_pokemon[from].clone();
+        Ok(())
+    }
+
+    /// Groups the valid recruits with identical species, level, stats, moves and name.
+    /// Only groups with at least two slots are returned, each sorted by slot, in order of their first slot.
+    /// The hero, partner and special episode slots are never included.
+    pub fn find_duplicate_stored(&self) -> Vec<Vec<usize>> {
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        let mut keys: HashMap<_, usize> = HashMap::new();
+
+        for (i, p) in self
+            .stored_pokemon
+            .iter()
+            .enumerate()
+            .skip(FIRST_RECRUIT_SLOT)
+            .filter(|(_, p)| p.valid)
+        {
+            let key = (
+                p.id,
+                p.level,
+                [
+                    p.hp,
+                    p.attack as u16,
+                    p.sp_attack as u16,
+                    p.defense as u16,
+                    p.sp_defense as u16,
+                ],
+                p.moves().map(|m| m.to_bits()),
+                p.name.clone(),
+            );
+
+            match keys.get(&key) {
+                Some(&g) => groups[g].push(i),
+                None => {
+                    keys.insert(key, groups.len());
+                    groups.push(vec![i]);
+                }
+            }
+        }
+
+        groups.retain(|g| g.len() > 1);
+        groups
+    }
+
+    /// Copies the stored Pokémon at `from` to the active slot `team_slot`, overwriting it.
+    /// See the `From<&StoredPokemon>` implementation of `ActivePokemon` for the field mapping.
+    ///
+    /// The crate has no move tables, so the PP of each valid move is set to `max_pp(move_id)`.
+    /// The copy keeps the roster number of the member it replaces, or gets the next one if the slot was empty.
+    /// Fails without changing anything with `SaveError::UnknownMaxPp` if `max_pp` returns `None` for a valid move,
+    /// or with `SaveError::FieldOverflow` if a max PP doesn't fit in its field.
+    pub fn clone_stored_to_active(
+        &mut self,
+        from: usize,
+        team_slot: usize,
+        max_pp: impl Fn(u16) -> Option<u8>,
+    ) -> Result<(), SaveError> {
+        let src = self
+            .stored_pokemon
+            .get(from)
+            .ok_or(SaveError::InvalidSlot(from))?;
+        if team_slot >= self.active_pokemon.len() {
+            return Err(SaveError::InvalidSlot(team_slot));
+        }
+        if !src.valid {
+            return Err(SaveError::EmptySlot(from));
+        }
+
+        let mut pkm = ActivePokemon::from(src);
+        for m in pkm.moves_mut().into_iter().filter(|m| m.valid) {
+            m.pp = max_pp(m.id).ok_or(SaveError::UnknownMaxPp(m.id))?;
+            check_field(active::moves::PP, &m.pp, "pp")?;
+        }
+
+        let replaced = &self.active_pokemon[team_slot];
+        pkm.roaster_number = if replaced.valid && replaced.roaster_number != 0 {
+            replaced.roaster_number
+        } else {
+            self.team_size() as u16 + 1
         };
 
-        let backup = match self.active_save_block {
-            ActiveSaveBlock::Primary => save::BACKUP_SAVE.start,
-            ActiveSaveBlock::Backup => save::PRIMARY_SAVE.start,
+        self.active_pokemon[team_slot] = pkm;
+        Ok(())
+    }
+
+    /// Returns the number of valid Pokémon in the active team.
+    pub fn team_size(&self) -> usize {
+        self.active_pokemon.iter().filter(|p| p.valid).count()
+    }
+
+    /// Adds the stored Pokémon at `stored_index` to the first free active slot.
+    /// The new member gets the next roster number, keeping them contiguous from 1.
+    /// Returns the active slot the Pokémon was placed in.
+    pub fn add_to_team(&mut self, stored_index: usize) -> Result<usize, SaveError> {
+        let src = self
+            .stored_pokemon
+            .get(stored_index)
+            .ok_or(SaveError::InvalidSlot(stored_index))?;
+        if !src.valid {
+            return Err(SaveError::EmptySlot(stored_index));
+        }
+
+        let slot = self
+            .active_pokemon
+            .iter()
+            .position(|p| !p.valid)
+            .ok_or(SaveError::TeamFull)?;
+        let roster_number = self.team_size() as u16 + 1;
+
+        self.active_pokemon[slot] = ActivePokemon {
+            roaster_number: roster_number,
+            ..ActivePokemon::from(src)
+        };
+
+        Ok(slot)
+    }
+
+    /// Finds the stored slot the active Pokémon at `team_slot` was recruited in.
+    /// Team members are also kept in the box. The active data has no known field pointing at that slot,
+    /// so the link is the only valid stored slot with the same species and name.
+    /// Returns `None` if there's no such slot or several, e.g. two recruits of a species with their default name.
+    pub fn linked_stored_index(&self, team_slot: usize) -> Option<usize> {
+        let active = self.active_pokemon.get(team_slot).filter(|p| p.valid)?;
+        let mut matches = self
+            .stored_pokemon
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.valid && p.id == active.id && p.name == active.name)
+            .map(|(i, _)| i);
+
+        match (matches.next(), matches.next()) {
+            (Some(idx), None) => Some(idx),
+            _ => None,
+        }
+    }
+
+    /// Removes the active Pokémon at `team_slot` from the team and writes it back to the box.
+    /// It's written to its linked stored slot, or the first free one if there's no unambiguous link.
+    /// The roster numbers of the remaining members are shifted down to fill the gap.
+    /// Returns the stored slot the Pokémon was written to.
+    ///
+    /// The hero and the partner can't leave the team in-game, removing them fails unless `force` is set.
+    /// A member counts as the hero or the partner if it matches stored slot 0 or 1, even when the link is ambiguous.
+    /// A save without them in the team may not load correctly.
+    pub fn send_to_storage(&mut self, team_slot: usize, force: bool) -> Result<usize, SaveError> {
+        let active = self
+            .active_pokemon
+            .get(team_slot)
+            .ok_or(SaveError::InvalidSlot(team_slot))?;
+        if !active.valid {
+            return Err(SaveError::EmptySlot(team_slot));
+        }
+
+        let protected = self.stored_pokemon[..2]
+            .iter()
+            .any(|p| p.valid && p.id == active.id && p.name == active.name);
+        if protected && !force {
+            return Err(SaveError::ProtectedTeamMember(team_slot));
+        }
+
+        let linked = self.linked_stored_index(team_slot);
+
+        let stored_index = match linked {
+            Some(idx) => idx,
+            None => (FIRST_RECRUIT_SLOT..self.stored_pokemon.len())
+                .find(|&i| !self.stored_pokemon[i].valid)
+                .ok_or(SaveError::NotEnoughSlots { needed: 1, free: 0 })?,
         };
 
-        self.general.save(&mut self.data, self.active_save_block);
+        let old = &self.stored_pokemon[stored_index];
+        let stored = if old.valid {
+            StoredPokemon {
+                unknown: old.unknown,
+                evolved_at_1: old.evolved_at_1,
+                evolved_at_2: old.evolved_at_2,
+                ..StoredPokemon::from(active)
+            }
+        } else {
+            StoredPokemon::from(active)
+        };
+
+        let roster_number = active.roaster_number;
+        self.stored_pokemon[stored_index] = stored;
+        self.active_pokemon[team_slot] = ActivePokemon::default();
+        for p in self.active_pokemon.iter_mut().filter(|p| p.valid) {
+            if p.roaster_number > roster_number {
+                p.roaster_number -= 1;
+            }
+        }
+
+        Ok(stored_index)
+    }
+
+    /// Returns the raw bytes of a save block, including its checksum.
+    pub fn block(&self, block: SaveBlock) -> &[u8] {
+        &self.data[block.range()]
+    }
+
+    /// Returns the raw bytes of a save block for patching regions the crate doesn't model.
+    /// `save` recalculates the checksums over the patched bytes, but it also writes the parsed
+    /// structures over their regions of the active block and mirrors the active block to the other one.
+    pub fn block_mut(&mut self, block: SaveBlock) -> &mut [u8] {
+        &mut self.data[block.range()]
+    }
+
+    /// Returns the raw bytes of the active save block, see `block`.
+    pub fn active_block(&self) -> &[u8] {
+        self.block(self.active_save_block.into())
+    }
+
+    /// Returns the raw bytes of the active save block, see `block_mut`.
+    pub fn active_block_mut(&mut self) -> &mut [u8] {
+        self.block_mut(self.active_save_block.into())
+    }
+
+    /// Writes every parsed structure to the active block of `data`.
+    /// Fails without writing anything if a value doesn't fit in its field.
+    pub(crate) fn store(&self, data: &mut [u8]) -> Result<(), SaveError> {
+        self.general.check_widths()?;
+        for p in self.stored_pokemon.iter() {
+            p.check_widths()?;
+        }
+        for p in &self.active_pokemon {
+            p.check_widths()?;
+        }
+
+        self.general.save(data, self.active_save_block)?;
 
         // Saving does not allocate on the heap.
         let stored = self
@@ -284,7 +986,7 @@ impl SkySave {
             );
 
         store_save_bits(
-            self.data.view_bits_mut(),
+            data.view_bits_mut(),
             self.active_save_block,
             stored::STORED_PKM_BITS,
             &stored.as_bitslice()[0..stored::STORED_PKM_BIT_LEN * stored::STORED_PKM_COUNT],
@@ -305,15 +1007,846 @@ impl SkySave {
             );
 
         store_save_bits(
-            self.data.view_bits_mut(),
+            data.view_bits_mut(),
             self.active_save_block,
             active::ACTIVE_PKM_BITS,
             active.as_bitslice(),
         );
 
-        self.data.copy_within(active_range, backup);
-        self.fix_checksums();
+        Ok(())
+    }
+
+    /// Hashes the game state held by the active block, as a SHA-256 digest.
+    /// Unsaved changes to the parsed structures are included and the checksum is left out,
+    /// so saving an unchanged save keeps the same hash.
+    /// Fails if a value doesn't fit in its field, see `save`.
+    pub fn content_hash(&self) -> Result<[u8; 32], SaveError> {
+        let mut data = self.data.clone();
+        self.store(&mut data)?;
+
+        let range = match self.active_save_block {
+            ActiveSaveBlock::Primary => save::PRIMARY_CHECKSUM,
+            ActiveSaveBlock::Backup => save::BACKUP_CHECKSUM,
+        };
+
+        Ok(Sha256::digest(&data[range]).into())
+    }
+
+    /// Hashes the raw bytes of `data`, as a SHA-256 digest.
+    pub fn raw_hash(&self) -> [u8; 32] {
+        Sha256::digest(&self.data).into()
+    }
+
+    /// Writes every parsed structure to `data`, mirrors the active block to the other one and recalculates the checksums.
+    ///
+    /// Blocks whose data is unchanged since the last load or save are left alone, so saving an untouched save is byte-identical:
+    /// the active block is only mirrored if it changed, and the other blocks keep their checksums unless they changed.
+    /// The active block's checksum is always recalculated.
+    fn prepare_image(&self, data: &mut [u8]) -> Result<(), SaveError> {
+        let active = SaveBlock::from(self.active_save_block);
+        let other = match self.active_save_block {
+            ActiveSaveBlock::Primary => SaveBlock::Backup,
+            ActiveSaveBlock::Backup => SaveBlock::Primary,
+        };
+        let changed = |data: &[u8], block: SaveBlock| {
+            data[block.data_range()] != self.saved_image[block.data_range()]
+        };
+
+        self.store(data)?;
+        let block_sum = checksum(data, active.data_range());
+        data[active.checksum_range()].copy_from_slice(&block_sum);
+
+        if changed(data, active) {
+            data.copy_within(active.data_range(), other.data_range().start);
+            let sum = checksum(data, other.data_range());
+            data[other.checksum_range()].copy_from_slice(&sum);
+            debug!(
+                "Saving the {:?} block and mirroring it to offset {:#X}",
+                self.active_save_block,
+                other.range().start
+            );
+        } else if changed(data, other) {
+            let sum = checksum(data, other.data_range());
+            data[other.checksum_range()].copy_from_slice(&sum);
+        }
+
+        // `data` is public and may have been patched directly, so the quicksave is compared too.
+        if changed(data, SaveBlock::Quicksave) {
+            let sum = checksum(data, save::QUICKSAVE_CHECKSUM);
+            data[save::QUICKSAVE_READ_CHECKSUM].copy_from_slice(&sum);
+        }
+
+        Ok(())
+    }
+
+    /// Saves all changes to `data`. Recalculates the checksums and writes to a file.
+    /// Saves loaded from a mirrored or padded dump are written back in the same layout, see `container`.
+    /// Fails with `SaveError::FieldOverflow` if a value doesn't fit in its field.
+    /// The file is replaced atomically, see `SaveOptions::atomic`.
+    pub fn save<P: AsRef<Path>>(&mut self, filename: P) -> Result<(), SaveError> {
+        self.save_with(filename, SaveOptions::default())
+    }
+
+    /// Saves all changes like `save`, with the given options.
+    pub fn save_with<P: AsRef<Path>>(
+        &mut self,
+        filename: P,
+        options: SaveOptions,
+    ) -> Result<(), SaveError> {
+        let data = self.prepare_data()?;
+        let file = self.container.wrap(&data);
+        if options.atomic {
+            write_atomic(filename.as_ref(), &file)?;
+        } else {
+            fs::write(filename, file).map_err(SaveError::Io)?;
+        }
+
+        self.commit_data(data);
+        Ok(())
+    }
+
+    /// Saves like `save`, keeping the previous file as `file.sav.1`.
+    /// Older copies move up to `file.sav.2` and so on, keeping at most `keep`; `file.sav.keep` is dropped.
+    /// Numbered copies past `keep` from earlier calls are left alone. With `keep` 0 no copy is made.
+    /// The backups are rotated only once the new image is written next to the file,
+    /// so nothing is rotated if the save fails before that.
+    pub fn save_with_backup<P: AsRef<Path>>(
+        &mut self,
+        filename: P,
+        keep: usize,
+    ) -> Result<(), SaveError> {
+        let path = filename.as_ref();
+        let data = self.prepare_data()?;
+        write_atomic_with(path, &self.container.wrap(&data), || {
+            rotate_backups(path, keep)
+        })?;
+
+        self.commit_data(data);
+        Ok(())
+    }
+
+    /// Saves like `save`, but writes only the 128KiB image like `export_canonical`.
+    /// `container` is left as is, so `save` still reproduces the layout the file was loaded from.
+    pub fn save_canonical<P: AsRef<Path>>(&mut self, filename: P) -> Result<(), SaveError> {
+        let data = self.prepare_data()?;
+        write_atomic(filename.as_ref(), &data[..MIN_SAVE_LEN])?;
+
+        self.commit_data(data);
+        Ok(())
+    }
+
+    /// Applies all changes to `data` like `save`, and returns the file contents instead of writing them.
+    /// Saving an unmodified save returns exactly the bytes it was loaded from.
+    /// `data` is left untouched if this fails.
+    pub fn save_to_vec(&mut self) -> Result<Vec<u8>, SaveError> {
+        let data = self.prepare_data()?;
+        let file = self.container.wrap(&data);
+        self.commit_data(data);
+
+        Ok(file)
+    }
+
+    /// Returns a copy of `data` with all changes applied, without committing it.
+    /// Callers writing it to a file commit it with `commit_data` once the write succeeds.
+    pub(crate) fn prepare_data(&self) -> Result<Vec<u8>, SaveError> {
+        let mut data = self.data.clone();
+        self.prepare_image(&mut data)?;
+
+        Ok(data)
+    }
 
-        fs::write(filename, &self.data).map_err(SaveError::Io)
+    /// Makes `data` from `prepare_data` the saved state.
+    pub(crate) fn commit_data(&mut self, data: Vec<u8>) {
+        self.data = data;
+        self.saved_image.clone_from(&self.data);
     }
+
+    /// Returns the saved image with all changes applied, trimmed to exactly 128KiB.
+    /// Unlike `save`, any flashcart padding, mirror copy or trailing footer is left out, and `data` is untouched.
+    /// Fails with `SaveError::FieldOverflow` if a value doesn't fit in its field.
+    pub fn export_canonical(&self) -> Result<Vec<u8>, SaveError> {
+        let mut data = self.data[..MIN_SAVE_LEN].to_vec();
+        self.prepare_image(&mut data)?;
+
+        Ok(data)
+    }
+}
+
+#[test]
+fn test_export_canonical() {
+    let mut image = vec![0; MIN_SAVE_LEN];
+    image[0x1000] = 0x12;
+    fix_data_checksums(&mut image);
+
+    let mut save = SkySave::from_slice(image.repeat(2)).unwrap();
+    save.stored_pokemon[7].level = 30;
+    save.general.held_money = 1234;
+
+    let canonical = save.export_canonical().unwrap();
+    assert_eq!(canonical.len(), MIN_SAVE_LEN);
+
+    let parsed = SkySave::from_slice(&canonical).unwrap();
+    assert_eq!(parsed.container, SaveContainer::Raw);
+    assert_eq!(parsed.stored_pokemon[7].level, 30);
+    assert_eq!(parsed.general.held_money, 1234);
+    assert_eq!(parsed.export_canonical().unwrap(), canonical);
+}
+
+#[test]
+fn test_repair_from_active_block() {
+    let mut data = vec![0; MIN_SAVE_LEN];
+    data[save::BACKUP_SAVE.start + 0x100] = 7;
+    fix_data_checksums(&mut data);
+    data[save::PRIMARY_SAVE.start + 0x100] = 1;
+
+    let mut save = SkySave::from_slice(&data).unwrap();
+    assert_eq!(save.active_save_block, ActiveSaveBlock::Backup);
+    assert!(!save.block_checksum_ok(SaveBlock::Primary));
+
+    save.repair_from_active_block();
+    assert!(save.block_checksum_ok(SaveBlock::Primary));
+    assert_eq!(save.data[save::PRIMARY_SAVE.start + 0x100], 7);
+}
+
+#[test]
+fn test_checksum_partial_word() {
+    let data = [0x01, 0x00, 0x00, 0x00, 0x02, 0x03, 0x04];
+
+    assert_eq!(checksum(&data, 0..5), [0x03, 0x00, 0x00, 0x00]);
+    assert_eq!(checksum(&data, 0..6), [0x03, 0x03, 0x00, 0x00]);
+    assert_eq!(checksum(&data, 0..7), [0x03, 0x03, 0x04, 0x00]);
+    assert_eq!(checksum(&data, 4..7), [0x02, 0x03, 0x04, 0x00]);
+}
+
+#[test]
+fn test_checksum_matches_naive() {
+    fn naive(data: &[u8]) -> [u8; 4] {
+        (data
+            .chunks(4)
+            .map(|chunk| {
+                let mut word = [0; 4];
+                word[..chunk.len()].copy_from_slice(chunk);
+                u32::from_le_bytes(word)
+            })
+            .fold(0u64, |acc, u| acc + u as u64) as u32)
+            .to_le_bytes()
+    }
+
+    // xorshift64, so every run checks the same buffers.
+    let mut state = 0x9E37_79B9_7F4A_7C15u64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    for _ in 0..256 {
+        let len = (next() % 4096) as usize;
+        let data: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+        let start = (next() as usize) % (len + 1);
+        let end = start + (next() as usize) % (len - start + 1);
+
+        assert_eq!(
+            checksum(&data, start..end),
+            naive(&data[start..end]),
+            "{}..{} of {} bytes",
+            start,
+            end,
+            len
+        );
+    }
+
+    let saturated = vec![0xFF; 0x10000];
+    assert_eq!(checksum(&saturated, 0..0x10000), naive(&saturated));
+}
+
+#[test]
+fn test_is_valid_save() {
+    let mut data = vec![0; MIN_SAVE_LEN];
+    assert!(is_valid_save(&data));
+    assert!(!is_valid_save(&data[..MIN_SAVE_LEN - 1]));
+
+    data[save::PRIMARY_READ_CHECKSUM.start] = 1;
+    assert!(is_valid_save(&data));
+    data[save::BACKUP_READ_CHECKSUM.start] = 1;
+    assert!(!is_valid_save(&data));
+}
+
+#[test]
+fn test_clone_stored() {
+    let mut save = SkySave::from_slice(vec![0; MIN_SAVE_LEN]).unwrap();
+    save.stored_pokemon[5].valid = true;
+    save.stored_pokemon[5].level = 30;
+    save.stored_pokemon[6].valid = true;
+
+    assert!(matches!(
+        save.clone_stored(5, 6, false),
+        Err(SaveError::SlotOccupied(6))
+    ));
+    assert!(matches!(
+        save.clone_stored(7, 8, false),
+        Err(SaveError::EmptySlot(7))
+    ));
+
+    save.clone_stored(5, 7, false).unwrap();
+    assert_eq!(save.stored_pokemon[7].level, 30);
+
+    save.stored_pokemon[5].move_1.valid = true;
+    save.stored_pokemon[5].move_1.id = 33;
+    assert!(matches!(
+        save.clone_stored_to_active(5, 2, |_| Some(u8::MAX)),
+        Err(SaveError::FieldOverflow { .. })
+    ));
+    assert!(!save.active_pokemon[2].valid);
+    assert!(matches!(
+        save.clone_stored_to_active(5, 2, |_| None),
+        Err(SaveError::UnknownMaxPp(33))
+    ));
+    assert!(!save.active_pokemon[2].valid);
+
+    save.clone_stored_to_active(5, 2, |id| (id == 33).then_some(35))
+        .unwrap();
+    assert!(save.active_pokemon[2].valid);
+    assert_eq!(save.active_pokemon[2].level, 30);
+    assert_eq!(save.active_pokemon[2].move_1.pp, 35);
+    assert_eq!(save.active_pokemon[2].roaster_number, 1);
+
+    // Replacing a member keeps its roster number.
+    save.clone_stored_to_active(6, 2, |_| None).unwrap();
+    assert_eq!(save.active_pokemon[2].roaster_number, 1);
+}
+
+#[test]
+fn test_general_byte_layout() {
+    let mut data = vec![0; MIN_SAVE_LEN];
+    // Held money 0x012345, starting at bit 6 of 0x990C.
+    data[0x990C..0x9910].copy_from_slice(&[0x40, 0xD1, 0x48, 0x00]);
+    data[general::EXPLORER_RANK].copy_from_slice(&[0x78, 0x56, 0x34, 0x12]);
+    let sum = checksum(&data, save::PRIMARY_CHECKSUM);
+    data[save::PRIMARY_READ_CHECKSUM].copy_from_slice(&sum);
+
+    let mut save = SkySave::from_slice(&data).unwrap();
+    assert_eq!(save.general.held_money, 0x012345);
+    assert_eq!(save.general.explorer_rank, 0x12345678);
+
+    save.general
+        .save(&mut save.data, ActiveSaveBlock::Primary)
+        .unwrap();
+    assert_eq!(save.data, data);
+}
+
+#[test]
+fn test_add_to_team() {
+    let mut save = SkySave::from_slice(vec![0; MIN_SAVE_LEN]).unwrap();
+    for i in 5..10 {
+        save.stored_pokemon[i].valid = true;
+    }
+
+    assert!(matches!(
+        save.add_to_team(10),
+        Err(SaveError::EmptySlot(10))
+    ));
+    for i in 0..4 {
+        assert_eq!(save.add_to_team(5 + i).unwrap(), i);
+        assert_eq!(save.active_pokemon[i].roaster_number, i as u16 + 1);
+    }
+
+    assert_eq!(save.team_size(), 4);
+    assert!(matches!(save.add_to_team(9), Err(SaveError::TeamFull)));
+}
+
+#[test]
+fn test_send_to_storage() {
+    let mut save = SkySave::from_slice(vec![0; MIN_SAVE_LEN]).unwrap();
+    save.stored_pokemon[0].valid = true;
+    save.stored_pokemon[0].id = 1;
+    save.stored_pokemon[6].valid = true;
+    save.stored_pokemon[6].id = 2;
+    save.stored_pokemon[6].evolved_at_1 = 12;
+    save.add_to_team(0).unwrap();
+    save.add_to_team(6).unwrap();
+    save.active_pokemon[1].level = 50;
+
+    assert!(matches!(
+        save.send_to_storage(0, false),
+        Err(SaveError::ProtectedTeamMember(0))
+    ));
+
+    assert_eq!(save.send_to_storage(1, false).unwrap(), 6);
+    assert_eq!(save.stored_pokemon[6].level, 50);
+    assert_eq!(save.stored_pokemon[6].evolved_at_1, 12);
+    assert!(!save.active_pokemon[1].valid);
+
+    assert_eq!(save.send_to_storage(0, true).unwrap(), 0);
+    assert_eq!(save.team_size(), 0);
+}
+
+#[test]
+fn test_linked_stored_index_ambiguous() {
+    let mut save = SkySave::from_slice(vec![0; MIN_SAVE_LEN]).unwrap();
+    for i in [6, 7] {
+        save.stored_pokemon[i].valid = true;
+        save.stored_pokemon[i].id = 4;
+    }
+    save.add_to_team(7).unwrap();
+    assert_eq!(save.linked_stored_index(0), None);
+
+    // With no link, the member goes to a free slot instead of overwriting either twin.
+    save.active_pokemon[0].level = 50;
+    assert_eq!(save.send_to_storage(0, false).unwrap(), 5);
+    assert_eq!(save.stored_pokemon[6].level, 0);
+    assert_eq!(save.stored_pokemon[7].level, 0);
+
+    save.stored_pokemon[5].valid = false;
+    save.stored_pokemon[6].valid = false;
+    save.add_to_team(7).unwrap();
+    assert_eq!(save.linked_stored_index(0), Some(7));
+}
+
+#[test]
+fn test_failed_save_is_not_committed() {
+    let mut save = SkySave::from_slice(vec![0; MIN_SAVE_LEN]).unwrap();
+    save.general.held_money = 1234;
+
+    let dir = crate::fixture::TempPath::new("missing_dir");
+    let path = dir.join("save.sav");
+    assert!(save.save(&path).is_err());
+    assert!(save.save_with_backup(&path, 1).is_err());
+    assert!(save.save_canonical(&path).is_err());
+
+    assert!(!save.pending_changes().unwrap().is_empty());
+    assert_eq!(save.data, vec![0; MIN_SAVE_LEN]);
+}
+
+#[test]
+fn test_send_to_storage_ambiguous_hero() {
+    let mut save = SkySave::from_slice(vec![0; MIN_SAVE_LEN]).unwrap();
+    for i in [0, 6] {
+        save.stored_pokemon[i].valid = true;
+        save.stored_pokemon[i].id = 4;
+    }
+    save.add_to_team(0).unwrap();
+    assert_eq!(save.linked_stored_index(0), None);
+
+    // The hero's link is ambiguous, it's still protected and isn't copied into a free slot.
+    assert!(matches!(
+        save.send_to_storage(0, false),
+        Err(SaveError::ProtectedTeamMember(0))
+    ));
+    assert!(save.active_pokemon[0].valid);
+    assert_eq!(save.stored_pokemon.iter().filter(|p| p.valid).count(), 2);
+}
+
+#[test]
+fn test_full_box_round_trip() {
+    let mut save = SkySave::from_slice(vec![0; MIN_SAVE_LEN]).unwrap();
+    assert_eq!(save.stored_pokemon.len(), stored::STORED_PKM_COUNT);
+    assert_eq!(save.active_pokemon.len(), active::ACTIVE_PKM_COUNT);
+
+    for (i, p) in save.stored_pokemon.iter_mut().enumerate() {
+        p.valid = true;
+        p.level = (i % 100) as u8 + 1;
+        p.id = i as u16;
+    }
+
+    let path = crate::fixture::TempPath::new("full_box.sav");
+    save.save(&path).unwrap();
+    let loaded = SkySave::open(&path).unwrap();
+
+    assert_eq!(loaded.stored_pokemon.len(), stored::STORED_PKM_COUNT);
+    for (i, p) in loaded.stored_pokemon.iter().enumerate() {
+        assert!(p.valid);
+        assert_eq!(p.level, (i % 100) as u8 + 1);
+        assert_eq!(p.id, i as u16);
+    }
+}
+
+#[test]
+fn test_ignore_checksums() {
+    let mut data = vec![0; MIN_SAVE_LEN];
+    data[save::PRIMARY_READ_CHECKSUM.start] = 1;
+    data[save::BACKUP_READ_CHECKSUM.start] = 1;
+    assert!(matches!(
+        SkySave::from_slice(&data),
+        Err(SaveError::InvalidChecksum { .. })
+    ));
+
+    let options = ParseOptions {
+        ignore_checksums: true,
+        preferred_block: Some(ActiveSaveBlock::Backup),
+    };
+    let mut save = SkySave::from_slice_with(&data, options).unwrap();
+    assert!(!save.checksums_ok);
+    assert_eq!(save.active_save_block, ActiveSaveBlock::Backup);
+
+    save.fix_checksums();
+    assert!(is_valid_save(&save.data));
+}
+
+#[test]
+fn test_new_blank() {
+    let mut save = SkySave::new_blank();
+    assert_eq!(save.active_save_block, ActiveSaveBlock::Primary);
+    assert!(save.checksums_ok && save.quicksave_valid);
+    assert_eq!(save.team_size(), 0);
+    assert!(save.stored_pokemon.iter().all(|p| !p.valid));
+
+    save.general.held_money = 300;
+    let reloaded = SkySave::from_slice(save.save_to_vec().unwrap()).unwrap();
+    assert_eq!(reloaded.general.held_money, 300);
+}
+
+#[test]
+fn test_from_slice_block() {
+    let mut data = vec![0; MIN_SAVE_LEN];
+    let save = SkySave::from_slice_block(&data, ActiveSaveBlock::Backup).unwrap();
+    assert_eq!(save.active_save_block, ActiveSaveBlock::Backup);
+
+    data[save::BACKUP_READ_CHECKSUM.start] = 1;
+    assert!(matches!(
+        SkySave::from_slice_block(&data, ActiveSaveBlock::Backup),
+        Err(SaveError::InvalidBlockChecksum {
+            block: ActiveSaveBlock::Backup,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_content_hash() {
+    let mut save = SkySave::from_slice(vec![0; MIN_SAVE_LEN]).unwrap();
+    save.general.held_money = 1234;
+    let hash = save.content_hash().unwrap();
+
+    let path = crate::fixture::TempPath::new("content_hash.sav");
+    save.save(&path).unwrap();
+    let mut loaded = SkySave::open(&path).unwrap();
+    assert_eq!(loaded.content_hash().unwrap(), hash);
+    assert_eq!(loaded.raw_hash(), save.raw_hash());
+
+    loaded.stored_pokemon[10].level = 5;
+    assert_ne!(loaded.content_hash().unwrap(), hash);
+}
+
+#[test]
+fn test_block_mut() {
+    let mut save = SkySave::from_slice(vec![0; MIN_SAVE_LEN]).unwrap();
+    assert_eq!(
+        save.block(SaveBlock::Quicksave).len(),
+        save::QUICKSAVE.len()
+    );
+
+    // An offset past every parsed structure.
+    save.active_block_mut()[0xB000] = 0xAB;
+    let path = crate::fixture::TempPath::new("block_mut.sav");
+    save.save(&path).unwrap();
+    let loaded = SkySave::open(&path).unwrap();
+
+    assert_eq!(loaded.block(SaveBlock::Primary)[0xB000], 0xAB);
+    assert_eq!(loaded.block(SaveBlock::Backup)[0xB000], 0xAB);
+}
+
+#[test]
+fn test_field_overflow() {
+    let mut save = SkySave::from_slice(vec![0; MIN_SAVE_LEN]).unwrap();
+    save.stored_pokemon[7].level = 200;
+    assert!(matches!(
+        save.content_hash(),
+        Err(SaveError::FieldOverflow {
+            field: "level",
+            bits: 7
+        })
+    ));
+
+    save.stored_pokemon[7].level = 100;
+    save.general.held_money = 1 << 24;
+    assert!(matches!(
+        save.content_hash(),
+        Err(SaveError::FieldOverflow {
+            field: "held_money",
+            bits: 24
+        })
+    ));
+}
+
+#[test]
+fn test_find_duplicate_stored() {
+    let mut save = SkySave::from_slice(vec![0; 0x20000]).unwrap();
+    for i in [0, 6, 9, 12, 20, 21] {
+        save.stored_pokemon[i].valid = true;
+        save.stored_pokemon[i].id = 25;
+        save.stored_pokemon[i].level = 10;
+    }
+    save.stored_pokemon[12].level = 11;
+    save.stored_pokemon[20].id = 26;
+    save.stored_pokemon[21].id = 26;
+
+    assert_eq!(save.find_duplicate_stored(), vec![vec![6, 9], vec![20, 21]]);
+}
+
+#[test]
+fn test_general_save_all_or_nothing() {
+    let mut data = vec![0; MIN_SAVE_LEN];
+    let mut general = General::load(&data, ActiveSaveBlock::Primary);
+    general.team_name = PmdString::truncate_to_fit("Overflow").0;
+    general.stored_money = u32::MAX;
+
+    assert!(general.save(&mut data, ActiveSaveBlock::Primary).is_err());
+    assert!(data.iter().all(|&b| b == 0));
+
+    let mut save = SkySave::from_slice(vec![0; MIN_SAVE_LEN]).unwrap();
+    save.general.stored_money = u32::MAX;
+    let before = save.data.clone();
+    assert!(save.save_to_vec().is_err());
+    assert_eq!(save.data, before);
+}
+
+#[test]
+fn test_patch_single_slot() {
+    use crate::fixture::fixture_save;
+
+    let mut data = fixture_save();
+    let block = ActiveSaveBlock::Primary;
+
+    let mut general = General::load(&data, block);
+    general.held_money = 777;
+    general.save(&mut data, block).unwrap();
+    let mut pkm = StoredPokemon::read_from_slot(&data, block, 5).unwrap();
+    pkm.level = 42;
+    pkm.write_to_slot(&mut data, block, 5).unwrap();
+    SaveBlock::Primary.fix_checksum(&mut data).unwrap();
+
+    let save = SkySave::from_slice(&data).unwrap();
+    assert!(save.block_checksum_ok(SaveBlock::Primary));
+    assert_eq!(save.general.held_money, 777);
+    assert_eq!(save.stored_pokemon[5].level, 42);
+    assert_eq!(
+        ActivePokemon::read_from_slot(&data, block, 0)
+            .unwrap()
+            .level,
+        save.active_pokemon[0].level
+    );
+
+    assert!(matches!(
+        StoredPokemon::read_from_slot(&data, block, stored::STORED_PKM_COUNT),
+        Err(SaveError::InvalidSlot(_))
+    ));
+    assert!(matches!(
+        StoredPokemon::read_from_slot(&data[..16], block, 0),
+        Err(SaveError::InvalidSize)
+    ));
+}
+
+/// Saves are loaded on worker threads and async tasks, so they must be able to cross threads.
+#[test]
+fn test_send() {
+    fn assert_send<T: Send>() {}
+
+    assert_send::<SkySave>();
+    assert_send::<General>();
+    assert_send::<StoredPokemon>();
+    assert_send::<ActivePokemon>();
+    assert_send::<SaveError>();
+}
+
+#[test]
+fn test_sky_save_size() {
+    assert!(std::mem::size_of::<SkySave>() <= 1024);
+}
+
+#[test]
+fn test_save_after_revert_mirrors() {
+    let mut save = SkySave::from_slice(vec![0; MIN_SAVE_LEN]).unwrap();
+    let block = |data: &[u8], block: SaveBlock| data[block.data_range()].to_vec();
+
+    save.stored_pokemon[7].level = 30;
+    let edited = save.save_to_vec().unwrap();
+    assert_eq!(
+        block(&edited, SaveBlock::Primary),
+        block(&edited, SaveBlock::Backup)
+    );
+
+    save.stored_pokemon[7].level = 0;
+    let reverted = save.save_to_vec().unwrap();
+    assert_eq!(
+        block(&reverted, SaveBlock::Primary),
+        block(&reverted, SaveBlock::Backup)
+    );
+    assert_eq!(
+        SkySave::from_slice(&reverted).unwrap().stored_pokemon[7].level,
+        0
+    );
+}
+
+#[test]
+fn test_save_atomic() {
+    let dir = crate::fixture::TempPath::new("save_atomic");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("save.sav");
+    fs::write(&path, b"old").unwrap();
+
+    let mut save = SkySave::from_slice(vec![0; MIN_SAVE_LEN]).unwrap();
+    save.general.held_money = 77;
+    save.save(&path).unwrap();
+
+    assert_eq!(SkySave::open(&path).unwrap().general.held_money, 77);
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+    let missing = dir.join("missing").join("save.sav");
+    match save.save(&missing) {
+        Err(SaveError::AtomicWrite { temp, dest, .. }) => {
+            assert_eq!(dest, missing);
+            assert_eq!(temp.parent(), missing.parent());
+        }
+        other => panic!("{:?}", other),
+    }
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+    // No temporary files are left behind.
+    for held_money in 1..=3 {
+        save.general.held_money = held_money;
+        save.save(&path).unwrap();
+    }
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+}
+
+#[test]
+fn test_save_canonical() {
+    let dir = crate::fixture::TempPath::new("save_canonical");
+    fs::create_dir_all(&dir).unwrap();
+
+    // A raw image with trailing bytes, like an emulator footer.
+    let mut data = SkySave::new_blank().save_to_vec().unwrap();
+    data.extend_from_slice(b"footer");
+    let mut save = SkySave::from_slice(&data).unwrap();
+    save.general.held_money = 12;
+
+    let raw = dir.join("save.sav");
+    save.save_canonical(&raw).unwrap();
+    let written = fs::read(&raw).unwrap();
+    assert_eq!(written.len(), MIN_SAVE_LEN);
+    assert_eq!(SkySave::from_slice(written).unwrap().general.held_money, 12);
+
+    let same = dir.join("save.dsv");
+    save.save(&same).unwrap();
+    assert!(fs::read(&same).unwrap().ends_with(b"footer"));
+}
+
+#[test]
+fn test_save_with_backup() {
+    let dir = crate::fixture::TempPath::new("save_with_backup");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("save.sav");
+    let money = |p: &Path| SkySave::open(p).unwrap().general.held_money;
+
+    // Nothing to rotate yet.
+    let mut save = SkySave::new_blank();
+    save.save_with_backup(&path, 2).unwrap();
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+    for held_money in 1..=3 {
+        save.general.held_money = held_money;
+        save.save_with_backup(&path, 2).unwrap();
+    }
+    assert_eq!(money(&path), 3);
+    assert_eq!(money(&numbered_backup(&path, 1)), 2);
+    assert_eq!(money(&numbered_backup(&path, 2)), 1);
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 3);
+
+    // A gap in the numbering is skipped.
+    fs::remove_file(numbered_backup(&path, 1)).unwrap();
+    save.general.held_money = 4;
+    save.save_with_backup(&path, 3).unwrap();
+    assert_eq!(money(&numbered_backup(&path, 1)), 3);
+    assert!(!numbered_backup(&path, 2).exists());
+    assert_eq!(money(&numbered_backup(&path, 3)), 1);
+    assert!(!numbered_backup(&numbered_backup(&path, 1), 1).exists());
+
+    // A failed save rotates nothing.
+    save.general.held_money = u32::MAX;
+    assert!(save.save_with_backup(&path, 3).is_err());
+    assert_eq!(money(&numbered_backup(&path, 1)), 3);
+
+    // Neither does a failed write, the backups are rotated only after the new image is written.
+    let missing = dir.join("missing").join("save.sav");
+    let mut rotated = false;
+    let result = write_atomic_with(&missing, b"new", || {
+        rotated = true;
+        Ok(())
+    });
+    assert!(result.is_err() && !rotated);
+}
+
+#[test]
+fn test_name_bytes_after_terminator_round_trip() {
+    let mut data = vec![0; MIN_SAVE_LEN];
+    let name = [0x41, 0x62, 0x00, 0x63, 0x64, 0x00, 0xFF, 0x90, 0xA0, 0x21];
+    data[general::TEAM_NAME].copy_from_slice(&name);
+    fix_data_checksums(&mut data);
+
+    let mut save = SkySave::from_slice(&data).unwrap();
+    assert_eq!(save.general.team_name.to_string_until_nul(), "Ab");
+    assert_eq!(save.general.team_name.to_save_bytes(), name);
+    save.stored_pokemon[3].name = save.general.team_name.clone();
+    save.general.held_money = 1;
+
+    let saved = SkySave::from_slice(save.save_to_vec().unwrap()).unwrap();
+    assert_eq!(saved.general.team_name.to_save_bytes(), name);
+    assert_eq!(saved.stored_pokemon[3].name.to_save_bytes(), name);
+}
+
+#[test]
+fn test_malformed_inputs_never_panic() {
+    use crate::fixture::fixture_save;
+
+    let lenient = ParseOptions {
+        ignore_checksums: true,
+        ..Default::default()
+    };
+    let noise = |len: usize, seed: usize| -> Vec<u8> {
+        (0..len)
+            .map(|i| ((i * 73 + seed * 31) % 251) as u8)
+            .collect()
+    };
+
+    let mut corpus = vec![
+        vec![],
+        vec![0; 4],
+        vec![0; MIN_SAVE_LEN - 1],
+        vec![0xFF; MIN_SAVE_LEN],
+        noise(MIN_SAVE_LEN, 1),
+        noise(MIN_SAVE_LEN + 3, 2),
+        noise(0x40000, 3),
+        noise(0x80000 - 1, 4),
+        // A padded dump whose only image is cut short.
+        [vec![0xFF; 0x40000 - 16], vec![0; 16]].concat(),
+    ];
+    // Every byte value in every name and field of the active block.
+    for b in [0x00, 0x7F, 0x80, 0xFF] {
+        let mut data = fixture_save();
+        data[save::PRIMARY_CHECKSUM].fill(b);
+        fix_data_checksums(&mut data);
+        corpus.push(data);
+    }
+    let mut truncated = fixture_save();
+    truncated.truncate(save::BACKUP_SAVE.start);
+    corpus.push(truncated);
+
+    for (i, data) in corpus.iter().enumerate() {
+        for options in [ParseOptions::default(), lenient] {
+            match SkySave::from_slice_with(data, options) {
+                Ok(mut save) => {
+                    let _ = save.save_to_vec();
+                }
+                Err(SaveError::InvalidSize) => assert!(data.len() < MIN_SAVE_LEN, "input {}", i),
+                Err(_) => {}
+            }
+        }
+    }
+
+    assert!(matches!(
+        validate(&[0; 16], ParseOptions::default()),
+        Err(SaveError::Malformed { .. })
+    ));
 }
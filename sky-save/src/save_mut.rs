@@ -0,0 +1,175 @@
+//! Edits a save in place, in a buffer owned by the caller.
+
+use crate::save::{fix_data_checksums, validate, Validated, MIN_SAVE_LEN};
+use crate::{
+    ActivePokemon, ActiveSaveBlock, General, ParseOptions, SaveBlock, SaveError, StoredPokemon,
+};
+
+/// A save borrowed from a caller-owned buffer, for embedders that can't give it away or copy it.
+/// Nothing is parsed up front, each accessor decodes or encodes its part of the active block directly.
+///
+/// The buffer is treated as a raw save image, mirrored or padded dumps aren't detected.
+#[derive(Debug)]
+pub struct SkySaveMut<'a> {
+    data: &'a mut [u8],
+    pub active_save_block: ActiveSaveBlock,
+    /// Whether the active block was written to since loading, see `fix_checksums`.
+    dirty: bool,
+}
+
+impl<'a> SkySaveMut<'a> {
+    /// Validates the buffer like `SkySave::from_slice` and selects the block to edit.
+    pub fn from_bytes_mut(data: &'a mut [u8]) -> Result<Self, SaveError> {
+        Self::from_bytes_mut_with(data, ParseOptions::default())
+    }
+
+    /// Validates the buffer like `SkySave::from_slice_with`.
+    pub fn from_bytes_mut_with(
+        data: &'a mut [u8],
+        options: ParseOptions,
+    ) -> Result<Self, SaveError> {
+        if data.len() < MIN_SAVE_LEN {
+            return Err(SaveError::InvalidSize);
+        }
+
+        let Validated {
+            active_save_block, ..
+        } = validate(data, options)?;
+
+        Ok(Self {
+            data,
+            active_save_block,
+            dirty: false,
+        })
+    }
+
+    /// The borrowed buffer.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.data
+    }
+
+    pub fn general(&self) -> General {
+        General::load(self.data, self.active_save_block)
+    }
+
+    /// Fails without writing anything if a value doesn't fit in its field.
+    pub fn set_general(&mut self, general: &General) -> Result<(), SaveError> {
+        general.check_widths()?;
+        general.save(self.data, self.active_save_block)?;
+        self.dirty = true;
+
+        Ok(())
+    }
+
+    pub fn stored_pokemon(&self, index: usize) -> Result<StoredPokemon, SaveError> {
+        StoredPokemon::read_from_slot(self.data, self.active_save_block, index)
+    }
+
+    /// Fails without writing anything if a value doesn't fit in its field.
+    pub fn set_stored_pokemon(
+        &mut self,
+        index: usize,
+        pokemon: &StoredPokemon,
+    ) -> Result<(), SaveError> {
+        pokemon.write_to_slot(self.data, self.active_save_block, index)?;
+        self.dirty = true;
+
+        Ok(())
+    }
+
+    pub fn active_pokemon(&self, index: usize) -> Result<ActivePokemon, SaveError> {
+        ActivePokemon::read_from_slot(self.data, self.active_save_block, index)
+    }
+
+    /// Fails without writing anything if a value doesn't fit in its field.
+    pub fn set_active_pokemon(
+        &mut self,
+        index: usize,
+        pokemon: &ActivePokemon,
+    ) -> Result<(), SaveError> {
+        pokemon.write_to_slot(self.data, self.active_save_block, index)?;
+        self.dirty = true;
+
+        Ok(())
+    }
+
+    /// Recalculates the checksums of every block.
+    /// If the active block was written to, it's first mirrored to the other one like `SkySave::save` does,
+    /// so the game can't fall back to a stale block with a valid checksum.
+    pub fn fix_checksums(&mut self) {
+        if self.dirty {
+            let (active, other) = match self.active_save_block {
+                ActiveSaveBlock::Primary => (SaveBlock::Primary, SaveBlock::Backup),
+                ActiveSaveBlock::Backup => (SaveBlock::Backup, SaveBlock::Primary),
+            };
+            self.data
+                .copy_within(active.data_range(), other.data_range().start);
+            self.dirty = false;
+        }
+
+        fix_data_checksums(self.data);
+    }
+}
+
+#[test]
+fn test_save_mut_round_trip() {
+    use crate::fixture::{fixture_save, random_save};
+    use crate::offsets::stored;
+    use crate::{PmdString, SkySave};
+
+    for mut data in [fixture_save(), random_save(7)] {
+        let owned = SkySave::from_slice(&data).unwrap();
+        let save = SkySaveMut::from_bytes_mut(&mut data).unwrap();
+
+        assert_eq!(save.active_save_block, owned.active_save_block);
+        assert_eq!(save.general().team_name, owned.general.team_name);
+        for (i, p) in owned.stored_pokemon.iter().enumerate() {
+            assert!(
+                save.stored_pokemon(i).unwrap().to_bits() == p.to_bits(),
+                "stored {}",
+                i
+            );
+        }
+        for (i, p) in owned.active_pokemon.iter().enumerate() {
+            assert!(
+                save.active_pokemon(i).unwrap().to_bits() == p.to_bits(),
+                "active {}",
+                i
+            );
+        }
+        assert!(matches!(
+            save.stored_pokemon(stored::STORED_PKM_COUNT),
+            Err(SaveError::InvalidSlot(_))
+        ));
+    }
+
+    // Edits through the borrowed buffer match the same edits saved through `SkySave`.
+    let mut data = fixture_save();
+    let mut owned = SkySave::from_slice(&data).unwrap();
+    let mut save = SkySaveMut::from_bytes_mut(&mut data).unwrap();
+
+    let mut general = save.general();
+    general.held_money = 999;
+    general.team_name = PmdString::try_from("Borrowed").unwrap();
+    save.set_general(&general).unwrap();
+    let mut pkm = save.stored_pokemon(5).unwrap();
+    pkm.level = 50;
+    save.set_stored_pokemon(5, &pkm).unwrap();
+    save.fix_checksums();
+
+    owned.general.held_money = 999;
+    owned.general.team_name = PmdString::try_from("Borrowed").unwrap();
+    owned.stored_pokemon[5].level = 50;
+    assert!(owned.export_canonical().unwrap() == data);
+
+    let mut save = SkySaveMut::from_bytes_mut(&mut data).unwrap();
+    pkm.level = 255;
+    pkm.iq = u16::MAX;
+    assert!(save.set_stored_pokemon(5, &pkm).is_err());
+    assert_eq!(save.stored_pokemon(5).unwrap().level, 50);
+
+    general.held_money = u32::MAX;
+    general.team_name = PmdString::try_from("Unchanged").unwrap();
+    assert!(save.set_general(&general).is_err());
+    assert_eq!(save.general().team_name.to_string_until_nul(), "Borrowed");
+}
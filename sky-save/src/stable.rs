@@ -0,0 +1,45 @@
+//! A curated, stable subset of the API for downstream editors.
+//!
+//! Everything re-exported here keeps its name, signature and behavior across minor versions.
+//! Changes to this module are breaking changes and require a major version bump,
+//! new items may be added in minor versions.
+//!
+//! The structs with public fields may gain new fields in minor versions,
+//! read and assign their fields, but build them with `Default` and struct update syntax
+//! rather than a full struct literal.
+//! The Pokémon lists of `SkySave` are only promised to deref to slices,
+//! their container types (currently `Box<ArrayVec>` and `ArrayVec`) may change.
+//!
+//! Items outside this module are experimental and may change in any release,
+//! this includes the team files, field descriptors, audit log, normalization, containers and the raw offsets.
+//!
+//! The signatures are checked by `test_stable_api`, update it only together with a major version bump.
+
+pub use crate::active::{ActiveMove, ActivePokemon};
+pub use crate::encoding::{PmdChar, PmdString};
+pub use crate::error::{EncodingError, SaveError};
+pub use crate::iq::IqMap;
+pub use crate::save::{is_valid_save, ActiveSaveBlock, General, ParseOptions, SaveBlock, SkySave};
+pub use crate::stored::{StoredMove, StoredPokemon};
+
+#[test]
+fn test_stable_api() {
+    use std::path::Path;
+
+    let _: fn(&'static [u8]) -> Result<SkySave, SaveError> = SkySave::from_slice;
+    let _: fn(&'static [u8], ParseOptions) -> Result<SkySave, SaveError> = SkySave::from_slice_with;
+    let _: fn(&'static Path) -> Result<SkySave, SaveError> = SkySave::open;
+    let _: fn(&mut SkySave, &'static Path) -> Result<(), SaveError> = SkySave::save;
+    let _: fn(&mut SkySave) = SkySave::fix_checksums;
+    let _: fn(&[u8]) -> bool = is_valid_save;
+
+    let _: fn(&StoredPokemon) -> [&StoredMove; 4] = StoredPokemon::moves;
+    let _: fn(&ActivePokemon) -> [&ActiveMove; 4] = ActivePokemon::moves;
+    let _: fn(&PmdString) -> String = PmdString::to_string_until_nul;
+    let _: fn(&'static str) -> Result<PmdString, EncodingError> = PmdString::try_from;
+
+    let save = SkySave::from_slice(vec![0; 0x20000]).unwrap();
+    let _: (&Vec<u8>, ActiveSaveBlock, &General) =
+        (&save.data, save.active_save_block, &save.general);
+    let _: (&[StoredPokemon], &[ActivePokemon]) = (&save.stored_pokemon, &save.active_pokemon);
+}
@@ -0,0 +1,93 @@
+//! Aggregates statistics over the stored Pokémon, see `SkySave::box_stats`.
+
+use crate::{SkySave, FIRST_RECRUIT_SLOT};
+use std::collections::BTreeMap;
+
+/// The number of level histogram buckets, each covering 10 levels.
+pub const LEVEL_BUCKETS: usize = 10;
+
+/// Statistics over the valid recruits in the box.
+/// The hero, partner and special episode slots are left out.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BoxStats {
+    pub recruited: usize,
+    /// The number of recruits per species ID.
+    pub species: BTreeMap<u16, usize>,
+    /// The number of recruits per level range, 1-10, 11-20 and so on.
+    /// Levels above 100 are counted in the last bucket, level 0 in the first.
+    pub levels: [usize; LEVEL_BUCKETS],
+    /// `None` if there are no recruits.
+    pub average_iq: Option<f64>,
+    /// The number of recruits knowing each move ID, counting each recruit once per move.
+    pub moves: BTreeMap<u16, usize>,
+}
+
+impl BoxStats {
+    /// The number of recruits knowing the move `id`.
+    pub fn knowing_move(&self, id: u16) -> usize {
+        self.moves.get(&id).copied().unwrap_or(0)
+    }
+}
+
+impl SkySave {
+    /// Computes `BoxStats` in one pass over the stored Pokémon.
+    pub fn box_stats(&self) -> BoxStats {
+        let mut stats = BoxStats::default();
+        let mut iq_sum = 0u64;
+
+        for p in self.stored_pokemon[FIRST_RECRUIT_SLOT..]
+            .iter()
+            .filter(|p| p.valid)
+        {
+            stats.recruited += 1;
+            *stats.species.entry(p.id).or_default() += 1;
+            let bucket = (p.level.max(1) as usize - 1) / 10;
+            stats.levels[bucket.min(LEVEL_BUCKETS - 1)] += 1;
+            iq_sum += p.iq as u64;
+
+            let mut known: Vec<u16> = p.moves().iter().filter(|m| m.valid).map(|m| m.id).collect();
+            known.sort_unstable();
+            known.dedup();
+            for id in known {
+                *stats.moves.entry(id).or_default() += 1;
+            }
+        }
+
+        stats.average_iq = (stats.recruited > 0).then(|| iq_sum as f64 / stats.recruited as f64);
+        stats
+    }
+}
+
+#[test]
+fn test_box_stats() {
+    use crate::fixture::{fixture_save, random_save};
+
+    let stats = SkySave::from_slice(fixture_save()).unwrap().box_stats();
+    assert_eq!(stats.recruited, 1);
+    assert_eq!(stats.species, BTreeMap::from([(7, 1)]));
+    assert_eq!(stats.levels[2], 1);
+    assert_eq!(stats.average_iq, Some(102.0));
+    assert_eq!(stats.knowing_move(35), 1);
+    assert_eq!(stats.knowing_move(33), 0);
+
+    let save = SkySave::from_slice(random_save(5)).unwrap();
+    let stats = save.box_stats();
+    let recruits: Vec<_> = save.stored_pokemon[FIRST_RECRUIT_SLOT..]
+        .iter()
+        .filter(|p| p.valid)
+        .collect();
+
+    assert_eq!(stats.recruited, recruits.len());
+    assert_eq!(stats.species.values().sum::<usize>(), recruits.len());
+    assert_eq!(stats.levels.iter().sum::<usize>(), recruits.len());
+
+    let id = recruits[0].move_1.id;
+    let knowing = recruits
+        .iter()
+        .filter(|p| p.moves().iter().any(|m| m.valid && m.id == id))
+        .count();
+    assert_eq!(stats.knowing_move(id), knowing);
+
+    let empty = SkySave::from_slice(vec![0; 0x20000]).unwrap().box_stats();
+    assert_eq!(empty.average_iq, None);
+}
@@ -1,146 +1,302 @@
 //! Handles loading and storing the stored Pokémon.
 
-use crate::offsets::stored::{moves, pokemon, STORED_MOVE_BIT_LEN, STORED_PKM_BIT_LEN};
-use crate::PmdString;
+use crate::layout::bit_layout;
+use crate::offsets::stored::{
+    moves, pokemon, STORED_MOVE_BIT_LEN, STORED_PKM_BITS, STORED_PKM_BIT_LEN, STORED_PKM_BYTE_LEN,
+    STORED_PKM_COUNT,
+};
+use crate::save::{load_save_bits, slot_bits, store_save_bits};
+use crate::{ActiveMove, ActivePokemon, ActiveSaveBlock, IqMap, PmdString, SaveError};
 use bitvec::prelude::*;
 use bitvec::BitArr;
 
-pub type IqMapBits = BitArr!(for 69, in u8, Lsb0);
+/// The first stored slot available for recruited Pokémon, see `StoredPokemon`.
+pub const FIRST_RECRUIT_SLOT: usize = 5;
 
 /// A static `BitArray` representing the bits of a `StoredPokemon`.
 pub type StoredPokemonBits = BitArr!(for STORED_PKM_BIT_LEN, in u8, Lsb0);
 /// A static `BitArray` representing the bits of a `StoredMove`.
 pub type StoredMoveBits = BitArr!(for STORED_MOVE_BIT_LEN, in u8, Lsb0);
 
-/// Represents each of the four moves in a `StoredPokemon`.
-#[derive(Debug, Default, Clone)]
-pub struct StoredMove {
-    pub valid: bool,
-    pub linked: bool,
-    pub switched: bool,
-    pub set: bool,
-    pub id: u16,
-    pub power_boost: u8,
+bit_layout! {
+    /// Represents each of the four moves in a `StoredPokemon`.
+    #[derive(Debug, Default, Clone)]
+    pub struct StoredMove(StoredMoveBits) {
+        pub valid: bool = moves::VALID,
+        pub linked: bool = moves::LINKED,
+        pub switched: bool = moves::SWITCHED,
+        pub set: bool = moves::SET,
+        pub id: u16 = moves::ID,
+        pub power_boost: u8 = moves::POWER_BOOST,
+    }
 }
 
-impl StoredMove {
-    pub fn from_bitslice(bits: &BitSlice<u8, Lsb0>) -> Self {
+/// Converts an `ActiveMove` to a `StoredMove`, dropping the PP and sealed state.
+impl From<&ActiveMove> for StoredMove {
+    fn from(value: &ActiveMove) -> Self {
         Self {
-            valid: bits[moves::VALID],
-            linked: bits[moves::LINKED],
-            switched: bits[moves::SWITCHED],
-            set: bits[moves::SET],
-            id: bits[moves::ID].load_le(),
-            power_boost: bits[moves::POWER_BOOST].load_le(),
+            valid: value.valid,
+            linked: value.linked,
+            switched: value.switched,
+            set: value.set,
+            id: value.id,
+            power_boost: value.power_boost,
         }
     }
+}
+
+bit_layout! {
+    /// Represents a recruited Pokémon in Chimecho's Assembly.
+    /// Holds information that isn't critical in dungeon mode.
+    ///
+    /// There are 550 available slots, see `offsets::stored::STORED_PKM_COUNT`.
+    /// Slots 0-1 are reserved for the player and partner.
+    /// Slots 2-4 are reserved for Pokemon from special episodes, they aren't available in the main story.
+    /// Slots five onwards are available for recruited Pokemon.
+    #[derive(Debug, Default, Clone)]
+    pub struct StoredPokemon(StoredPokemonBits) {
+        pub valid: bool = pokemon::VALID,
+        pub level: u8 = pokemon::LEVEL,
+        pub id: u16 = pokemon::ID,
+        pub met_at: u8 = pokemon::MET_AT,
+        pub met_floor: u8 = pokemon::MET_FLOOR,
+        pub unknown: bool = pokemon::UNKNOWN,
+        pub evolved_at_1: u8 = pokemon::EVOLVED_AT_1,
+        pub evolved_at_2: u8 = pokemon::EVOLVED_AT_2,
+        pub iq: u16 = pokemon::IQ,
+        pub hp: u16 = pokemon::HP,
+        pub attack: u8 = pokemon::ATTACK,
+        pub sp_attack: u8 = pokemon::SP_ATTACK,
+        pub defense: u8 = pokemon::DEFENSE,
+        pub sp_defense: u8 = pokemon::SP_DEFENSE,
+        pub exp: u32 = pokemon::EXP,
+        pub iq_map: IqMap = pokemon::IQ_MAP,
+        pub tactic: u8 = pokemon::TACTIC,
+        pub move_1: StoredMove = pokemon::MOVE_1,
+        pub move_2: StoredMove = pokemon::MOVE_2,
+        pub move_3: StoredMove = pokemon::MOVE_3,
+        pub move_4: StoredMove = pokemon::MOVE_4,
+        pub name: PmdString = pokemon::NAME,
+    }
+}
 
-    pub fn to_bits(&self) -> StoredMoveBits {
-        let mut bits = bitarr![u8, Lsb0; 0; STORED_MOVE_BIT_LEN];
+impl StoredPokemon {
+    /// Decodes stored slot `index` from `block` of the raw save image `data`, without parsing anything else.
+    /// The checksums aren't checked, see `SkySaveMut` for validated access.
+    pub fn read_from_slot(
+        data: &[u8],
+        block: ActiveSaveBlock,
+        index: usize,
+    ) -> Result<Self, SaveError> {
+        let range = slot_bits(
+            data.len(),
+            STORED_PKM_BITS,
+            STORED_PKM_BIT_LEN,
+            STORED_PKM_COUNT,
+            index,
+        )?;
+        Ok(Self::from_bitslice(load_save_bits(
+            data.view_bits(),
+            block,
+            range,
+        )))
+    }
 
-        bits.set(moves::VALID, self.valid);
-        bits.set(moves::LINKED, self.linked);
-        bits.set(moves::SWITCHED, self.switched);
-        bits.set(moves::SET, self.set);
-        bits[moves::ID].store_le(self.id);
-        bits[moves::POWER_BOOST].store_le(self.power_boost);
+    /// Encodes into stored slot `index` of `block` in the raw save image `data`.
+    /// Fails without writing anything if a value doesn't fit in its field.
+    /// The block's checksum is left stale, see `SaveBlock::fix_checksum`.
+    pub fn write_to_slot(
+        &self,
+        data: &mut [u8],
+        block: ActiveSaveBlock,
+        index: usize,
+    ) -> Result<(), SaveError> {
+        let range = slot_bits(
+            data.len(),
+            STORED_PKM_BITS,
+            STORED_PKM_BIT_LEN,
+            STORED_PKM_COUNT,
+            index,
+        )?;
+        self.check_widths()?;
+        store_save_bits(
+            data.view_bits_mut(),
+            block,
+            range,
+            &self.to_bits()[..STORED_PKM_BIT_LEN],
+        );
 
-        bits
+        Ok(())
     }
-}
 
-/// Represents a recruited Pokémon in Chimecho's Assembly.
-/// Holds information that isn't critical in dungeon mode.
-///
-/// There are 550 available slots.
-/// Slots 0-1 are reserved for the player and partner.
-/// Slots 2-4 are reserved for Pokemon from special episodes, they aren't available in the main story.
-/// Slots five onwards are available for recruited Pokemon.
-#[derive(Debug, Default, Clone)]
-pub struct StoredPokemon {
-    pub valid: bool,
-    pub level: u8,
-    pub id: u16,
-    pub met_at: u8,
-    pub met_floor: u8,
-    pub unknown: bool,
-    pub evolved_at_1: u8,
-    pub evolved_at_2: u8,
-    pub iq: u16,
-    pub hp: u16,
-    pub attack: u8,
-    pub sp_attack: u8,
-    pub defense: u8,
-    pub sp_defense: u8,
-    pub exp: u32,
-    pub iq_map: IqMapBits,
-    pub tactic: u8,
-    pub move_1: StoredMove,
-    pub move_2: StoredMove,
-    pub move_3: StoredMove,
-    pub move_4: StoredMove,
-    pub name: PmdString,
-}
+    /// Packs the record as other editors exchange it, the stored slot bits as they are in the save.
+    /// Bit `n` of the record is bit `n % 8` of byte `n / 8`, least significant first,
+    /// and the bits past `STORED_PKM_BIT_LEN` in the last byte are zero.
+    /// Values too large for their field are truncated like `to_bits`.
+    pub fn to_packed_bytes(&self) -> [u8; STORED_PKM_BYTE_LEN] {
+        self.to_bits().into_inner()
+    }
 
-impl StoredPokemon {
-    pub fn from_bitslice(value: &BitSlice<u8, Lsb0>) -> Self {
-        let mut iq: IqMapBits = bitarr![u8, Lsb0; 0; 69];
-        iq[0..69].copy_from_bitslice(&value[pokemon::IQ_MAP]);
+    /// Unpacks a record in the layout of `to_packed_bytes`.
+    /// The padding bits in the last byte are ignored, not every tool clears them.
+    pub fn from_packed_bytes(bytes: &[u8]) -> Result<Self, SaveError> {
+        if bytes.len() != STORED_PKM_BYTE_LEN {
+            return Err(SaveError::InvalidPackedLength {
+                expected: STORED_PKM_BYTE_LEN,
+                found: bytes.len(),
+            });
+        }
+
+        Ok(Self::from_bitslice(
+            &bytes.view_bits()[..STORED_PKM_BIT_LEN],
+        ))
+    }
+
+    /// Returns references to the four moves, in slot order.
+    pub fn moves(&self) -> [&StoredMove; 4] {
+        [&self.move_1, &self.move_2, &self.move_3, &self.move_4]
+    }
+
+    /// Returns mutable references to the four moves, in slot order.
+    pub fn moves_mut(&mut self) -> [&mut StoredMove; 4] {
+        [
+            &mut self.move_1,
+            &mut self.move_2,
+            &mut self.move_3,
+            &mut self.move_4,
+        ]
+    }
+
+    /// Replaces the move at `slot` (0 to 3).
+    pub fn set_move(&mut self, slot: usize, value: StoredMove) -> Result<(), SaveError> {
+        let m = self
+            .moves_mut()
+            .into_iter()
+            .nth(slot)
+            .ok_or(SaveError::InvalidSlot(slot))?;
+        *m = value;
+
+        Ok(())
+    }
 
-        let name_bytes = &value[pokemon::NAME];
+    /// Returns `true` if the name differs from the default name of the Pokémon's species, see `PmdString::default_name_for`.
+    /// `species_name` looks up the name of a species ID, returns `None` if it doesn't know the species.
+    pub fn has_nickname<'a>(
+        &self,
+        species_name: impl FnOnce(u16) -> Option<&'a str>,
+    ) -> Option<bool> {
+        let default = PmdString::default_name_for(self.id, species_name)?;
+        Some(self.name.to_string_until_nul() != default.to_string_until_nul())
+    }
+}
 
+/// Converts an `ActivePokemon` to a `StoredPokemon`.
+/// The stored HP is taken from the maximum HP, dungeon-only state is dropped.
+impl From<&ActivePokemon> for StoredPokemon {
+    fn from(value: &ActivePokemon) -> Self {
         Self {
-            valid: value[pokemon::VALID],
-            level: value[pokemon::LEVEL].load_le(),
-            id: value[pokemon::ID].load_le(),
-            met_at: value[pokemon::MET_AT].load_le(),
-            met_floor: value[pokemon::MET_FLOOR].load_le(),
-            unknown: value[pokemon::UNKNOWN],
-            evolved_at_1: value[pokemon::EVOLVED_AT_1].load_le(),
-            evolved_at_2: value[pokemon::EVOLVED_AT_2].load_le(),
-            iq: value[pokemon::IQ].load_le(),
-            hp: value[pokemon::HP].load_le(),
-            attack: value[pokemon::ATTACK].load_le(),
-            sp_attack: value[pokemon::SP_ATTACK].load_le(),
-            defense: value[pokemon::DEFENSE].load_le(),
-            sp_defense: value[pokemon::SP_DEFENSE].load_le(),
-            exp: value[pokemon::EXP].load_le(),
-            iq_map: iq,
-            tactic: value[pokemon::TACTIC].load_le(),
-            move_1: StoredMove::from_bitslice(&value[pokemon::MOVE_1]),
-            move_2: StoredMove::from_bitslice(&value[pokemon::MOVE_2]),
-            move_3: StoredMove::from_bitslice(&value[pokemon::MOVE_3]),
-            move_4: StoredMove::from_bitslice(&value[pokemon::MOVE_4]),
-            name: PmdString::from(name_bytes),
+            valid: value.valid,
+            level: value.level,
+            id: value.id,
+            met_at: value.met_at,
+            met_floor: value.met_floor,
+            unknown: false,
+            evolved_at_1: 0,
+            evolved_at_2: 0,
+            iq: value.iq,
+            hp: value.max_hp,
+            attack: value.attack,
+            sp_attack: value.sp_attack,
+            defense: value.defense,
+            sp_defense: value.sp_defense,
+            exp: value.exp,
+            iq_map: value.iq_map,
+            tactic: value.tactic,
+            move_1: StoredMove::from(&value.move_1),
+            move_2: StoredMove::from(&value.move_2),
+            move_3: StoredMove::from(&value.move_3),
+            move_4: StoredMove::from(&value.move_4),
+            name: value.name.clone(),
         }
     }
+}
+
+#[test]
+fn test_stored_pokemon_byte_layout() {
+    // Valid, level 100, ID 0x123, met at 0x45.
+    let mut bytes = [0u8; 46];
+    bytes[..4].copy_from_slice(&[0xC9, 0x23, 0x29, 0x02]);
+
+    let pkm = StoredPokemon::from_bitslice(bytes.view_bits());
+    assert!(pkm.valid);
+    assert_eq!(pkm.level, 100);
+    assert_eq!(pkm.id, 0x123);
+    assert_eq!(pkm.met_at, 0x45);
+    assert_eq!(pkm.to_bits().into_inner(), bytes);
+}
+
+#[test]
+fn test_stored_pokemon_bits_round_trip() {
+    let mut bytes = [0u8; 46];
+    for (i, b) in bytes.iter_mut().enumerate() {
+        *b = (i as u8).wrapping_mul(151).wrapping_add(17);
+    }
+    let bits = &bytes.view_bits::<Lsb0>()[0..STORED_PKM_BIT_LEN];
+
+    let pkm = StoredPokemon::from_bitslice(bits);
+    assert_eq!(&pkm.to_bits()[0..STORED_PKM_BIT_LEN], bits);
+}
+
+#[test]
+fn test_stored_pokemon_packed_bytes() {
+    use crate::fixture::fixture_save;
+    use crate::SkySave;
+
+    let data = fixture_save();
+    let save = SkySave::from_slice(&data).unwrap();
+    let block = &data.view_bits::<Lsb0>()[save.active_save_block as usize * 8..];
 
-    pub fn to_bits(&self) -> StoredPokemonBits {
-        let mut bits = bitarr![u8, Lsb0; 0; STORED_PKM_BIT_LEN];
-
-        bits.set(pokemon::VALID, self.valid);
-        bits[pokemon::LEVEL].store_le(self.level);
-        bits[pokemon::ID].store_le(self.id);
-        bits[pokemon::MET_AT].store_le(self.met_at);
-        bits[pokemon::MET_FLOOR].store_le(self.met_floor);
-        bits.set(34, self.unknown);
-        bits[pokemon::EVOLVED_AT_1].store_le(self.evolved_at_1);
-        bits[pokemon::EVOLVED_AT_2].store_le(self.evolved_at_2);
-        bits[pokemon::IQ].store_le(self.iq);
-        bits[pokemon::HP].store_le(self.hp);
-        bits[pokemon::ATTACK].store_le(self.attack);
-        bits[pokemon::SP_ATTACK].store_le(self.sp_attack);
-        bits[pokemon::DEFENSE].store_le(self.defense);
-        bits[pokemon::SP_DEFENSE].store_le(self.sp_defense);
-        bits[pokemon::EXP].store_le(self.exp);
-        bits[pokemon::IQ_MAP].copy_from_bitslice(&self.iq_map[0..69]);
-        bits[pokemon::TACTIC].store_le(self.tactic);
-        bits[pokemon::MOVE_1].copy_from_bitslice(&self.move_1.to_bits()[0..STORED_MOVE_BIT_LEN]);
-        bits[pokemon::MOVE_2].copy_from_bitslice(&self.move_2.to_bits()[0..STORED_MOVE_BIT_LEN]);
-        bits[pokemon::MOVE_3].copy_from_bitslice(&self.move_3.to_bits()[0..STORED_MOVE_BIT_LEN]);
-        bits[pokemon::MOVE_4].copy_from_bitslice(&self.move_4.to_bits()[0..STORED_MOVE_BIT_LEN]);
-        bits[pokemon::NAME].copy_from_bitslice(self.name.to_save_bytes().view_bits());
-
-        bits
+    for (i, p) in save.stored_pokemon.iter().enumerate() {
+        let start = STORED_PKM_BITS.start + i * STORED_PKM_BIT_LEN;
+        let mut expected = [0u8; STORED_PKM_BYTE_LEN];
+        expected.view_bits_mut::<Lsb0>()[..STORED_PKM_BIT_LEN]
+            .copy_from_bitslice(&block[start..start + STORED_PKM_BIT_LEN]);
+
+        let packed = p.to_packed_bytes();
+        assert_eq!(packed, expected, "slot {}", i);
+        let unpacked = StoredPokemon::from_packed_bytes(&packed).unwrap();
+        assert_eq!(unpacked.to_packed_bytes(), packed, "slot {}", i);
     }
+
+    // The six padding bits are ignored when unpacking and cleared when packing.
+    let packed = save.stored_pokemon[0].to_packed_bytes();
+    let mut padded = packed;
+    padded[STORED_PKM_BYTE_LEN - 1] |= 0xFC;
+    let pkm = StoredPokemon::from_packed_bytes(&padded).unwrap();
+    assert_eq!(pkm.to_packed_bytes(), packed);
+
+    assert!(matches!(
+        StoredPokemon::from_packed_bytes(&packed[1..]),
+        Err(SaveError::InvalidPackedLength {
+            expected: STORED_PKM_BYTE_LEN,
+            found: 45
+        })
+    ));
+}
+
+#[test]
+fn test_stored_pokemon_has_nickname() {
+    let names = |id| (id == 4).then_some("Charmander");
+    let mut pkm = StoredPokemon {
+        id: 4,
+        name: PmdString::try_from("Charmander").unwrap(),
+        ..Default::default()
+    };
+    assert_eq!(pkm.has_nickname(names), Some(false));
+
+    pkm.name = PmdString::try_from("Ember").unwrap();
+    assert_eq!(pkm.has_nickname(names), Some(true));
+
+    pkm.id = 5;
+    assert_eq!(pkm.has_nickname(names), None);
 }
@@ -0,0 +1,527 @@
+//! Handles the shareable team format (`.skyteam`).
+//!
+//! A team file holds a list of exported Pokémon and a small header, so a team can be shared without the whole save.
+//! Every number is little-endian. The layout is:
+//! - `SKYTEAM\0` magic (8 bytes).
+//! - Format version (`u16`).
+//! - Source region (`u8`).
+//! - Length of the crate version string (`u8`), followed by the UTF-8 bytes.
+//! - Number of members (`u16`).
+//! - For each member: the kind (`u8`, 0 for stored and 1 for active), the record length (`u16`),
+//!   and the packed bits of the Pokémon, zero-padded to whole bytes.
+//!
+//! A team code is the same bytes as standard, padded base64, for pasting in chat.
+
+use crate::offsets::active::ACTIVE_PKM_BIT_LEN;
+use crate::offsets::stored::STORED_PKM_BIT_LEN;
+use crate::save::write_atomic;
+use crate::{ActivePokemon, SaveError, SkySave, StoredPokemon, FIRST_RECRUIT_SLOT};
+use bitvec::order::Lsb0;
+use bitvec::view::BitView;
+use std::fs;
+use std::path::Path;
+
+/// The magic bytes at the start of every team file.
+pub const TEAM_MAGIC: &[u8; 8] = b"SKYTEAM\0";
+/// The newest team file version this crate can read and write.
+pub const TEAM_VERSION: u16 = 1;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+const STORED_RECORD_LEN: usize = STORED_PKM_BIT_LEN.div_ceil(8);
+const ACTIVE_RECORD_LEN: usize = ACTIVE_PKM_BIT_LEN.div_ceil(8);
+
+/// The game region a team file was exported from.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[repr(u8)]
+pub enum Region {
+    /// North American and European releases, which share the same character table.
+    Western = 0,
+    Japanese = 1,
+}
+
+impl TryFrom<u8> for Region {
+    type Error = SaveError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Region::Western),
+            1 => Ok(Region::Japanese),
+            _ => Err(SaveError::InvalidTeamFile("unknown region")),
+        }
+    }
+}
+
+/// A single Pokémon in a team file.
+#[derive(Debug, Clone)]
+pub enum TeamMember {
+    Stored(StoredPokemon),
+    Active(ActivePokemon),
+}
+
+/// Where `SkySave::import_team` places the imported Pokémon.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TeamPlacement {
+    /// Active members go to the free active slots, with a copy in the box like recruited team members.
+    /// Stored members go to the box.
+    Active,
+    /// Every member goes to the box, active members are converted to stored ones.
+    Storage,
+}
+
+/// An in-memory team file.
+#[derive(Debug, Clone)]
+pub struct TeamFile {
+    pub version: u16,
+    pub region: Region,
+    pub crate_version: String,
+    pub members: Vec<TeamMember>,
+}
+
+impl TeamFile {
+    /// Creates an empty team file stamped with the current crate version.
+    pub fn new() -> Self {
+        Self {
+            version: TEAM_VERSION,
+            region: Region::Western,
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            members: Vec::new(),
+        }
+    }
+
+    /// Serializes the team file to bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        let crate_version = &self.crate_version.as_bytes()[..self.crate_version.len().min(255)];
+
+        out.extend_from_slice(TEAM_MAGIC);
+        out.extend_from_slice(&self.version.to_le_bytes());
+        out.push(self.region as u8);
+        out.push(crate_version.len() as u8);
+        out.extend_from_slice(crate_version);
+        out.extend_from_slice(&(self.members.len() as u16).to_le_bytes());
+
+        for m in &self.members {
+            match m {
+                TeamMember::Stored(p) => {
+                    out.push(0);
+                    out.extend_from_slice(&(STORED_RECORD_LEN as u16).to_le_bytes());
+                    out.extend_from_slice(&p.to_bits().into_inner());
+                }
+                TeamMember::Active(p) => {
+                    out.push(1);
+                    out.extend_from_slice(&(ACTIVE_RECORD_LEN as u16).to_le_bytes());
+                    out.extend_from_slice(&p.to_bits().into_inner());
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Parses a team file from bytes.
+    /// Files written by a newer version of the format are rejected.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, SaveError> {
+        let mut reader = Reader(data);
+
+        if reader.take(TEAM_MAGIC.len())? != TEAM_MAGIC {
+            return Err(SaveError::InvalidTeamFile("missing magic bytes"));
+        }
+
+        let version = u16::from_le_bytes(reader.array()?);
+        if version > TEAM_VERSION {
+            return Err(SaveError::UnsupportedTeamVersion {
+                found: version,
+                supported: TEAM_VERSION,
+            });
+        }
+
+        let region = Region::try_from(reader.array::<1>()?[0])?;
+        let version_len = reader.array::<1>()?[0] as usize;
+        let crate_version = std::str::from_utf8(reader.take(version_len)?)
+            .map_err(|_| SaveError::InvalidTeamFile("crate version is not UTF-8"))?
+            .to_string();
+
+        let count = u16::from_le_bytes(reader.array()?) as usize;
+        let mut members = Vec::with_capacity(count);
+        for _ in 0..count {
+            let kind = reader.array::<1>()?[0];
+            let len = u16::from_le_bytes(reader.array()?) as usize;
+            let record = reader.take(len)?;
+
+            let member = match (kind, len) {
+                (0, STORED_RECORD_LEN) => {
+                    TeamMember::Stored(StoredPokemon::from_bitslice(record.view_bits::<Lsb0>()))
+                }
+                (1, ACTIVE_RECORD_LEN) => {
+                    TeamMember::Active(ActivePokemon::from_bitslice(record.view_bits::<Lsb0>()))
+                }
+                (0 | 1, _) => return Err(SaveError::InvalidTeamFile("invalid record length")),
+                _ => return Err(SaveError::InvalidTeamFile("unknown member kind")),
+            };
+            members.push(member);
+        }
+
+        if !reader.0.is_empty() {
+            return Err(SaveError::InvalidTeamFile("trailing data"));
+        }
+
+        Ok(Self {
+            version,
+            region,
+            crate_version,
+            members,
+        })
+    }
+
+    /// Serializes the team file to a single-line team code.
+    pub fn to_code(&self) -> String {
+        base64_encode(&self.to_bytes())
+    }
+
+    /// Parses a team code, ignoring surrounding and embedded whitespace.
+    pub fn from_code(code: &str) -> Result<Self, SaveError> {
+        let code: String = code.split_whitespace().collect();
+        Self::from_bytes(&base64_decode(&code)?)
+    }
+}
+
+fn base64_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let mut group = [0u8; 3];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let n = u32::from_be_bytes([0, group[0], group[1], group[2]]);
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+
+    out
+}
+
+fn base64_decode(code: &str) -> Result<Vec<u8>, SaveError> {
+    let invalid = || SaveError::InvalidTeamFile("invalid team code");
+    let bytes = code.as_bytes();
+    if !bytes.len().is_multiple_of(4) {
+        return Err(invalid());
+    }
+
+    let mut out = Vec::with_capacity(bytes.len() / 4 * 3);
+    for (i, chunk) in bytes.chunks(4).enumerate() {
+        let last = i == bytes.len() / 4 - 1;
+        let padding = chunk.iter().rev().take_while(|&&c| c == b'=').count();
+        if padding > 2 || (padding > 0 && !last) {
+            return Err(invalid());
+        }
+
+        let mut n = 0u32;
+        for &c in &chunk[..4 - padding] {
+            let v = BASE64_ALPHABET
+                .iter()
+                .position(|&a| a == c)
+                .ok_or_else(invalid)?;
+            n = n << 6 | v as u32;
+        }
+        n <<= 6 * padding;
+
+        out.extend_from_slice(&n.to_be_bytes()[1..4 - padding]);
+    }
+
+    Ok(out)
+}
+
+impl Default for TeamFile {
+    fn default() -> Self {
+        TeamFile::new()
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], SaveError> {
+        if self.0.len() < len {
+            return Err(SaveError::InvalidTeamFile("unexpected end of file"));
+        }
+
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], SaveError> {
+        Ok(self.take(N)?.try_into().unwrap()) // Safe, exactly N bytes.
+    }
+}
+
+impl SkySave {
+    /// The region of the save. Only Western saves are supported, their offsets and character table are used throughout.
+    pub fn region(&self) -> Region {
+        Region::Western
+    }
+
+    /// Builds a team file from every valid active Pokémon and the stored Pokémon at `indices`.
+    /// Indices pointing at empty slots are skipped.
+    pub fn team_file(&self, indices: &[usize]) -> Result<TeamFile, SaveError> {
+        let mut team = TeamFile {
+            region: self.region(),
+            ..TeamFile::new()
+        };
+
+        team.members.extend(
+            self.active_pokemon
+                .iter()
+                .filter(|p| p.valid)
+                .cloned()
+                .map(TeamMember::Active),
+        );
+
+        for &idx in indices {
+            let pkm = self
+                .stored_pokemon
+                .get(idx)
+                .ok_or(SaveError::InvalidSlot(idx))?;
+            if pkm.valid {
+                team.members.push(TeamMember::Stored(pkm.clone()));
+            }
+        }
+
+        Ok(team)
+    }
+
+    /// Exports the active team and the stored Pokémon at `indices` to a team file.
+    pub fn export_team<P: AsRef<Path>>(&self, path: P, indices: &[usize]) -> Result<(), SaveError> {
+        let team = self.team_file(indices)?;
+        write_atomic(path.as_ref(), &team.to_bytes())
+    }
+
+    /// Places the members of a team file into the save.
+    /// Nothing is changed if the file is from another region or there aren't enough free slots for every member.
+    /// Empty members are skipped.
+    /// Stored members never overwrite the reserved slots at the start of the box.
+    /// Active members placed in the team get the lowest roster numbers not in use, see `SkySave::validate_roster`.
+    /// Returns the number of imported Pokémon.
+    pub fn import_team_file(
+        &mut self,
+        team: &TeamFile,
+        placement: TeamPlacement,
+    ) -> Result<usize, SaveError> {
+        if team.region != self.region() {
+            return Err(SaveError::TeamRegionMismatch {
+                found: team.region,
+                expected: self.region(),
+            });
+        }
+
+        let members: Vec<&TeamMember> = team
+            .members
+            .iter()
+            .filter(|m| match m {
+                TeamMember::Stored(p) => p.valid,
+                TeamMember::Active(p) => p.valid,
+            })
+            .collect();
+        let free_active: Vec<usize> = (0..self.active_pokemon.len())
+            .filter(|&i| !self.active_pokemon[i].valid)
+            .collect();
+        let free_stored: Vec<usize> = (FIRST_RECRUIT_SLOT..self.stored_pokemon.len())
+            .filter(|&i| !self.stored_pokemon[i].valid)
+            .collect();
+
+        let active_count = members
+            .iter()
+            .filter(|m| matches!(m, TeamMember::Active(_)))
+            .count();
+        let (needed_active, needed_stored) = match placement {
+            TeamPlacement::Active => (active_count, members.len()),
+            TeamPlacement::Storage => (0, members.len()),
+        };
+
+        if needed_active > free_active.len() {
+            return Err(SaveError::NotEnoughSlots {
+                needed: needed_active,
+                free: free_active.len(),
+            });
+        }
+        if needed_stored > free_stored.len() {
+            return Err(SaveError::NotEnoughSlots {
+                needed: needed_stored,
+                free: free_stored.len(),
+            });
+        }
+
+        let mut free_active = free_active.into_iter();
+        let mut free_stored = free_stored.into_iter();
+        let mut roster_number = 0;
+        for &m in &members {
+            match (m, placement) {
+                (TeamMember::Active(p), TeamPlacement::Active) => {
+                    roster_number = (roster_number + 1..)
+                        .find(|&n| {
+                            self.active_pokemon
+                                .iter()
+                                .all(|a| !a.valid || a.roaster_number != n)
+                        })
+                        .unwrap(); // Safe, there are fewer members than roster numbers.
+                    self.stored_pokemon[free_stored.next().unwrap()] = StoredPokemon::from(p);
+                    self.active_pokemon[free_active.next().unwrap()] = ActivePokemon {
+                        roaster_number: roster_number,
+                        ..p.clone()
+                    };
+                }
+                (TeamMember::Active(p), TeamPlacement::Storage) => {
+                    self.stored_pokemon[free_stored.next().unwrap()] = StoredPokemon::from(p);
+                }
+                (TeamMember::Stored(p), _) => {
+                    self.stored_pokemon[free_stored.next().unwrap()] = p.clone();
+                }
+            }
+        }
+
+        Ok(members.len())
+    }
+
+    /// Imports a team file from disk, see `import_team_file`.
+    pub fn import_team<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        placement: TeamPlacement,
+    ) -> Result<usize, SaveError> {
+        let data = fs::read(path).map_err(SaveError::Io)?;
+        let team = TeamFile::from_bytes(&data)?;
+        self.import_team_file(&team, placement)
+    }
+}
+
+#[test]
+fn test_team_file_round_trip() {
+    let mut team = TeamFile::new();
+    let stored = StoredPokemon {
+        valid: true,
+        level: 42,
+        id: 387,
+        ..Default::default()
+    };
+    let active = ActivePokemon {
+        valid: true,
+        level: 17,
+        max_hp: 90,
+        ..Default::default()
+    };
+    team.members.push(TeamMember::Stored(stored));
+    team.members.push(TeamMember::Active(active));
+
+    let parsed = TeamFile::from_bytes(&team.to_bytes()).unwrap();
+    assert_eq!(parsed.version, TEAM_VERSION);
+    assert_eq!(parsed.crate_version, env!("CARGO_PKG_VERSION"));
+    assert!(matches!(&parsed.members[0], TeamMember::Stored(p) if p.level == 42 && p.id == 387));
+    assert!(matches!(&parsed.members[1], TeamMember::Active(p) if p.level == 17 && p.max_hp == 90));
+}
+
+#[test]
+fn test_team_file_future_version() {
+    let mut bytes = TeamFile::new().to_bytes();
+    bytes[8..10].copy_from_slice(&(TEAM_VERSION + 1).to_le_bytes());
+
+    assert!(matches!(
+        TeamFile::from_bytes(&bytes),
+        Err(SaveError::UnsupportedTeamVersion { .. })
+    ));
+}
+
+#[test]
+fn test_team_code_round_trip() {
+    let mut team = TeamFile::new();
+    team.members.push(TeamMember::Active(ActivePokemon {
+        valid: true,
+        level: 33,
+        ..Default::default()
+    }));
+
+    let code = team.to_code();
+    assert!(code.is_ascii() && !code.contains(char::is_whitespace));
+    let parsed = TeamFile::from_code(&format!(" {}\n", code)).unwrap();
+    assert!(matches!(&parsed.members[0], TeamMember::Active(p) if p.level == 33));
+
+    assert_eq!(base64_encode(b"Man"), "TWFu");
+    assert_eq!(base64_encode(b"Ma"), "TWE=");
+    assert_eq!(base64_decode("TWE=").unwrap(), b"Ma");
+    assert!(base64_decode("TW=u").is_err());
+    assert!(base64_decode("TWE").is_err());
+    assert!(TeamFile::from_code("not a team code").is_err());
+}
+
+#[test]
+fn test_import_active_placement_links_roster() {
+    let mut team = TeamFile::new();
+    for id in [1, 4] {
+        team.members.push(TeamMember::Active(ActivePokemon {
+            valid: true,
+            level: 5,
+            id,
+            ..Default::default()
+        }));
+    }
+
+    let mut save = SkySave::from_slice(vec![0; 0x20000]).unwrap();
+    assert_eq!(
+        save.import_team_file(&team, TeamPlacement::Active).unwrap(),
+        2
+    );
+    assert_eq!(save.team_size(), 2);
+    assert_eq!(save.active_pokemon[1].roaster_number, 2);
+    assert_eq!(save.linked_stored_index(1), Some(FIRST_RECRUIT_SLOT + 1));
+    assert!(save.validate_roster().is_empty());
+}
+
+#[test]
+fn test_import_skips_empty_and_fills_roster_gaps() {
+    let mut save = SkySave::from_slice(vec![0; 0x20000]).unwrap();
+    save.active_pokemon[0] = ActivePokemon {
+        valid: true,
+        roaster_number: 2,
+        ..Default::default()
+    };
+    save.stored_pokemon[FIRST_RECRUIT_SLOT].valid = true;
+
+    // The empty stored slot isn't exported.
+    let mut team = save
+        .team_file(&[FIRST_RECRUIT_SLOT, FIRST_RECRUIT_SLOT + 1])
+        .unwrap();
+    assert_eq!(team.members.len(), 2);
+    team.members
+        .push(TeamMember::Active(ActivePokemon::default()));
+    team.members.push(TeamMember::Active(ActivePokemon {
+        valid: true,
+        id: 7,
+        ..Default::default()
+    }));
+
+    // The empty member is skipped and the new members take the free roster numbers 1 and 3.
+    assert_eq!(
+        save.import_team_file(&team, TeamPlacement::Active).unwrap(),
+        3
+    );
+    let mut numbers: Vec<u16> = save
+        .active_pokemon
+        .iter()
+        .filter(|p| p.valid)
+        .map(|p| p.roaster_number)
+        .collect();
+    numbers.sort();
+    assert_eq!(numbers, [1, 2, 3]);
+
+    team.region = Region::Japanese;
+    assert!(matches!(
+        save.import_team_file(&team, TeamPlacement::Storage),
+        Err(SaveError::TeamRegionMismatch { .. })
+    ));
+}